@@ -0,0 +1,184 @@
+//! 双签保护：持久化最后一次签名的 (height, round, step)，拒绝回退或同位置不同内容的签名。
+//!
+//! 签名前先落盘（临时文件 + fsync + rename），进程崩溃重启后仍能拒绝重复投票。
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 同一轮次内的签名步骤，按发生顺序排序。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Proposal,
+    Prevote,
+    Precommit,
+}
+
+/// 签名位置：按 (height, round, step) 字典序单调递增。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SignPosition {
+    pub height: u64,
+    pub round: u32,
+    pub step: Step,
+}
+
+impl std::fmt::Display for SignPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}/{:?}", self.height, self.round, self.step)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SignError {
+    #[error("refusing to sign {requested}: already signed {last}")]
+    Regression {
+        last: SignPosition,
+        requested: SignPosition,
+    },
+    #[error("refusing to sign conflicting message at {0}")]
+    Conflict(SignPosition),
+    #[error("sign state: {0}")]
+    State(#[from] std::io::Error),
+    #[error(transparent)]
+    Crypto(#[from] ark_crypto::CryptoError),
+    #[error("remote signer: {0}")]
+    Remote(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SignState {
+    position: SignPosition,
+    #[serde(with = "hex_digest")]
+    digest: [u8; 32],
+}
+
+pub struct SignGuard {
+    path: Option<PathBuf>,
+    last: Option<SignState>,
+}
+
+impl SignGuard {
+    /// 打开状态文件；不存在时视为从未签名。
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let last = match fs::read(&path) {
+            Ok(raw) => Some(
+                serde_json::from_slice(&raw)
+                    .map_err(|e| anyhow::anyhow!("corrupted sign state {}: {e}", path.display()))?,
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            last,
+        })
+    }
+
+    /// 不落盘（仅测试与远程签名器的本地镜像使用）。
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            last: None,
+        }
+    }
+
+    pub fn last(&self) -> Option<SignPosition> {
+        self.last.as_ref().map(|s| s.position)
+    }
+
+    /// 允许签名时先持久化新位置；同位置同摘要视为重放，直接放行。
+    pub fn record(&mut self, position: SignPosition, digest: &[u8; 32]) -> Result<(), SignError> {
+        if let Some(last) = &self.last {
+            if position < last.position {
+                return Err(SignError::Regression {
+                    last: last.position,
+                    requested: position,
+                });
+            }
+            if position == last.position {
+                return if &last.digest == digest {
+                    Ok(())
+                } else {
+                    Err(SignError::Conflict(position))
+                };
+            }
+        }
+        let state = SignState {
+            position,
+            digest: *digest,
+        };
+        if let Some(path) = &self.path {
+            persist(path, &state)?;
+        }
+        self.last = Some(state);
+        Ok(())
+    }
+}
+
+fn persist(path: &Path, state: &SignState) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&serde_json::to_vec(state).expect("sign state serializes"))?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+mod hex_digest {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(d: &[u8; 32], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&hex::encode(d))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<[u8; 32], D::Error> {
+        let s = String::deserialize(d)?;
+        let mut out = [0u8; 32];
+        hex::decode_to_slice(&s, &mut out).map_err(serde::de::Error::custom)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(height: u64, round: u32, step: Step) -> SignPosition {
+        SignPosition {
+            height,
+            round,
+            step,
+        }
+    }
+
+    #[test]
+    fn refuses_regression_and_conflict_across_restarts() {
+        let dir = std::env::temp_dir().join(format!("ark-guard-{}", std::process::id()));
+        let path = dir.join("sign_state.json");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut guard = SignGuard::open(&path).unwrap();
+        guard.record(pos(5, 0, Step::Prevote), &[1; 32]).unwrap();
+        guard.record(pos(5, 0, Step::Precommit), &[2; 32]).unwrap();
+        drop(guard);
+
+        let mut guard = SignGuard::open(&path).unwrap();
+        assert_eq!(guard.last(), Some(pos(5, 0, Step::Precommit)));
+        guard.record(pos(5, 0, Step::Precommit), &[2; 32]).unwrap();
+        assert!(matches!(
+            guard.record(pos(5, 0, Step::Precommit), &[3; 32]),
+            Err(SignError::Conflict(_))
+        ));
+        assert!(matches!(
+            guard.record(pos(5, 0, Step::Prevote), &[1; 32]),
+            Err(SignError::Regression { .. })
+        ));
+        guard.record(pos(5, 1, Step::Proposal), &[4; 32]).unwrap();
+        guard.record(pos(6, 0, Step::Proposal), &[5; 32]).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
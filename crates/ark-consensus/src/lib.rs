@@ -1,2 +1,11 @@
 //! 共识占位：后续实现 HotStuff/Streamlet PoS
+//!
+//! 已就绪：验证者签名器（本地 / 远程）与双签保护。
+pub mod guard;
+pub mod metrics;
+pub mod signer;
+
+pub use guard::{SignError, SignGuard, SignPosition, Step};
+pub use signer::{LocalSigner, RemoteSigner, ValidatorSigner};
+
 pub struct Consensus;
@@ -0,0 +1,31 @@
+//! 共识指标：由出块流程在进入新的 view（高度）/ round 时调用 `enter` 更新。
+use ark_metrics::IntGauge;
+use std::sync::LazyLock;
+
+pub static VIEW: LazyLock<IntGauge> =
+    LazyLock::new(|| ark_metrics::int_gauge("consensus_view", "Current consensus view"));
+pub static ROUND: LazyLock<IntGauge> =
+    LazyLock::new(|| ark_metrics::int_gauge("consensus_round", "Current round within the view"));
+
+/// 进入 view（即待出块高度）的第 round 轮。
+pub fn enter(view: u64, round: u32) {
+    VIEW.set(view as i64);
+    ROUND.set(round as i64);
+}
+
+/// 在全局注册表中注册本 crate 的指标。
+pub fn register() {
+    LazyLock::force(&VIEW);
+    LazyLock::force(&ROUND);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enter_sets_view_and_round() {
+        enter(7, 2);
+        assert_eq!((VIEW.get(), ROUND.get()), (7, 2));
+    }
+}
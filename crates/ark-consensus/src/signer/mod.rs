@@ -0,0 +1,75 @@
+//! 共识签名器：本地 keystore 私钥，或经本地 Unix socket 访问的远程签名器。
+//!
+//! 远程签名器依赖 Unix domain socket，仅在 Unix 平台可用；其他平台上
+//! [`RemoteSigner::connect`] 直接返回错误。
+use crate::guard::{SignError, SignGuard, SignPosition};
+use ark_crypto::{SecretKey, Signer};
+
+#[cfg(unix)]
+mod remote;
+
+#[cfg(unix)]
+pub use remote::{bind, serve, RemoteSigner};
+
+/// 带双签保护的共识消息签名。
+pub trait ValidatorSigner: Send {
+    fn public_key(&self) -> [u8; 33];
+    fn sign_vote(
+        &mut self,
+        position: SignPosition,
+        digest: &[u8; 32],
+    ) -> Result<[u8; 64], SignError>;
+}
+
+pub struct LocalSigner {
+    key: SecretKey,
+    guard: SignGuard,
+}
+
+impl LocalSigner {
+    pub fn new(key: SecretKey, guard: SignGuard) -> Self {
+        Self { key, guard }
+    }
+}
+
+impl ValidatorSigner for LocalSigner {
+    fn public_key(&self) -> [u8; 33] {
+        self.key.public_key()
+    }
+
+    fn sign_vote(
+        &mut self,
+        position: SignPosition,
+        digest: &[u8; 32],
+    ) -> Result<[u8; 64], SignError> {
+        self.guard.record(position, digest)?;
+        Ok(self.key.sign(digest)?)
+    }
+}
+
+/// 非 Unix 平台的占位：没有 Unix domain socket，连接总是失败。
+#[cfg(not(unix))]
+pub enum RemoteSigner {}
+
+#[cfg(not(unix))]
+impl RemoteSigner {
+    pub fn connect(
+        _path: impl AsRef<std::path::Path>,
+        _guard: SignGuard,
+    ) -> Result<Self, SignError> {
+        Err(SignError::Remote(
+            "remote signer requires Unix domain sockets, unsupported on this platform".into(),
+        ))
+    }
+}
+
+#[cfg(not(unix))]
+impl ValidatorSigner for RemoteSigner {
+    fn public_key(&self) -> [u8; 33] {
+        match *self {}
+    }
+
+    fn sign_vote(&mut self, _: SignPosition, _: &[u8; 32]) -> Result<[u8; 64], SignError> {
+        match *self {}
+    }
+}
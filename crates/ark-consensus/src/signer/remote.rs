@@ -0,0 +1,221 @@
+//! 经本地 Unix socket 访问的远程签名器。
+//!
+//! 协议为逐行 JSON（一行一个请求 / 响应）：
+//! - `{"method":"pubkey"}` → `{"pubkey":"<hex>"}`
+//! - `{"method":"sign","position":{"height":..,"round":..,"step":"prevote"},"digest":"<hex>"}`
+//!   → `{"signature":"<hex>"}`
+//! - 失败统一返回 `{"error":"..."}`
+//!
+//! 签名器进程自身维护双签保护；节点侧同样保留一份，任一侧拒绝即不签名。
+use super::ValidatorSigner;
+use crate::guard::{SignError, SignGuard, SignPosition};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 远程签名请求的读写超时
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Request {
+    Pubkey,
+    Sign {
+        position: SignPosition,
+        digest: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Response {
+    Pubkey(String),
+    Signature(String),
+    Error(String),
+}
+
+/// 远程签名器客户端；连接断开后在下一次请求时重连。
+pub struct RemoteSigner {
+    path: PathBuf,
+    conn: Option<BufReader<UnixStream>>,
+    pubkey: [u8; 33],
+    guard: SignGuard,
+}
+
+impl RemoteSigner {
+    /// 连接签名器并取回公钥。
+    pub fn connect(path: impl AsRef<Path>, guard: SignGuard) -> Result<Self, SignError> {
+        let mut signer = Self {
+            path: path.as_ref().to_path_buf(),
+            conn: None,
+            pubkey: [0; 33],
+            guard,
+        };
+        match signer.call(&Request::Pubkey)? {
+            Response::Pubkey(hex) => signer.pubkey = decode_hex(&hex)?,
+            other => return Err(unexpected(other)),
+        }
+        Ok(signer)
+    }
+
+    fn call(&mut self, req: &Request) -> Result<Response, SignError> {
+        let result = self.roundtrip(req);
+        if result.is_err() {
+            self.conn = None;
+        }
+        match result? {
+            Response::Error(msg) => Err(SignError::Remote(msg)),
+            resp => Ok(resp),
+        }
+    }
+
+    fn roundtrip(&mut self, req: &Request) -> Result<Response, SignError> {
+        if self.conn.is_none() {
+            let stream = UnixStream::connect(&self.path)?;
+            stream.set_read_timeout(Some(REMOTE_TIMEOUT))?;
+            stream.set_write_timeout(Some(REMOTE_TIMEOUT))?;
+            self.conn = Some(BufReader::new(stream));
+        }
+        let conn = self.conn.as_mut().expect("connected above");
+        let mut line = serde_json::to_vec(req).expect("request serializes");
+        line.push(b'\n');
+        conn.get_mut().write_all(&line)?;
+        let mut buf = String::new();
+        if conn.read_line(&mut buf)? == 0 {
+            return Err(SignError::Remote("connection closed".into()));
+        }
+        serde_json::from_str(&buf).map_err(|e| SignError::Remote(format!("bad response: {e}")))
+    }
+}
+
+impl ValidatorSigner for RemoteSigner {
+    fn public_key(&self) -> [u8; 33] {
+        self.pubkey
+    }
+
+    fn sign_vote(
+        &mut self,
+        position: SignPosition,
+        digest: &[u8; 32],
+    ) -> Result<[u8; 64], SignError> {
+        self.guard.record(position, digest)?;
+        let req = Request::Sign {
+            position,
+            digest: hex::encode(digest),
+        };
+        match self.call(&req)? {
+            Response::Signature(hex) => decode_hex(&hex),
+            other => Err(unexpected(other)),
+        }
+    }
+}
+
+/// 绑定签名器 socket：清理残留的 socket 文件，并限制为仅属主可访问。
+pub fn bind(path: impl AsRef<Path>) -> std::io::Result<UnixListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    let path = path.as_ref();
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ))
+        }
+        Err(_) => {}
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// 每个连接一个线程；签名经互斥锁串行，双签保护对所有连接生效。直到 listener 出错才返回。
+pub fn serve(
+    listener: UnixListener,
+    signer: impl ValidatorSigner + 'static,
+) -> std::io::Result<()> {
+    let signer: Arc<Mutex<dyn ValidatorSigner>> = Arc::new(Mutex::new(signer));
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let signer = signer.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = handle(stream, &signer) {
+                tracing::debug!(error = %e, "signer connection closed");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle(stream: UnixStream, signer: &Mutex<dyn ValidatorSigner>) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let mut signer = signer.lock().unwrap_or_else(|e| e.into_inner());
+        let resp = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Pubkey) => Response::Pubkey(hex::encode(signer.public_key())),
+            Ok(Request::Sign { position, digest }) => {
+                match decode_hex::<32>(&digest).and_then(|d| signer.sign_vote(position, &d)) {
+                    Ok(sig) => Response::Signature(hex::encode(sig)),
+                    Err(e) => {
+                        tracing::warn!(%position, error = %e, "remote sign refused");
+                        Response::Error(e.to_string())
+                    }
+                }
+            }
+            Err(e) => Response::Error(format!("bad request: {e}")),
+        };
+        drop(signer);
+        let mut out = serde_json::to_vec(&resp).expect("response serializes");
+        out.push(b'\n');
+        writer.write_all(&out)?;
+    }
+    Ok(())
+}
+
+fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N], SignError> {
+    let mut out = [0u8; N];
+    hex::decode_to_slice(s, &mut out).map_err(|e| SignError::Remote(format!("bad hex: {e}")))?;
+    Ok(out)
+}
+
+fn unexpected(resp: Response) -> SignError {
+    SignError::Remote(format!("unexpected response {resp:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guard::Step;
+    use crate::signer::LocalSigner;
+    use ark_crypto::{SecretKey, Signer};
+
+    #[test]
+    fn remote_signer_signs_and_enforces_guard() {
+        let path = std::env::temp_dir().join(format!("ark-signer-{}.sock", std::process::id()));
+        let listener = bind(&path).unwrap();
+        let key = SecretKey::from_bytes(&[9u8; 32]).unwrap();
+        let expected = key.public_key();
+        std::thread::spawn(move || serve(listener, LocalSigner::new(key, SignGuard::in_memory())));
+
+        // 两个客户端各自的本地保护互不知情，签名器侧仍拒绝冲突签名
+        let mut a = RemoteSigner::connect(&path, SignGuard::in_memory()).unwrap();
+        let mut b = RemoteSigner::connect(&path, SignGuard::in_memory()).unwrap();
+        assert_eq!(a.public_key(), expected);
+        let pos = SignPosition {
+            height: 3,
+            round: 0,
+            step: Step::Prevote,
+        };
+        let sig = a.sign_vote(pos, &[1; 32]).unwrap();
+        assert!(ark_crypto::verify(&expected, &[1; 32], &sig));
+        assert!(matches!(
+            b.sign_vote(pos, &[2; 32]),
+            Err(SignError::Remote(msg)) if msg.contains("conflicting")
+        ));
+        let _ = std::fs::remove_file(&path);
+    }
+}
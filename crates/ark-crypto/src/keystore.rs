@@ -0,0 +1,228 @@
+//! 验证者密钥文件：与钱包 keystore 相同的加密方案。
+//!
+//! - KDF：scrypt（默认 N=2^15, r=8, p=1）或 PBKDF2-HMAC-SHA256
+//! - 对称加密：AES-256-GCM，16 字节随机盐、12 字节随机 nonce
+//! - `crypto` 字段与钱包 keystore 的 JSON 格式一致，可互相解密
+use crate::secp256k1::SecretKey;
+use crate::Signer;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+pub const VERSION: u32 = 1;
+pub const CIPHER: &str = "AES-256-GCM";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeystoreError {
+    #[error("unsupported keystore version {0}")]
+    Version(u32),
+    #[error("unsupported cipher: {0}")]
+    Cipher(String),
+    #[error("invalid kdf params: {0}")]
+    Kdf(String),
+    #[error("malformed field {0}")]
+    Decode(&'static str),
+    #[error("wrong password or corrupted keystore")]
+    Decrypt,
+    #[error("public key does not match the encrypted key")]
+    PubkeyMismatch,
+}
+
+/// 密钥派生参数。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    Scrypt { n: u32, r: u32, p: u32 },
+    Pbkdf2 { iterations: u32 },
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Kdf::Scrypt {
+            n: 1 << 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// 加密后的验证者私钥（JSON 落盘）。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidatorKeystore {
+    pub version: u32,
+    /// 33 字节压缩公钥（hex），无需口令即可识别验证者
+    pub pubkey_hex: String,
+    pub crypto: Crypto,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Crypto {
+    pub cipher: String,
+    pub kdf: String,
+    pub kdfparams: KdfParams,
+    /// base64(12B)
+    pub nonce: String,
+    /// base64(密文 + GCM 标签)
+    pub ciphertext: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// base64
+    pub salt: String,
+    pub dklen: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p: Option<u32>,
+}
+
+impl ValidatorKeystore {
+    pub fn encrypt(key: &SecretKey, password: &str, kdf: Kdf) -> Result<Self, KeystoreError> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let (name, iterations, n, r, p) = match kdf {
+            Kdf::Scrypt { n, r, p } => ("scrypt", None, Some(n), Some(r), Some(p)),
+            Kdf::Pbkdf2 { iterations } => ("pbkdf2", Some(iterations), None, None, None),
+        };
+        let kdfparams = KdfParams {
+            salt: B64.encode(salt),
+            dklen: 32,
+            iterations,
+            n,
+            r,
+            p,
+        };
+        let mut dk = derive_key(password, name, &kdfparams)?;
+        let cipher = Aes256Gcm::new_from_slice(&dk).expect("32-byte key");
+        dk.zeroize();
+        let mut raw = key.to_bytes();
+        let ct = cipher.encrypt(Nonce::from_slice(&nonce), raw.as_slice());
+        raw.zeroize();
+        let ct = ct.map_err(|_| KeystoreError::Decrypt)?;
+        Ok(Self {
+            version: VERSION,
+            pubkey_hex: hex::encode(key.public_key()),
+            crypto: Crypto {
+                cipher: CIPHER.into(),
+                kdf: name.into(),
+                kdfparams,
+                nonce: B64.encode(nonce),
+                ciphertext: B64.encode(ct),
+            },
+        })
+    }
+
+    /// 解密私钥，并核对与明文公钥一致。
+    pub fn decrypt(&self, password: &str) -> Result<SecretKey, KeystoreError> {
+        if self.version != VERSION {
+            return Err(KeystoreError::Version(self.version));
+        }
+        let c = &self.crypto;
+        if c.cipher != CIPHER {
+            return Err(KeystoreError::Cipher(c.cipher.clone()));
+        }
+        let nonce = B64
+            .decode(&c.nonce)
+            .ok()
+            .filter(|n| n.len() == 12)
+            .ok_or(KeystoreError::Decode("nonce"))?;
+        let ct = B64
+            .decode(&c.ciphertext)
+            .map_err(|_| KeystoreError::Decode("ciphertext"))?;
+        let mut dk = derive_key(password, &c.kdf, &c.kdfparams)?;
+        let cipher = Aes256Gcm::new_from_slice(&dk).expect("32-byte key");
+        dk.zeroize();
+        let mut pt = cipher
+            .decrypt(Nonce::from_slice(&nonce), ct.as_slice())
+            .map_err(|_| KeystoreError::Decrypt)?;
+        let raw: Result<[u8; 32], _> = pt.as_slice().try_into();
+        pt.zeroize();
+        let mut raw = raw.map_err(|_| KeystoreError::Decrypt)?;
+        let key = SecretKey::from_bytes(&raw).map_err(|_| KeystoreError::Decrypt);
+        raw.zeroize();
+        let key = key?;
+        if hex::encode(key.public_key()) != self.pubkey_hex.to_ascii_lowercase() {
+            return Err(KeystoreError::PubkeyMismatch);
+        }
+        Ok(key)
+    }
+}
+
+fn derive_key(password: &str, kdf: &str, params: &KdfParams) -> Result<[u8; 32], KeystoreError> {
+    if params.dklen != 32 {
+        return Err(KeystoreError::Kdf(format!("dklen {}", params.dklen)));
+    }
+    let salt = B64
+        .decode(&params.salt)
+        .map_err(|_| KeystoreError::Decode("salt"))?;
+    let mut key = [0u8; 32];
+    match kdf {
+        "pbkdf2" => {
+            let iterations = params.iterations.unwrap_or(600_000);
+            if iterations == 0 {
+                return Err(KeystoreError::Kdf("iterations must be positive".into()));
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, iterations, &mut key);
+        }
+        "scrypt" => {
+            let n = params.n.unwrap_or(1 << 15);
+            if !n.is_power_of_two() || n < 2 {
+                return Err(KeystoreError::Kdf(format!(
+                    "scrypt n {n} is not a power of two"
+                )));
+            }
+            let log_n = n.trailing_zeros() as u8;
+            let sp = scrypt::Params::new(log_n, params.r.unwrap_or(8), params.p.unwrap_or(1), 32)
+                .map_err(|e| KeystoreError::Kdf(e.to_string()))?;
+            scrypt::scrypt(password.as_bytes(), &salt, &sp, &mut key)
+                .map_err(|e| KeystoreError::Kdf(e.to_string()))?;
+        }
+        other => return Err(KeystoreError::Kdf(format!("unknown kdf {other}"))),
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_and_rejects_wrong_password() {
+        let key = SecretKey::from_bytes(&[3u8; 32]).unwrap();
+        for kdf in [
+            Kdf::Pbkdf2 { iterations: 1000 },
+            Kdf::Scrypt {
+                n: 1 << 10,
+                r: 8,
+                p: 1,
+            },
+        ] {
+            let ks = ValidatorKeystore::encrypt(&key, "pw", kdf).unwrap();
+            let json = serde_json::to_string(&ks).unwrap();
+            let ks: ValidatorKeystore = serde_json::from_str(&json).unwrap();
+            assert_eq!(ks.decrypt("pw").unwrap().to_bytes(), key.to_bytes());
+            assert_eq!(ks.decrypt("bad").unwrap_err(), KeystoreError::Decrypt);
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_pubkey() {
+        let key = SecretKey::from_bytes(&[3u8; 32]).unwrap();
+        let other = SecretKey::from_bytes(&[4u8; 32]).unwrap();
+        let mut ks =
+            ValidatorKeystore::encrypt(&key, "pw", Kdf::Pbkdf2 { iterations: 10 }).unwrap();
+        ks.pubkey_hex = hex::encode(other.public_key());
+        assert_eq!(ks.decrypt("pw").unwrap_err(), KeystoreError::PubkeyMismatch);
+    }
+}
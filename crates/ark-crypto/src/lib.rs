@@ -1,2 +1,16 @@
-//! 加密占位：后续接入 ed25519 / secp256k1 / BLS
-pub trait Signer {}
+//! 加密原语：secp256k1 签名 / 验签（交易与共识消息均对 32 字节摘要签名）。
+//!
+//! - 公钥：33 字节压缩格式
+//! - 签名：r||s 共 64 字节，要求 low-S（拒绝可延展签名）
+//! - 验证者私钥以 keystore 加密落盘（见 [`keystore`]）
+pub mod keystore;
+pub mod secp256k1;
+
+pub use keystore::{Kdf, KeystoreError, ValidatorKeystore};
+pub use secp256k1::{recover, verify, CryptoError, SecretKey};
+
+/// 对 32 字节摘要签名的密钥持有方（本地密钥或远程签名器）。
+pub trait Signer {
+    fn public_key(&self) -> [u8; 33];
+    fn sign(&self, digest: &[u8; 32]) -> Result<[u8; 64], CryptoError>;
+}
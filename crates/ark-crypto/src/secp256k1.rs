@@ -0,0 +1,170 @@
+use crate::Signer;
+use k256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use zeroize::Zeroize;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    #[error("invalid secret key")]
+    InvalidSecretKey,
+    #[error("signing failed: {0}")]
+    Signing(String),
+}
+
+/// secp256k1 私钥（drop 时清零原始字节）。
+pub struct SecretKey {
+    raw: [u8; 32],
+    key: SigningKey,
+}
+
+impl SecretKey {
+    pub fn from_bytes(raw: &[u8; 32]) -> Result<Self, CryptoError> {
+        let key = SigningKey::from_slice(raw).map_err(|_| CryptoError::InvalidSecretKey)?;
+        Ok(Self { raw: *raw, key })
+    }
+
+    /// 由系统随机源生成。
+    pub fn generate() -> Self {
+        use rand::RngCore;
+        let mut raw = [0u8; 32];
+        loop {
+            rand::rngs::OsRng.fill_bytes(&mut raw);
+            if let Ok(key) = Self::from_bytes(&raw) {
+                raw.zeroize();
+                return key;
+            }
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.raw
+    }
+
+    /// 65 字节非压缩公钥（0x04 || x || y），用于派生以太坊地址。
+    pub fn uncompressed_public_key(&self) -> [u8; 65] {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        point
+            .as_bytes()
+            .try_into()
+            .expect("uncompressed point is 65 bytes")
+    }
+
+    /// 以太坊风格的可恢复签名：r||s||y_parity 共 65 字节（low-S）。
+    pub fn sign_recoverable(&self, digest: &[u8; 32]) -> Result<[u8; 65], CryptoError> {
+        let (sig, id) = self
+            .key
+            .sign_prehash_recoverable(digest)
+            .map_err(|e| CryptoError::Signing(e.to_string()))?;
+        let (sig, parity) = match sig.normalize_s() {
+            Some(low) => (low, !id.is_y_odd()),
+            None => (sig, id.is_y_odd()),
+        };
+        let mut out = [0u8; 65];
+        out[..64].copy_from_slice(&sig.to_bytes());
+        out[64] = parity as u8;
+        Ok(out)
+    }
+}
+
+impl Signer for SecretKey {
+    fn public_key(&self) -> [u8; 33] {
+        let point = self.key.verifying_key().to_encoded_point(true);
+        point
+            .as_bytes()
+            .try_into()
+            .expect("compressed point is 33 bytes")
+    }
+
+    fn sign(&self, digest: &[u8; 32]) -> Result<[u8; 64], CryptoError> {
+        let sig: Signature = self
+            .key
+            .sign_prehash(digest)
+            .map_err(|e| CryptoError::Signing(e.to_string()))?;
+        let sig = sig.normalize_s().unwrap_or(sig);
+        Ok(sig.to_bytes().into())
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.raw.zeroize();
+    }
+}
+
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+/// 校验压缩公钥对摘要的签名；格式错误或 high-S 签名一律返回 false。
+pub fn verify(pubkey: &[u8], digest: &[u8; 32], signature: &[u8]) -> bool {
+    let Ok(vk) = VerifyingKey::from_sec1_bytes(pubkey) else {
+        return false;
+    };
+    if pubkey.len() != 33 {
+        return false;
+    }
+    let Ok(sig) = Signature::from_slice(signature) else {
+        return false;
+    };
+    if sig.normalize_s().is_some() {
+        return false;
+    }
+    vk.verify_prehash(digest, &sig).is_ok()
+}
+
+/// 由 r||s||y_parity（65 字节）签名恢复非压缩公钥；格式错误、high-S 或 y_parity 不为 0/1 时返回 None。
+pub fn recover(digest: &[u8; 32], signature: &[u8]) -> Option<[u8; 65]> {
+    let [rs @ .., parity] = signature else {
+        return None;
+    };
+    if rs.len() != 64 || *parity > 1 {
+        return None;
+    }
+    let sig = Signature::from_slice(rs).ok()?;
+    if sig.normalize_s().is_some() {
+        return None;
+    }
+    let id = RecoveryId::new(*parity == 1, false);
+    let vk = VerifyingKey::recover_from_prehash(digest, &sig, id).ok()?;
+    vk.to_encoded_point(false).as_bytes().try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_verify_roundtrip_and_rejects_tampering() {
+        let sk = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let pk = sk.public_key();
+        let digest = [1u8; 32];
+        let sig = sk.sign(&digest).unwrap();
+        assert!(verify(&pk, &digest, &sig));
+        assert!(!verify(&pk, &[2u8; 32], &sig));
+        let mut bad = sig;
+        bad[10] ^= 1;
+        assert!(!verify(&pk, &digest, &bad));
+        assert!(!verify(&pk[..32], &digest, &sig));
+        assert!(SecretKey::from_bytes(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn recoverable_signature_recovers_uncompressed_key() {
+        let sk = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let digest = [1u8; 32];
+        let sig = sk.sign_recoverable(&digest).unwrap();
+        assert_eq!(recover(&digest, &sig), Some(sk.uncompressed_public_key()));
+        assert!(verify(&sk.public_key(), &digest, &sig[..64]));
+        let mut flipped = sig;
+        flipped[64] ^= 1;
+        assert_ne!(
+            recover(&digest, &flipped),
+            Some(sk.uncompressed_public_key())
+        );
+        flipped[64] = 27;
+        assert_eq!(recover(&digest, &flipped), None);
+        assert_eq!(recover(&digest, &sig[..64]), None);
+    }
+}
@@ -0,0 +1,426 @@
+//! 区块组装：按顺序执行候选交易，累计 gas，达到区块 gas 上限即停止打包。
+//! 导入外部区块时以同一流程重放，并核对区块头中的根与 gas。
+use crate::error::ExecError;
+use crate::executor::{verify_signature, BlockEnv, Executor};
+use crate::fee_market::BlockFees;
+use crate::metrics;
+use crate::staking::EpochTransition;
+use crate::state::State;
+use crate::upgrade::UpgradeError;
+use ark_types::block::{logs_bloom, receipts_root, tx_root};
+use ark_types::{Amount, Block, BlockHeader, Gas, Receipt, SignedTransaction, H256};
+use std::borrow::Cow;
+use std::time::Instant;
+
+/// 组装完成的区块内容。
+#[derive(Clone, Debug)]
+pub struct BuiltBlock {
+    pub env: BlockEnv,
+    pub txs: Vec<SignedTransaction>,
+    pub receipts: Vec<Receipt>,
+    pub gas_used: Gas,
+    pub state_root: H256,
+    /// 本区块为 epoch 末块时的质押结算结果
+    pub epoch_transition: Option<EpochTransition>,
+    /// 本区块开头激活的协议升级
+    pub upgrades: Vec<String>,
+}
+
+impl BuiltBlock {
+    /// 费用摘要，用于推进 FeeHistory。
+    pub fn fees(&self) -> BlockFees {
+        BlockFees::from_block(
+            self.env.height,
+            self.env.base_fee,
+            self.env.gas_limit,
+            &self.txs,
+            &self.receipts,
+        )
+    }
+
+    pub fn into_block(self, parent_hash: H256) -> Block {
+        let tx_root = tx_root(&self.txs);
+        let receipts_root = receipts_root(&self.receipts);
+        let logs_bloom = logs_bloom(&self.receipts);
+        Block {
+            header: BlockHeader {
+                height: self.env.height,
+                parent_hash,
+                timestamp_ms: self.env.timestamp_ms,
+                proposer: self.env.proposer,
+                state_root: self.state_root,
+                tx_root,
+                receipts_root,
+                gas_limit: self.env.gas_limit,
+                gas_used: self.gas_used,
+                base_fee: self.env.base_fee,
+                logs_bloom,
+            },
+            txs: self.txs,
+        }
+    }
+}
+
+pub struct BlockBuilder<'a> {
+    /// 本区块生效的执行器（已应用治理修改的参数）
+    exec: Cow<'a, Executor>,
+    state: &'a mut State,
+    env: BlockEnv,
+    gas_used: Gas,
+    txs: Vec<SignedTransaction>,
+    receipts: Vec<Receipt>,
+    upgrades: Vec<String>,
+    started: Instant,
+}
+
+impl<'a> BlockBuilder<'a> {
+    /// 先激活本高度到期的协议升级；本版本无法执行这些升级时返回错误，状态不变。
+    pub fn new(
+        exec: &'a Executor,
+        state: &'a mut State,
+        env: BlockEnv,
+    ) -> Result<Self, UpgradeError> {
+        let upgrades = exec.begin_block(state, &env)?;
+        Ok(Self {
+            exec: exec.at(state),
+            state,
+            env,
+            gas_used: 0,
+            txs: Vec::new(),
+            receipts: Vec::new(),
+            upgrades,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn gas_remaining(&self) -> Gas {
+        self.env.gas_limit - self.gas_used
+    }
+
+    /// 剩余 gas 已不足以容纳最便宜的交易。
+    pub fn is_full(&self) -> bool {
+        self.gas_remaining() < self.exec.schedule.tx_base
+    }
+
+    /// 尝试打包一笔交易；gas_limit 超过剩余额度或校验失败时拒绝，状态不变。
+    pub fn push(&mut self, stx: SignedTransaction) -> Result<&Receipt, ExecError> {
+        let remaining = self.gas_remaining();
+        if stx.tx.gas_limit > remaining {
+            return Err(ExecError::BlockGasExhausted {
+                remaining,
+                gas_limit: stx.tx.gas_limit,
+            });
+        }
+        let timer = metrics::TX_SECONDS.start_timer();
+        let outcome = self.exec.apply(self.state, &stx, &self.env)?;
+        timer.observe_duration();
+        metrics::TXS_TOTAL.inc();
+        metrics::GAS_USED_TOTAL.inc_by(outcome.gas_used);
+        self.gas_used += outcome.gas_used;
+        let receipt = outcome.into_receipt(stx.hash(), self.gas_used);
+        self.txs.push(stx);
+        self.receipts.push(receipt);
+        Ok(self.receipts.last().expect("just pushed"))
+    }
+
+    /// 执行区块收尾（治理计票与执行、epoch 结算）并提交状态。
+    pub fn finish(self) -> BuiltBlock {
+        let epoch_transition = self.exec.end_block(self.state, &self.env);
+        self.state.commit();
+        metrics::BLOCK_SECONDS.observe(self.started.elapsed().as_secs_f64());
+        BuiltBlock {
+            epoch_transition,
+            upgrades: self.upgrades,
+            state_root: self.state.state_root(),
+            env: self.env,
+            txs: self.txs,
+            receipts: self.receipts,
+            gas_used: self.gas_used,
+        }
+    }
+}
+
+/// 从候选交易中组装区块：跳过无法打包的交易，区块满时停止。
+/// 返回区块内容与被跳过的交易（附原因）。
+pub fn fill_block<I>(
+    exec: &Executor,
+    state: &mut State,
+    env: BlockEnv,
+    candidates: I,
+) -> Result<(BuiltBlock, Vec<(SignedTransaction, ExecError)>), UpgradeError>
+where
+    I: IntoIterator<Item = SignedTransaction>,
+{
+    let mut builder = BlockBuilder::new(exec, state, env)?;
+    let mut skipped = Vec::new();
+    for stx in candidates {
+        if builder.is_full() {
+            break;
+        }
+        if let Err(e) = builder.push(stx.clone()) {
+            skipped.push((stx, e));
+        }
+    }
+    Ok((builder.finish(), skipped))
+}
+
+/// 重放外部区块（导入 / 同步）时的校验失败。
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("block {height}: expected height {expected}")]
+    Height { height: u64, expected: u64 },
+    #[error("block {height}: parent hash does not match block {}", height - 1)]
+    Parent { height: u64 },
+    #[error("block {height}: timestamp {timestamp_ms} not after parent")]
+    Timestamp { height: u64, timestamp_ms: u64 },
+    #[error("block {height}: base fee {got}, expected {expected}")]
+    BaseFee {
+        height: u64,
+        got: Amount,
+        expected: Amount,
+    },
+    #[error("block {height}: gas limit {got}, expected {expected}")]
+    GasLimit {
+        height: u64,
+        got: Gas,
+        expected: Gas,
+    },
+    #[error("block {height} tx {index}: invalid signature")]
+    Signature { height: u64, index: usize },
+    #[error("block {height} tx {index}: {source}")]
+    Tx {
+        height: u64,
+        index: usize,
+        source: ExecError,
+    },
+    #[error("block {height}: {field} mismatch after execution")]
+    Header { height: u64, field: &'static str },
+    #[error(transparent)]
+    Upgrade(#[from] UpgradeError),
+}
+
+/// 在 parent 之后重放区块并核对执行结果与区块头一致。
+/// 失败时 state 可能已部分修改，调用方应在副本上重放。
+pub fn replay_block(
+    exec: &Executor,
+    state: &mut State,
+    chain_id: &str,
+    parent: &BlockHeader,
+    block: &Block,
+) -> Result<BuiltBlock, ReplayError> {
+    let h = &block.header;
+    let height = h.height;
+    if height != parent.height + 1 {
+        return Err(ReplayError::Height {
+            height,
+            expected: parent.height + 1,
+        });
+    }
+    if h.parent_hash != parent.hash() {
+        return Err(ReplayError::Parent { height });
+    }
+    if h.timestamp_ms <= parent.timestamp_ms {
+        return Err(ReplayError::Timestamp {
+            height,
+            timestamp_ms: h.timestamp_ms,
+        });
+    }
+    // 区块 gas 上限与 base fee 规则按治理修改后的参数，与出块一致
+    let active = exec.at(state);
+    let expected = active.params.gas_limit_block;
+    if h.gas_limit != expected {
+        return Err(ReplayError::GasLimit {
+            height,
+            got: h.gas_limit,
+            expected,
+        });
+    }
+    let expected =
+        active
+            .fee_market
+            .next_base_fee(parent.base_fee, parent.gas_used, parent.gas_limit);
+    if h.base_fee != expected {
+        return Err(ReplayError::BaseFee {
+            height,
+            got: h.base_fee,
+            expected,
+        });
+    }
+    let env = BlockEnv {
+        chain_id: chain_id.to_string(),
+        height,
+        parent_hash: parent.hash(),
+        timestamp_ms: h.timestamp_ms,
+        proposer: h.proposer,
+        gas_limit: h.gas_limit,
+        base_fee: h.base_fee,
+    };
+    let mut builder = BlockBuilder::new(exec, state, env)?;
+    for (index, stx) in block.txs.iter().enumerate() {
+        // 执行器不校验签名（出块候选已在交易池准入时校验），外部区块须在此逐笔校验
+        if !verify_signature(stx) {
+            return Err(ReplayError::Signature { height, index });
+        }
+        builder
+            .push(stx.clone())
+            .map_err(|source| ReplayError::Tx {
+                height,
+                index,
+                source,
+            })?;
+    }
+    let built = builder.finish();
+    let mismatch = |field| Err(ReplayError::Header { height, field });
+    if built.gas_used != h.gas_used {
+        return mismatch("gas_used");
+    }
+    if built.state_root != h.state_root {
+        return mismatch("state_root");
+    }
+    if tx_root(&built.txs) != h.tx_root {
+        return mismatch("tx_root");
+    }
+    if receipts_root(&built.receipts) != h.receipts_root {
+        return mismatch("receipts_root");
+    }
+    if logs_bloom(&built.receipts) != h.logs_bloom {
+        return mismatch("logs_bloom");
+    }
+    Ok(built)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::tests::{env, funded, params, signed};
+    use ark_crypto::{SecretKey, Signer};
+    use ark_types::{Action, Address};
+
+    #[test]
+    fn stops_when_block_gas_limit_reached() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1, 2, 3]);
+        let mut env = env();
+        env.gas_limit = 50_000;
+        let to = Address([7u8; 20]);
+        let txs = vec![
+            signed(1, 0, Action::Transfer { to }, 21_000),
+            signed(2, 0, Action::Transfer { to }, 30_000),
+            signed(3, 0, Action::Transfer { to }, 21_000),
+        ];
+        let (built, skipped) = fill_block(&exec, &mut state, env, txs).unwrap();
+        // 第二笔交易的 gas_limit 超过剩余额度被跳过，第三笔仍可打包
+        assert_eq!(built.txs.len(), 2);
+        assert_eq!(built.gas_used, 42_000);
+        assert_eq!(built.receipts[1].cumulative_gas_used, 42_000);
+        assert_eq!(skipped.len(), 1);
+        assert!(matches!(skipped[0].1, ExecError::BlockGasExhausted { .. }));
+        assert_eq!(state.nonce(&Address::from_pubkey(&[2u8; 33])), 0);
+
+        let fees = built.fees();
+        assert_eq!(fees.tips, vec![(2, 21_000), (2, 21_000)]);
+        assert_eq!(fees.gas_used, 42_000);
+
+        let root = state.state_root();
+        let block = built.into_block(H256::ZERO);
+        assert_eq!(block.header.state_root, root);
+        assert_eq!(block.header.gas_used, 42_000);
+        assert_eq!(block.header.gas_limit, 50_000);
+    }
+
+    fn secret(k: u8) -> SecretKey {
+        SecretKey::from_bytes(&[k; 32]).unwrap()
+    }
+
+    /// 由真实私钥签名的交易（重放会校验签名）。
+    fn signed_by(k: u8, nonce: u64, action: Action) -> SignedTransaction {
+        let sk = secret(k);
+        let mut stx = signed(k, nonce, action, 21_000);
+        stx.pubkey = sk.public_key().to_vec();
+        stx.signature = sk.sign(&stx.tx.signing_hash().0).unwrap().to_vec();
+        stx
+    }
+
+    fn funded_signers(keys: &[u8]) -> State {
+        let mut s = State::new();
+        for k in keys {
+            s.add_balance(
+                &Address::from_pubkey(&secret(*k).public_key()),
+                1_000_000_000_000,
+            );
+        }
+        s.commit();
+        s
+    }
+
+    #[test]
+    fn replay_reproduces_built_block_and_rejects_tampering() {
+        let exec = Executor::new(&params());
+        let mut parent = env();
+        parent.height = 0;
+        let parent = fill_block(&exec, &mut funded(&[]), parent, Vec::new())
+            .unwrap()
+            .0
+            .into_block(H256::ZERO)
+            .header;
+        let mut env = env();
+        env.timestamp_ms = 1_000;
+        env.base_fee =
+            exec.fee_market
+                .next_base_fee(parent.base_fee, parent.gas_used, parent.gas_limit);
+        let to = Address([7u8; 20]);
+        let txs = vec![
+            signed_by(1, 0, Action::Transfer { to }),
+            signed_by(2, 0, Action::Transfer { to }),
+        ];
+        let (built, _) = fill_block(&exec, &mut funded_signers(&[1, 2]), env.clone(), txs).unwrap();
+        let block = built.into_block(parent.hash());
+
+        let chain_id = env.chain_id.as_str();
+        let replayed = replay_block(
+            &exec,
+            &mut funded_signers(&[1, 2]),
+            chain_id,
+            &parent,
+            &block,
+        );
+        assert_eq!(replayed.unwrap().into_block(parent.hash()), block);
+
+        let mut bad = block.clone();
+        bad.header.state_root = H256::ZERO;
+        assert_eq!(
+            replay_block(&exec, &mut funded_signers(&[1, 2]), chain_id, &parent, &bad).unwrap_err(),
+            ReplayError::Header {
+                height: env.height,
+                field: "state_root"
+            }
+        );
+        let mut bad = block.clone();
+        bad.header.parent_hash = H256::ZERO;
+        assert!(matches!(
+            replay_block(&exec, &mut funded_signers(&[1, 2]), chain_id, &parent, &bad),
+            Err(ReplayError::Parent { .. })
+        ));
+        let mut bad = block.clone();
+        bad.header.gas_limit += 1;
+        assert!(matches!(
+            replay_block(&exec, &mut funded_signers(&[1, 2]), chain_id, &parent, &bad),
+            Err(ReplayError::GasLimit { .. })
+        ));
+        // 伪造签名：执行器本身不验签，转账仍可执行，必须在重放时拒绝
+        let mut bad = block.clone();
+        bad.txs[1].signature[0] ^= 1;
+        assert_eq!(
+            replay_block(&exec, &mut funded_signers(&[1, 2]), chain_id, &parent, &bad).unwrap_err(),
+            ReplayError::Signature {
+                height: env.height,
+                index: 1
+            }
+        );
+        // 发送方余额不足：交易本身无法上链
+        assert!(matches!(
+            replay_block(&exec, &mut funded_signers(&[1]), chain_id, &parent, &block),
+            Err(ReplayError::Tx { index: 1, .. })
+        ));
+    }
+}
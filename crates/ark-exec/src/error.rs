@@ -0,0 +1,29 @@
+//! 执行错误：交易不满足上链条件（不会产生回执）。
+//! 执行期失败（回滚 / gas 耗尽）体现在回执状态中，而非此处。
+use ark_types::{Amount, Gas};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ExecError {
+    #[error("chain id mismatch: expected {expected}, got {got}")]
+    ChainIdMismatch { expected: String, got: String },
+    #[error("nonce too low: expected {expected}, got {got}")]
+    NonceTooLow { expected: u64, got: u64 },
+    #[error("nonce too high: expected {expected}, got {got}")]
+    NonceTooHigh { expected: u64, got: u64 },
+    #[error("insufficient funds: need {need}, have {have}")]
+    InsufficientFunds { need: Amount, have: Amount },
+    #[error("intrinsic gas too low: limit {limit}, required {required}")]
+    IntrinsicGas { limit: Gas, required: Gas },
+    #[error("max fee per gas {max_fee} below base fee {base_fee}")]
+    FeeCapTooLow { max_fee: Amount, base_fee: Amount },
+    #[error("max priority fee {tip} exceeds max fee {max_fee}")]
+    TipAboveFeeCap { tip: Amount, max_fee: Amount },
+    #[error("tx gas limit {gas_limit} exceeds block gas limit {block_limit}")]
+    ExceedsBlockGasLimit { gas_limit: Gas, block_limit: Gas },
+    #[error("block gas exhausted: remaining {remaining}, tx needs {gas_limit}")]
+    BlockGasExhausted { remaining: Gas, gas_limit: Gas },
+    #[error("fee overflow")]
+    FeeOverflow,
+    #[error("feature gate {0} is not active")]
+    FeatureDisabled(&'static str),
+}
@@ -0,0 +1,568 @@
+//! EVM 执行后端（evm 特性门开启后可用）：由 revm 按以太坊 Cancun 规则执行，与原生 VM 共用账户、余额与存储模型。
+//!
+//! - 部署（Action::EvmCreate）与调用 EVM 合约的 Action::Call 作为一笔完整的以太坊交易交给 revm：
+//!   固有 gas、nonce、转账、执行、退款与手续费都按以太坊规则结算，小费付给出块者，
+//!   base fee 部分由执行器销毁或转入 FeeMarket 配置的地址
+//! - 合约地址按以太坊 CREATE 规则 keccak256(rlp([sender, nonce]))[12..] 计算
+//! - 运行时代码以 EVM_MAGIC 前缀存储，Action::Call 据此选择 EVM 或原生 VM
+//! - 原生 VM 合约在 EVM 中表现为单字节 INVALID 代码：EVM 合约调用原生合约一律失败
+//! - BLOCKHASH：开启特性门后每个区块开始时记录父区块哈希，保留最近 256 个区块，更早的为 0
+//! - 合约间调用的帧由 revm 在堆上维护，嵌套到 1024 层也不依赖调用方线程的栈大小
+use crate::executor::BlockEnv;
+use crate::state::{Account, State};
+use ark_types::{Action, Address, Amount, ExecStatus, Gas, Log, Transaction, H256};
+use revm::interpreter::gas::validate_initial_tx_gas;
+use revm::primitives::{
+    self as rp, AccountInfo, Bytecode, Bytes, EvmState, ExecutionResult, HaltReason, Output,
+    SpecId, TxKind, B256, U256,
+};
+use revm::{DatabaseRef, Evm};
+use std::convert::Infallible;
+
+pub use ark_types::eth::keccak256;
+
+/// EVM 运行时代码在状态中的前缀（0xEF 开头的代码不会由 EVM 部署产生，见 EIP-3541）。
+pub const EVM_MAGIC: &[u8] = b"\xefEVM";
+/// 执行规则对应的以太坊硬分叉
+pub const SPEC: SpecId = SpecId::CANCUN;
+/// BLOCKHASH 可查询的最近区块数
+pub const BLOCK_HASH_WINDOW: u64 = 256;
+const KEY_BLOCK_HASH: &[u8] = b"evm/blockhash/";
+/// 原生 VM 合约在 EVM 中呈现的代码（INVALID）
+const NATIVE_PLACEHOLDER: &[u8] = &[0xfe];
+
+pub fn is_evm_code(code: &[u8]) -> bool {
+    code.starts_with(EVM_MAGIC)
+}
+
+/// 以太坊 CREATE 地址：keccak256(rlp([sender, nonce])) 后 20 字节。
+pub fn contract_address(sender: &Address, nonce: u64) -> Address {
+    from_evm_address(to_evm_address(sender).create(nonce))
+}
+
+/// 交给 revm 执行的交易：部署 EVM 合约，或调用已部署的 EVM 合约。
+pub fn handles(state: &State, action: &Action) -> bool {
+    match action {
+        Action::EvmCreate { .. } => true,
+        Action::Call { to, .. } => state.code(to).is_some_and(is_evm_code),
+        _ => false,
+    }
+}
+
+/// EVM 部署按以太坊规则的固有 gas（含 EIP-3860 初始化代码计费）。
+pub fn create_intrinsic_gas(init_code: &[u8]) -> Gas {
+    validate_initial_tx_gas(SPEC, init_code, true, &[])
+}
+
+/// 区块开始时记录父区块哈希供 BLOCKHASH 查询，并删除滑出窗口的记录。
+pub fn record_block_hash(state: &mut State, env: &BlockEnv) {
+    let Some(parent) = env.height.checked_sub(1) else {
+        return;
+    };
+    state.module_set(block_hash_key(parent), env.parent_hash.0.to_vec());
+    if let Some(expired) = parent.checked_sub(BLOCK_HASH_WINDOW) {
+        state.module_delete(&block_hash_key(expired));
+    }
+}
+
+fn block_hash_key(height: u64) -> Vec<u8> {
+    [KEY_BLOCK_HASH, &height.to_be_bytes()].concat()
+}
+
+/// 一笔 EVM 交易的执行结果；状态修改与日志已写回 State。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    pub status: ExecStatus,
+    pub gas_used: Gas,
+    pub contract_address: Option<Address>,
+    pub revert_reason: Option<String>,
+    /// RETURN / REVERT 的数据（eth_call 以此作为返回值或错误数据）
+    pub output: Vec<u8>,
+}
+
+/// 执行交易并把修改写回 state。revm 拒绝交易（不满足以太坊校验规则）时返回原因，状态不变。
+pub fn transact(
+    state: &mut State,
+    env: &BlockEnv,
+    sender: &Address,
+    tx: &Transaction,
+    max_code_size: usize,
+) -> Result<Execution, String> {
+    let (kind, data) = match &tx.action {
+        Action::EvmCreate { code } => (TxKind::Create, code),
+        Action::Call { to, input } => (TxKind::Call(to_evm_address(to)), input),
+        _ => return Err("not an EVM transaction".into()),
+    };
+    let outcome = Evm::builder()
+        .with_ref_db(StateDb { state, env })
+        .with_spec_id(SPEC)
+        .modify_cfg_env(|cfg| {
+            cfg.chain_id = ark_types::eth::eth_chain_id(&env.chain_id);
+            cfg.limit_contract_code_size = Some(max_code_size);
+        })
+        .modify_block_env(|block| {
+            block.number = U256::from(env.height);
+            block.coinbase = to_evm_address(&env.proposer);
+            block.timestamp = U256::from(env.timestamp_ms / 1000);
+            block.gas_limit = U256::from(env.gas_limit);
+            block.basefee = U256::from(env.base_fee);
+        })
+        .modify_tx_env(|t| {
+            t.caller = to_evm_address(sender);
+            t.gas_limit = tx.gas_limit;
+            t.gas_price = U256::from(tx.max_fee_per_gas);
+            t.gas_priority_fee = Some(U256::from(tx.max_priority_fee_per_gas));
+            t.transact_to = kind;
+            t.value = U256::from(tx.value);
+            t.data = Bytes::copy_from_slice(data);
+            t.nonce = Some(tx.nonce);
+        })
+        .build()
+        .transact()
+        .map_err(|e| e.to_string())?;
+    commit(state, outcome.state);
+
+    Ok(match outcome.result {
+        ExecutionResult::Success {
+            gas_used,
+            logs,
+            output,
+            ..
+        } => {
+            for log in logs {
+                state.emit_log(Log {
+                    address: from_evm_address(log.address),
+                    topics: log.topics().iter().map(|t| H256(t.0)).collect(),
+                    data: log.data.data.to_vec(),
+                });
+            }
+            let (output, contract_address) = match output {
+                Output::Call(data) => (data.to_vec(), None),
+                Output::Create(_, addr) => (Vec::new(), addr.map(from_evm_address)),
+            };
+            Execution {
+                status: ExecStatus::Success,
+                gas_used,
+                contract_address,
+                revert_reason: None,
+                output,
+            }
+        }
+        ExecutionResult::Revert { gas_used, output } => Execution {
+            status: ExecStatus::Reverted,
+            gas_used,
+            contract_address: None,
+            revert_reason: Some(revert_reason(&output)),
+            output: output.to_vec(),
+        },
+        ExecutionResult::Halt { reason, gas_used } => Execution {
+            status: match reason {
+                HaltReason::OutOfGas(_) => ExecStatus::OutOfGas,
+                _ => ExecStatus::Reverted,
+            },
+            gas_used,
+            contract_address: None,
+            revert_reason: match reason {
+                HaltReason::OutOfGas(_) => None,
+                other => Some(format!("{other:?}")),
+            },
+            output: Vec::new(),
+        },
+    })
+}
+
+/// 把 revm 的账户修改写回状态：余额与 nonce、新合约的运行时代码、变更的存储槽。
+/// 最终为空且原本不存在的账户（如零小费的出块者）不写入（EIP-161）。
+fn commit(state: &mut State, changes: EvmState) {
+    let mut changes: Vec<_> = changes
+        .into_iter()
+        .filter(|(_, a)| a.is_touched())
+        .collect();
+    // 按地址顺序写回，回滚日志与执行顺序无关
+    changes.sort_by_key(|(addr, _)| *addr);
+    for (addr, account) in changes {
+        let addr = from_evm_address(addr);
+        let prev = state.account(&addr);
+        // EIP-6780：只有同一交易内创建的合约会被销毁，它此前没有代码与存储
+        if account.is_selfdestructed() || account.is_empty() {
+            if prev != Account::default() {
+                state.set_account(addr, Account::default());
+            }
+            continue;
+        }
+        state.set_account(
+            addr,
+            Account {
+                nonce: account.info.nonce,
+                balance: account.info.balance.saturating_to::<Amount>(),
+                code_hash: prev.code_hash,
+            },
+        );
+        if account.is_created() {
+            let runtime = account
+                .info
+                .code
+                .as_ref()
+                .map(Bytecode::original_bytes)
+                .unwrap_or_default();
+            if !runtime.is_empty() {
+                state.set_code(&addr, [EVM_MAGIC, &runtime].concat());
+            }
+        }
+        for (key, slot) in account.changed_storage_slots() {
+            state.set_storage(
+                &addr,
+                H256(key.to_be_bytes()),
+                H256(slot.present_value.to_be_bytes()),
+            );
+        }
+    }
+}
+
+/// revm 读取状态的视图（只读；修改经 commit 写回）。
+struct StateDb<'a> {
+    state: &'a State,
+    env: &'a BlockEnv,
+}
+
+impl DatabaseRef for StateDb<'_> {
+    type Error = Infallible;
+
+    fn basic_ref(&self, address: rp::Address) -> Result<Option<AccountInfo>, Infallible> {
+        let addr = from_evm_address(address);
+        let account = self.state.account(&addr);
+        if account == Account::default() {
+            return Ok(None);
+        }
+        let code = self.state.code(&addr).map(|code| {
+            Bytecode::new_raw(Bytes::copy_from_slice(
+                code.strip_prefix(EVM_MAGIC).unwrap_or(NATIVE_PLACEHOLDER),
+            ))
+        });
+        Ok(Some(AccountInfo {
+            balance: U256::from(account.balance),
+            nonce: account.nonce,
+            code_hash: code.as_ref().map_or(rp::KECCAK_EMPTY, Bytecode::hash_slow),
+            code,
+        }))
+    }
+
+    fn code_by_hash_ref(&self, _code_hash: B256) -> Result<Bytecode, Infallible> {
+        // basic_ref 总是随账户带上代码，revm 不会按哈希再取
+        Ok(Bytecode::new())
+    }
+
+    fn storage_ref(&self, address: rp::Address, index: U256) -> Result<U256, Infallible> {
+        let slot = self
+            .state
+            .storage(&from_evm_address(address), &H256(index.to_be_bytes()));
+        Ok(U256::from_be_bytes(slot.0))
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Infallible> {
+        let Ok(height) = u64::try_from(number) else {
+            return Ok(B256::ZERO);
+        };
+        // 模拟执行不经过区块开头，父区块哈希直接取自执行环境
+        if height + 1 == self.env.height {
+            return Ok(B256::from(self.env.parent_hash.0));
+        }
+        let hash = self
+            .state
+            .module_get(&block_hash_key(height))
+            .and_then(|raw| <[u8; 32]>::try_from(raw).ok())
+            .unwrap_or_default();
+        Ok(B256::from(hash))
+    }
+}
+
+fn to_evm_address(addr: &Address) -> rp::Address {
+    rp::Address::from(addr.0)
+}
+
+fn from_evm_address(addr: rp::Address) -> Address {
+    Address(addr.into_array())
+}
+
+/// 回滚原因：解码 Solidity `Error(string)`，否则为返回数据的十六进制。
+fn revert_reason(data: &[u8]) -> String {
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    if data.len() >= 68 && data[..4] == ERROR_SELECTOR {
+        let len = U256::from_be_slice(&data[36..68]);
+        if len <= U256::from(data.len() - 68) {
+            let len = len.to::<usize>();
+            if let Ok(reason) = std::str::from_utf8(&data[68..68 + len]) {
+                return reason.to_string();
+            }
+        }
+    }
+    if data.is_empty() {
+        return "execution reverted".into();
+    }
+    let hex: String = data.iter().map(|b| format!("{b:02x}")).collect();
+    format!("0x{hex}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::tests::env;
+    use revm::interpreter::opcode as op;
+
+    const SENDER: Address = Address([2u8; 20]);
+    const CALLEE: Address = Address([0xbb; 20]);
+
+    fn funded() -> State {
+        let mut state = State::new();
+        state.add_balance(&SENDER, 1 << 100);
+        state
+    }
+
+    fn deploy(state: &mut State, addr: &Address, runtime: &[u8]) {
+        state.set_code(addr, [EVM_MAGIC, runtime].concat());
+    }
+
+    fn tx(state: &State, action: Action, gas_limit: Gas) -> Transaction {
+        Transaction {
+            chain_id: env().chain_id,
+            nonce: state.nonce(&SENDER),
+            action,
+            value: 0,
+            gas_limit,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 2,
+        }
+    }
+
+    fn call_in(state: &mut State, env: &BlockEnv, to: Address, input: Vec<u8>) -> Execution {
+        let gas_limit = env.gas_limit;
+        let tx = tx(state, Action::Call { to, input }, gas_limit);
+        transact(state, env, &SENDER, &tx, 24_576).unwrap()
+    }
+
+    fn call(state: &mut State, to: Address, input: Vec<u8>) -> Execution {
+        call_in(state, &env(), to, input)
+    }
+
+    fn word(v: u64) -> [u8; 32] {
+        U256::from(v).to_be_bytes()
+    }
+
+    #[rustfmt::skip]
+    const RETURN_CALLDATA_WORD: [u8; 9] = [
+        op::PUSH0, op::CALLDATALOAD, op::PUSH0, op::MSTORE, op::PUSH1, 32, op::PUSH0, op::RETURN, op::STOP,
+    ];
+
+    #[test]
+    fn create_address_matches_ethereum() {
+        let sender = Address(hex_literal("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"));
+        let expect = |h: &str| Address(hex_literal(h));
+        assert_eq!(
+            contract_address(&sender, 0),
+            expect("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d")
+        );
+        assert_eq!(
+            contract_address(&sender, 1),
+            expect("343c43a37d37dff08ae8c4a11544c718abb4fcf8")
+        );
+    }
+
+    fn hex_literal(h: &str) -> [u8; 20] {
+        let mut out = [0u8; 20];
+        for (i, slot) in out.iter_mut().enumerate() {
+            *slot = u8::from_str_radix(&h[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn create_stores_runtime_code_and_settles_fees() {
+        let runtime = RETURN_CALLDATA_WORD;
+        #[rustfmt::skip]
+        let mut init = vec![
+            op::PUSH1, runtime.len() as u8, op::PUSH1, 10, op::PUSH0, op::CODECOPY,
+            op::PUSH1, runtime.len() as u8, op::PUSH0, op::RETURN,
+        ];
+        init.extend_from_slice(&runtime);
+        let intrinsic = create_intrinsic_gas(&init);
+        let mut state = funded();
+        let before = state.balance(&SENDER);
+        let create = tx(&state, Action::EvmCreate { code: init }, 200_000);
+        let out = transact(&mut state, &env(), &SENDER, &create, 24_576).unwrap();
+
+        assert_eq!(out.status, ExecStatus::Success);
+        let addr = contract_address(&SENDER, 0);
+        assert_eq!(out.contract_address, Some(addr));
+        assert_eq!(
+            state.code(&addr).unwrap(),
+            [EVM_MAGIC, &runtime[..]].concat()
+        );
+        assert_eq!((state.nonce(&SENDER), state.nonce(&addr)), (1, 1));
+        // 按实际单价 min(10, 8 + 2) 计费，小费归出块者，base fee 不记入任何账户
+        let used = out.gas_used as Amount;
+        assert!(out.gas_used > intrinsic);
+        assert_eq!(state.balance(&SENDER), before - used * 10);
+        assert_eq!(state.balance(&env().proposer), used * 2);
+
+        let out = call(&mut state, addr, word(7).to_vec());
+        assert_eq!(out.output, word(7));
+    }
+
+    #[test]
+    fn logs_and_storage_land_only_on_success() {
+        let mut state = funded();
+        // 写入槽 0 并发出 LOG1(topic 7)；带 calldata 时随后以 word(7) 回滚
+        #[rustfmt::skip]
+        let code = [
+            op::PUSH1, 1, op::PUSH0, op::SSTORE,
+            op::PUSH1, 7, op::PUSH0, op::PUSH0, op::LOG1,
+            op::CALLDATASIZE, op::PUSH1, 14, op::JUMPI, op::STOP,
+            op::JUMPDEST, op::PUSH1, 7, op::PUSH0, op::MSTORE, op::PUSH1, 32, op::PUSH0, op::REVERT,
+        ];
+        deploy(&mut state, &CALLEE, &code);
+
+        let out = call(&mut state, CALLEE, vec![1]);
+        assert_eq!(out.status, ExecStatus::Reverted);
+        assert_eq!(out.output, word(7));
+        assert_eq!(
+            out.revert_reason.as_deref(),
+            Some(&*format!("0x{:064x}", 7))
+        );
+        assert!(state.take_logs().is_empty());
+        assert!(state.storage(&CALLEE, &H256::ZERO).is_zero());
+        assert_eq!(state.nonce(&SENDER), 1);
+
+        let out = call(&mut state, CALLEE, Vec::new());
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(
+            state.take_logs(),
+            vec![Log {
+                address: CALLEE,
+                topics: vec![H256::from_u128(7)],
+                data: Vec::new(),
+            }]
+        );
+        assert_eq!(state.storage(&CALLEE, &H256::ZERO), H256::from_u128(1));
+    }
+
+    #[test]
+    fn revert_decodes_solidity_error_string() {
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(word(32));
+        data.extend(word(9));
+        let mut text = b"not owner".to_vec();
+        text.resize(32, 0);
+        data.extend(text);
+        assert_eq!(revert_reason(&data), "not owner");
+        assert_eq!(revert_reason(&[0xde, 0xad]), "0xdead");
+        assert_eq!(revert_reason(&[]), "execution reverted");
+    }
+
+    #[test]
+    fn blockhash_sees_the_recent_window() {
+        let mut state = funded();
+        let hash = |h: u64| H256::from_u128(h as u128 + 1_000);
+        for height in 2..300 {
+            let env = BlockEnv {
+                height,
+                parent_hash: hash(height - 1),
+                ..env()
+            };
+            record_block_hash(&mut state, &env);
+        }
+        assert!(state.module_get(&block_hash_key(42)).is_none());
+        assert!(state.module_get(&block_hash_key(43)).is_some());
+
+        #[rustfmt::skip]
+        let code = [
+            op::PUSH0, op::CALLDATALOAD, op::BLOCKHASH,
+            op::PUSH0, op::MSTORE, op::PUSH1, 32, op::PUSH0, op::RETURN,
+        ];
+        deploy(&mut state, &CALLEE, &code);
+        // 当前区块 300：父区块哈希取自执行环境，其余取自记录
+        let env = BlockEnv {
+            height: 300,
+            parent_hash: hash(299),
+            ..env()
+        };
+        for (number, expect) in [(299, hash(299)), (298, hash(298)), (44, hash(44))] {
+            let out = call_in(&mut state, &env, CALLEE, word(number).to_vec());
+            assert_eq!(out.output, expect.0);
+        }
+        for number in [43, 300] {
+            let out = call_in(&mut state, &env, CALLEE, word(number).to_vec());
+            assert_eq!(out.output, [0; 32]);
+        }
+    }
+
+    #[test]
+    fn recursion_reaches_the_call_depth_limit_without_growing_the_stack() {
+        // 槽 0 计数加一后以全部 gas 调用自己
+        #[rustfmt::skip]
+        let code = [
+            op::PUSH0, op::SLOAD, op::PUSH1, 1, op::ADD, op::PUSH0, op::SSTORE,
+            op::PUSH0, op::PUSH0, op::PUSH0, op::PUSH0, op::PUSH0, op::ADDRESS, op::GAS, op::CALL,
+        ];
+        let mut state = funded();
+        deploy(&mut state, &CALLEE, &code);
+        let env = BlockEnv {
+            gas_limit: 1 << 40,
+            ..env()
+        };
+        // 在小栈线程上执行：帧在堆上，深度不依赖线程栈
+        let (out, state) = std::thread::Builder::new()
+            .stack_size(256 << 10)
+            .spawn(move || {
+                let out = call_in(&mut state, &env, CALLEE, Vec::new());
+                (out, state)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        let frames = revm::CALL_STACK_LIMIT as u128 + 1;
+        assert_eq!(state.storage(&CALLEE, &H256::ZERO), H256::from_u128(frames));
+    }
+
+    #[test]
+    fn native_contracts_look_like_invalid_code() {
+        let mut state = funded();
+        state.set_code(&CALLEE, vec![0x01, 0x02, 0x03]);
+        let caller = Address([0xcc; 20]);
+        // 返回 [CALL 是否成功, EXTCODESIZE]
+        let mut code = vec![
+            op::PUSH0,
+            op::PUSH0,
+            op::PUSH0,
+            op::PUSH0,
+            op::PUSH0,
+            op::PUSH20,
+        ];
+        code.extend_from_slice(CALLEE.as_bytes());
+        code.extend([op::GAS, op::CALL, op::PUSH0, op::MSTORE, op::PUSH20]);
+        code.extend_from_slice(CALLEE.as_bytes());
+        #[rustfmt::skip]
+        code.extend([
+            op::EXTCODESIZE, op::PUSH1, 32, op::MSTORE,
+            op::PUSH1, 64, op::PUSH0, op::RETURN,
+        ]);
+        deploy(&mut state, &caller, &code);
+        let out = call(&mut state, caller, Vec::new());
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(out.output, [word(0), word(1)].concat());
+        assert!(handles(
+            &state,
+            &Action::Call {
+                to: caller,
+                input: Vec::new()
+            }
+        ));
+        assert!(!handles(
+            &state,
+            &Action::Call {
+                to: CALLEE,
+                input: Vec::new()
+            }
+        ));
+    }
+}
@@ -0,0 +1,842 @@
+//! 交易执行器：校验、购买 gas、执行、回滚与退款。
+//!
+//! 计费流程：
+//! 1. 预扣 gas_limit * max_fee_per_gas，nonce + 1（失败交易同样生效）
+//! 2. 扣除固有 gas（基础费 + 负载字节），再执行转账 / 部署 / 调用
+//! 3. OutOfGas：回滚执行期修改，gas_limit 全部计费
+//!    Revert：回滚执行期修改，按已用 gas 计费，不享受存储退款
+//!    Success：已用 gas 减去封顶退款
+//! 4. 按实际单价 min(max_fee, base_fee + tip) 结算：多余预付款退还发送方，
+//!    小费支付给出块者，base fee 部分销毁或转入 FeeMarket 配置的地址
+//!
+//! EVM 交易（部署与调用 EVM 合约）由 revm 按以太坊规则完成上述流程，见 evm 模块。
+use crate::error::ExecError;
+use crate::evm;
+use crate::fee_market::FeeMarket;
+use crate::gas::{GasMeter, GasSchedule};
+use crate::staking::{EpochTransition, Staking};
+use crate::state::State;
+use crate::vm::{CallContext, Vm, VmError};
+use ark_types::{
+    sha256, Action, Address, Amount, ChainParams, ExecStatus, FeatureGates, Gas, Genesis, Log,
+    Receipt, SignedTransaction, Transaction, UpgradePlan, H256,
+};
+
+/// 区块执行环境。
+#[derive(Clone, Debug)]
+pub struct BlockEnv {
+    pub chain_id: String,
+    pub height: u64,
+    /// 父区块哈希（EVM 的 BLOCKHASH 使用）
+    pub parent_hash: H256,
+    pub timestamp_ms: u64,
+    pub proposer: Address,
+    pub gas_limit: Gas,
+    pub base_fee: Amount,
+}
+
+/// 单笔交易执行结果（尚未带区块内累计 gas）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxOutcome {
+    pub status: ExecStatus,
+    pub gas_used: Gas,
+    pub effective_gas_price: Amount,
+    /// 本交易销毁（或转入 base fee 接收地址）的金额
+    pub base_fee_paid: Amount,
+    pub contract_address: Option<Address>,
+    pub revert_reason: Option<String>,
+    pub output: Vec<u8>,
+    /// 执行成功时合约发出的事件日志（失败时为空）
+    pub logs: Vec<Log>,
+}
+
+impl TxOutcome {
+    pub fn into_receipt(self, tx_hash: H256, cumulative_gas_used: Gas) -> Receipt {
+        Receipt {
+            tx_hash,
+            status: self.status,
+            gas_used: self.gas_used,
+            cumulative_gas_used,
+            effective_gas_price: self.effective_gas_price,
+            contract_address: self.contract_address,
+            revert_reason: self.revert_reason,
+            logs: self.logs,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Executor {
+    /// 构造时的链参数（治理修改后的参数见 at()）
+    pub params: ChainParams,
+    pub schedule: GasSchedule,
+    pub fee_market: FeeMarket,
+    pub max_code_size: usize,
+    pub staking: Staking,
+    /// 创世开启的特性门（升级另行开启的见 feature_gates()）
+    pub feature_gates: FeatureGates,
+    /// 创世预定的协议升级
+    pub upgrades: Vec<UpgradePlan>,
+}
+
+impl Executor {
+    pub fn new(params: &ChainParams) -> Self {
+        Self {
+            params: params.clone(),
+            schedule: GasSchedule::default(),
+            fee_market: FeeMarket::new(params),
+            max_code_size: params.wasm.max_code_size,
+            staking: Staking::new(params),
+            feature_gates: FeatureGates::default(),
+            upgrades: Vec::new(),
+        }
+    }
+
+    /// 按创世配置构造，带上特性门与预定升级。
+    pub fn for_genesis(genesis: &Genesis) -> Self {
+        Self {
+            feature_gates: genesis.feature_gates.clone(),
+            upgrades: genesis.upgrades.clone(),
+            ..Self::new(&genesis.params)
+        }
+    }
+
+    /// 固有 gas：基础费 + 负载字节费（部署、质押、治理另计基础费）。
+    pub fn intrinsic_gas(&self, action: &Action) -> Gas {
+        let g = &self.schedule;
+        let data = action.payload().len() as Gas * g.tx_data_byte;
+        let extra = match action {
+            Action::Deploy { .. } | Action::EvmCreate { .. } => g.deploy_base,
+            Action::Staking { .. } => g.staking_op,
+            Action::Governance { .. } => g.governance_op,
+            _ => 0,
+        };
+        let gas = g.tx_base + data + extra;
+        // EVM 部署另按以太坊规则计费（含 EIP-3860），取两者中较大的
+        match action {
+            Action::EvmCreate { code } => gas.max(evm::create_intrinsic_gas(code)),
+            _ => gas,
+        }
+    }
+
+    /// 上链前校验（不修改状态）。
+    pub fn validate(
+        &self,
+        state: &State,
+        stx: &SignedTransaction,
+        env: &BlockEnv,
+    ) -> Result<(), ExecError> {
+        self.check_eth(state, stx)?;
+        self.validate_as(state, &stx.sender(), &stx.tx, env)
+    }
+
+    /// 以太坊交易只在 evm 特性门开启后接受。
+    fn check_eth(&self, state: &State, stx: &SignedTransaction) -> Result<(), ExecError> {
+        if stx.is_eth() && !self.feature_gates(state).evm {
+            return Err(ExecError::FeatureDisabled("evm"));
+        }
+        Ok(())
+    }
+
+    /// 以给定发送方校验交易（签名由调用方负责，模拟执行时不需要签名）。
+    pub fn validate_as(
+        &self,
+        state: &State,
+        sender: &Address,
+        tx: &Transaction,
+        env: &BlockEnv,
+    ) -> Result<(), ExecError> {
+        if tx.chain_id != env.chain_id {
+            return Err(ExecError::ChainIdMismatch {
+                expected: env.chain_id.clone(),
+                got: tx.chain_id.clone(),
+            });
+        }
+        if matches!(tx.action, Action::EvmCreate { .. }) && !self.feature_gates(state).evm {
+            return Err(ExecError::FeatureDisabled("evm"));
+        }
+        if tx.gas_limit > env.gas_limit {
+            return Err(ExecError::ExceedsBlockGasLimit {
+                gas_limit: tx.gas_limit,
+                block_limit: env.gas_limit,
+            });
+        }
+        let required = self.intrinsic_gas(&tx.action);
+        if tx.gas_limit < required {
+            return Err(ExecError::IntrinsicGas {
+                limit: tx.gas_limit,
+                required,
+            });
+        }
+        if tx.max_priority_fee_per_gas > tx.max_fee_per_gas {
+            return Err(ExecError::TipAboveFeeCap {
+                tip: tx.max_priority_fee_per_gas,
+                max_fee: tx.max_fee_per_gas,
+            });
+        }
+        if tx.max_fee_per_gas < env.base_fee {
+            return Err(ExecError::FeeCapTooLow {
+                max_fee: tx.max_fee_per_gas,
+                base_fee: env.base_fee,
+            });
+        }
+        let expected = state.nonce(sender);
+        if tx.nonce < expected {
+            return Err(ExecError::NonceTooLow {
+                expected,
+                got: tx.nonce,
+            });
+        }
+        if tx.nonce > expected {
+            return Err(ExecError::NonceTooHigh {
+                expected,
+                got: tx.nonce,
+            });
+        }
+        let need = max_cost(tx.gas_limit, tx.max_fee_per_gas, tx.value)?;
+        let have = state.balance(sender);
+        if have < need {
+            return Err(ExecError::InsufficientFunds { need, have });
+        }
+        Ok(())
+    }
+
+    /// 执行单笔交易。校验失败返回 Err 且状态不变；执行失败体现在 TxOutcome 中。
+    pub fn apply(
+        &self,
+        state: &mut State,
+        stx: &SignedTransaction,
+        env: &BlockEnv,
+    ) -> Result<TxOutcome, ExecError> {
+        self.check_eth(state, stx)?;
+        self.apply_as(state, stx.sender(), &stx.tx, env)
+    }
+
+    /// 以给定发送方执行交易，语义同 apply。
+    pub fn apply_as(
+        &self,
+        state: &mut State,
+        sender: Address,
+        tx: &Transaction,
+        env: &BlockEnv,
+    ) -> Result<TxOutcome, ExecError> {
+        self.validate_as(state, &sender, tx, env)?;
+        if evm::handles(state, &tx.action) {
+            return Ok(self.apply_evm(state, sender, tx, env));
+        }
+
+        let prepaid = tx.gas_limit as Amount * tx.max_fee_per_gas;
+        let price = tx
+            .effective_gas_price(env.base_fee)
+            .expect("fee cap validated");
+        let nonce = state.nonce(&sender);
+        state.sub_balance(&sender, prepaid);
+        state.increment_nonce(&sender);
+
+        let mut meter = GasMeter::new(tx.gas_limit);
+        meter
+            .charge(self.intrinsic_gas(&tx.action))
+            .expect("intrinsic gas validated");
+
+        let cp = state.checkpoint();
+        let mut contract_address = None;
+        let result = match &tx.action {
+            Action::Transfer { to } => self.transfer(state, &sender, to, tx.value),
+            Action::Deploy { code } => {
+                let addr = contract_address_for(&sender, nonce);
+                contract_address = Some(addr);
+                self.deploy(state, &mut meter, &sender, &addr, code, tx.value)
+            }
+            Action::EvmCreate { .. } => unreachable!("EVM deployments run on revm"),
+            // 目标为 EVM 合约的调用已交给 revm
+            Action::Call { to, input } => {
+                self.call_native(state, &mut meter, &sender, to, tx.value, input)
+            }
+            Action::Staking { op } => self
+                .staking
+                .apply(state, &sender, tx.value, op, env.height)
+                .map(|_| Vec::new())
+                .map_err(|e| VmError::Revert(e.to_string())),
+            Action::Governance { op } => self
+                .apply_governance(state, &sender, tx.value, op, env.height)
+                .map(|_| Vec::new())
+                .map_err(|e| VmError::Revert(e.to_string())),
+        };
+
+        let (status, gas_used, revert_reason, output) = match result {
+            Ok(output) => (
+                ExecStatus::Success,
+                meter.finalize(self.schedule.max_refund_quotient),
+                None,
+                output,
+            ),
+            Err(VmError::OutOfGas) => {
+                state.revert_to(cp);
+                contract_address = None;
+                (ExecStatus::OutOfGas, meter.limit(), None, Vec::new())
+            }
+            Err(e) => {
+                state.revert_to(cp);
+                contract_address = None;
+                (
+                    ExecStatus::Reverted,
+                    meter.used(),
+                    Some(e.to_string()),
+                    Vec::new(),
+                )
+            }
+        };
+
+        let base_fee_paid = gas_used as Amount * env.base_fee;
+        let tip_paid = gas_used as Amount * (price - env.base_fee);
+        state.add_balance(&sender, prepaid - base_fee_paid - tip_paid);
+        state.add_balance(&env.proposer, tip_paid);
+        if let Some(recipient) = &self.fee_market.base_fee_recipient {
+            state.add_balance(recipient, base_fee_paid);
+        }
+
+        Ok(TxOutcome {
+            status,
+            gas_used,
+            effective_gas_price: price,
+            base_fee_paid,
+            contract_address,
+            revert_reason,
+            output,
+            logs: state.take_logs(),
+        })
+    }
+
+    /// 区块收尾：计票与执行到期的治理提案；若为 epoch 最后一个区块，结算质押奖励并轮换验证者集合。
+    pub fn end_block(&self, state: &mut State, env: &BlockEnv) -> Option<EpochTransition> {
+        self.end_block_governance(state, env.height);
+        if !self.staking.is_epoch_end(env.height) {
+            return None;
+        }
+        Some(
+            self.staking
+                .end_epoch(state, self.staking.epoch_of(env.height)),
+        )
+    }
+
+    fn transfer(
+        &self,
+        state: &mut State,
+        from: &Address,
+        to: &Address,
+        value: Amount,
+    ) -> Result<Vec<u8>, VmError> {
+        if !state.transfer(from, to, value) {
+            return Err(VmError::Revert("insufficient balance for value".into()));
+        }
+        Ok(Vec::new())
+    }
+
+    fn deploy(
+        &self,
+        state: &mut State,
+        meter: &mut GasMeter,
+        sender: &Address,
+        addr: &Address,
+        code: &[u8],
+        value: Amount,
+    ) -> Result<Vec<u8>, VmError> {
+        if code.len() > self.max_code_size {
+            return Err(VmError::Revert(format!(
+                "code size {} exceeds limit {}",
+                code.len(),
+                self.max_code_size
+            )));
+        }
+        if evm::is_evm_code(code) {
+            return Err(VmError::Revert(
+                "code must not start with the EVM code marker".into(),
+            ));
+        }
+        if state.code(addr).is_some() {
+            return Err(VmError::Revert("contract address collision".into()));
+        }
+        meter.charge(code.len() as Gas * self.schedule.deploy_byte)?;
+        self.transfer(state, sender, addr, value)?;
+        state.set_code(addr, code.to_vec());
+        Ok(Vec::new())
+    }
+
+    /// 调用原生 VM 合约；目标没有代码时只转账。
+    fn call_native(
+        &self,
+        state: &mut State,
+        meter: &mut GasMeter,
+        sender: &Address,
+        to: &Address,
+        value: Amount,
+        input: &[u8],
+    ) -> Result<Vec<u8>, VmError> {
+        self.transfer(state, sender, to, value)?;
+        let Some(code) = state.code(to).map(<[u8]>::to_vec) else {
+            return Ok(Vec::new());
+        };
+        let ctx = CallContext {
+            address: *to,
+            caller: *sender,
+            value,
+            input,
+        };
+        Vm::new(&self.schedule).run(&code, &ctx, state, meter)
+    }
+
+    /// EVM 交易：revm 完成 nonce、转账、执行与计费（小费付给出块者），这里只处理 base fee 的去向。
+    fn apply_evm(
+        &self,
+        state: &mut State,
+        sender: Address,
+        tx: &Transaction,
+        env: &BlockEnv,
+    ) -> TxOutcome {
+        let price = tx
+            .effective_gas_price(env.base_fee)
+            .expect("fee cap validated");
+        let run =
+            evm::transact(state, env, &sender, tx, self.max_code_size).unwrap_or_else(|reason| {
+                // 通过了本链校验却被 revm 拒绝：与其他失败交易一样 nonce + 1、gas_limit 全部计费
+                let fee = tx.gas_limit as Amount * price;
+                state.sub_balance(&sender, fee);
+                state.increment_nonce(&sender);
+                state.add_balance(&env.proposer, fee - tx.gas_limit as Amount * env.base_fee);
+                evm::Execution {
+                    status: ExecStatus::Reverted,
+                    gas_used: tx.gas_limit,
+                    contract_address: None,
+                    revert_reason: Some(reason),
+                    output: Vec::new(),
+                }
+            });
+        let base_fee_paid = run.gas_used as Amount * env.base_fee;
+        if let Some(recipient) = &self.fee_market.base_fee_recipient {
+            state.add_balance(recipient, base_fee_paid);
+        }
+        TxOutcome {
+            status: run.status,
+            gas_used: run.gas_used,
+            effective_gas_price: price,
+            base_fee_paid,
+            contract_address: run.contract_address,
+            revert_reason: run.revert_reason,
+            output: run.output,
+            logs: state.take_logs(),
+        }
+    }
+}
+
+/// 交易最大花费：gas_limit * max_fee_per_gas + value。
+pub fn max_cost(gas_limit: Gas, max_fee: Amount, value: Amount) -> Result<Amount, ExecError> {
+    (gas_limit as Amount)
+        .checked_mul(max_fee)
+        .and_then(|f| f.checked_add(value))
+        .ok_or(ExecError::FeeOverflow)
+}
+
+/// 校验交易签名：原生交易对 JSON 摘要验签；以太坊交易须能由其 EIP-1559 签名摘要恢复出所带的非压缩公钥。
+pub fn verify_signature(stx: &SignedTransaction) -> bool {
+    if !stx.is_eth() {
+        return ark_crypto::verify(&stx.pubkey, &stx.tx.signing_hash().0, &stx.signature);
+    }
+    let Some(eth) = stx.eth_transaction() else {
+        return false;
+    };
+    ark_crypto::recover(&eth.signing_hash().0, &stx.signature)
+        .is_some_and(|pubkey| pubkey[..] == stx.pubkey[..])
+}
+
+/// 合约地址：Sha256(sender || nonce_be) 前 20 字节。
+pub fn contract_address_for(sender: &Address, nonce: u64) -> Address {
+    let mut buf = Vec::with_capacity(28);
+    buf.extend_from_slice(sender.as_bytes());
+    buf.extend_from_slice(&nonce.to_be_bytes());
+    let h = sha256(&buf);
+    let mut out = [0u8; 20];
+    out.copy_from_slice(&h.0[..20]);
+    Address(out)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::vm::op;
+    use ark_types::Transaction;
+
+    pub(crate) const PRICE: Amount = 10;
+    pub(crate) const BASE_FEE: Amount = 8;
+
+    pub(crate) fn params() -> ChainParams {
+        let raw = include_str!("../../../config/genesis.json");
+        ark_types::Genesis::from_json(raw).unwrap().params
+    }
+
+    pub(crate) fn env() -> BlockEnv {
+        BlockEnv {
+            chain_id: "ark-astra-1".into(),
+            height: 1,
+            parent_hash: H256::ZERO,
+            timestamp_ms: 0,
+            proposer: Address([9u8; 20]),
+            gas_limit: 20_000_000,
+            base_fee: BASE_FEE,
+        }
+    }
+
+    pub(crate) fn signed(key: u8, nonce: u64, action: Action, gas_limit: Gas) -> SignedTransaction {
+        SignedTransaction {
+            tx: Transaction {
+                chain_id: "ark-astra-1".into(),
+                nonce,
+                action,
+                value: 0,
+                gas_limit,
+                max_fee_per_gas: PRICE,
+                max_priority_fee_per_gas: 2,
+            },
+            pubkey: vec![key; 33],
+            signature: Vec::new(),
+        }
+    }
+
+    pub(crate) fn funded(keys: &[u8]) -> State {
+        let mut s = State::new();
+        for k in keys {
+            s.add_balance(&Address::from_pubkey(&[*k; 33]), 1_000_000_000_000);
+        }
+        s.commit();
+        s
+    }
+
+    #[test]
+    fn transfer_burns_base_fee_and_tips_proposer() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        let to = Address([7u8; 20]);
+        let mut stx = signed(1, 0, Action::Transfer { to }, 50_000);
+        stx.tx.value = 5;
+        let out = exec.apply(&mut state, &stx, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(out.gas_used, 21_000);
+        assert_eq!(out.effective_gas_price, PRICE);
+        assert_eq!(out.base_fee_paid, 21_000 * BASE_FEE);
+        assert_eq!(state.balance(&to), 5);
+        assert_eq!(state.balance(&env().proposer), 21_000 * (PRICE - BASE_FEE));
+        assert_eq!(
+            state.balance(&stx.sender()),
+            1_000_000_000_000 - 5 - 21_000 * PRICE
+        );
+    }
+
+    #[test]
+    fn out_of_gas_reverts_state_and_consumes_limit() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        let code = vec![op::PUSH, 1, 1, op::PUSH, 1, 1, op::SSTORE, op::STOP];
+        let deploy = signed(1, 0, Action::Deploy { code }, 200_000);
+        let addr = exec
+            .apply(&mut state, &deploy, &env())
+            .unwrap()
+            .contract_address
+            .unwrap();
+
+        let call = signed(
+            1,
+            1,
+            Action::Call {
+                to: addr,
+                input: vec![],
+            },
+            30_000,
+        );
+        let out = exec.apply(&mut state, &call, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::OutOfGas);
+        assert_eq!(out.gas_used, 30_000);
+        assert!(state.storage(&addr, &H256::from_u128(1)).is_zero());
+        assert_eq!(state.nonce(&call.sender()), 2);
+    }
+
+    #[test]
+    fn clearing_storage_earns_capped_refund() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        // 输入第 0 字为要写入的值，写到键 1
+        let code = vec![op::PUSH, 1, 0, op::CALLDATALOAD, op::PUSH, 1, 1, op::SSTORE];
+        let deploy = signed(1, 0, Action::Deploy { code }, 200_000);
+        let addr = exec
+            .apply(&mut state, &deploy, &env())
+            .unwrap()
+            .contract_address
+            .unwrap();
+
+        let mut input = vec![0u8; 16];
+        input[15] = 1;
+        let set = signed(1, 1, Action::Call { to: addr, input }, 100_000);
+        exec.apply(&mut state, &set, &env()).unwrap();
+
+        let clear = signed(
+            1,
+            2,
+            Action::Call {
+                to: addr,
+                input: vec![0u8; 16],
+            },
+            100_000,
+        );
+        let out = exec.apply(&mut state, &clear, &env()).unwrap();
+        let raw = 21_000 + 16 * 16 + 2 + 3 + 2 + 5_000;
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(out.gas_used, raw - 4_800);
+    }
+
+    #[test]
+    fn revert_keeps_used_gas_only() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        let code = vec![op::PUSH, 1, 42, op::REVERT];
+        let deploy = signed(1, 0, Action::Deploy { code }, 200_000);
+        let addr = exec
+            .apply(&mut state, &deploy, &env())
+            .unwrap()
+            .contract_address
+            .unwrap();
+        let call = signed(
+            1,
+            1,
+            Action::Call {
+                to: addr,
+                input: vec![],
+            },
+            100_000,
+        );
+        let out = exec.apply(&mut state, &call, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Reverted);
+        assert_eq!(out.gas_used, 21_000 + 2 + 2);
+        assert_eq!(
+            out.revert_reason.as_deref(),
+            Some("reverted: revert code 42")
+        );
+    }
+
+    #[test]
+    fn tip_is_capped_by_fee_cap_and_base_fee_can_go_to_treasury() {
+        let mut exec = Executor::new(&params());
+        let treasury = Address([5u8; 20]);
+        exec.fee_market.base_fee_recipient = Some(treasury);
+        let mut state = funded(&[1]);
+        let mut stx = signed(1, 0, Action::Transfer { to: treasury }, 21_000);
+        stx.tx.max_fee_per_gas = 9;
+        stx.tx.max_priority_fee_per_gas = 5;
+        let out = exec.apply(&mut state, &stx, &env()).unwrap();
+        assert_eq!(out.effective_gas_price, 9);
+        assert_eq!(state.balance(&env().proposer), 21_000);
+        assert_eq!(state.balance(&treasury), 21_000 * BASE_FEE);
+
+        let mut low = signed(1, 1, Action::Transfer { to: treasury }, 21_000);
+        low.tx.max_fee_per_gas = BASE_FEE - 1;
+        low.tx.max_priority_fee_per_gas = 0;
+        assert!(matches!(
+            exec.validate(&state, &low, &env()),
+            Err(ExecError::FeeCapTooLow { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_rejects_bad_nonce_and_low_gas() {
+        let exec = Executor::new(&params());
+        let state = funded(&[1]);
+        let to = Address([7u8; 20]);
+        let stx = signed(1, 3, Action::Transfer { to }, 21_000);
+        assert!(matches!(
+            exec.validate(&state, &stx, &env()),
+            Err(ExecError::NonceTooHigh { .. })
+        ));
+        let stx = signed(1, 0, Action::Transfer { to }, 20_999);
+        assert!(matches!(
+            exec.validate(&state, &stx, &env()),
+            Err(ExecError::IntrinsicGas { .. })
+        ));
+    }
+
+    #[test]
+    fn staking_action_moves_stake_and_reverts_on_error() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1, 2]);
+        let mut pubkey = vec![0x02];
+        pubkey.extend_from_slice(&[5u8; 32]);
+        let op = ark_types::StakingOp::CreateValidator {
+            pubkey,
+            commission_bps: 100,
+        };
+        let mut create = signed(1, 0, Action::Staking { op }, 100_000);
+        create.tx.value = 2_000_000_000;
+        let out = exec.apply(&mut state, &create, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(out.gas_used, 21_000 + 20_000);
+        let operator = create.sender();
+        assert_eq!(state.validator(&operator).unwrap().stake, 2_000_000_000);
+        assert_eq!(state.balance(&crate::staking::STAKING_POOL), 2_000_000_000);
+
+        let op = ark_types::StakingOp::Undelegate {
+            validator: operator,
+            amount: 1,
+        };
+        let bad = signed(2, 0, Action::Staking { op }, 100_000);
+        let out = exec.apply(&mut state, &bad, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Reverted);
+        assert!(out
+            .revert_reason
+            .unwrap()
+            .contains("insufficient delegation"));
+    }
+
+    #[test]
+    fn evm_create_needs_the_gate_and_calls_dispatch_to_the_evm() {
+        use revm::interpreter::opcode as e;
+        #[rustfmt::skip]
+        let runtime = [
+            e::PUSH0, e::CALLDATALOAD, e::PUSH0, e::SSTORE,
+            e::PUSH0, e::SLOAD, e::PUSH0, e::MSTORE,
+            e::PUSH1, 32, e::PUSH0, e::RETURN,
+        ];
+        #[rustfmt::skip]
+        let mut init = vec![
+            e::PUSH1, runtime.len() as u8, e::PUSH1, 10, e::PUSH0, e::CODECOPY,
+            e::PUSH1, runtime.len() as u8, e::PUSH0, e::RETURN,
+        ];
+        init.extend_from_slice(&runtime);
+
+        let mut exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        let create = signed(1, 0, Action::EvmCreate { code: init }, 200_000);
+        assert_eq!(
+            exec.validate(&state, &create, &env()),
+            Err(ExecError::FeatureDisabled("evm"))
+        );
+
+        exec.feature_gates.evm = true;
+        let out = exec.apply(&mut state, &create, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        let addr = out.contract_address.unwrap();
+        assert_eq!(addr, evm::contract_address(&create.sender(), 0));
+        assert_eq!(
+            state.code(&addr).unwrap(),
+            [evm::EVM_MAGIC, &runtime[..]].concat()
+        );
+
+        let mut input = vec![0u8; 32];
+        input[31] = 7;
+        let call = signed(1, 1, Action::Call { to: addr, input }, 100_000);
+        let out = exec.apply(&mut state, &call, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(out.output[31], 7);
+        assert_eq!(state.storage(&addr, &H256::default()).low_u128(), 7);
+
+        let fake = signed(
+            1,
+            2,
+            Action::Deploy {
+                code: evm::EVM_MAGIC.to_vec(),
+            },
+            100_000,
+        );
+        let out = exec.apply(&mut state, &fake, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Reverted);
+    }
+
+    #[test]
+    fn eth_transactions_use_ethereum_sender_hash_and_signature() {
+        use ark_crypto::{SecretKey, Signer};
+        use ark_types::eth::{self, Eip1559Transaction};
+
+        let key = SecretKey::from_bytes(&[3u8; 32]).unwrap();
+        let to = Address([7u8; 20]);
+        let mut eth_tx = Eip1559Transaction {
+            chain_id: eth::eth_chain_id("ark-astra-1"),
+            nonce: 0,
+            max_priority_fee_per_gas: 2,
+            max_fee_per_gas: PRICE,
+            gas_limit: 21_000,
+            to: Some(to),
+            value: 5,
+            data: Vec::new(),
+            signature: [0; 65],
+        };
+        eth_tx.signature = key.sign_recoverable(&eth_tx.signing_hash().0).unwrap();
+        let stx = SignedTransaction {
+            tx: eth_tx.to_native("ark-astra-1"),
+            pubkey: key.uncompressed_public_key().to_vec(),
+            signature: eth_tx.signature.to_vec(),
+        };
+        assert!(verify_signature(&stx));
+        assert_eq!(
+            stx.sender(),
+            eth::address_from_pubkey(&key.uncompressed_public_key())
+        );
+        assert_ne!(stx.sender(), Address::from_pubkey(&key.public_key()));
+        assert_eq!(stx.hash(), eth::keccak256(&eth_tx.encode()));
+
+        let mut tampered = stx.clone();
+        tampered.tx.value = 6;
+        assert!(!verify_signature(&tampered));
+        let mut replayed = stx.clone();
+        replayed.tx.chain_id = "ark-devnet".into();
+        assert!(!verify_signature(&replayed));
+
+        let mut exec = Executor::new(&params());
+        let mut state = State::new();
+        state.add_balance(&stx.sender(), 1_000_000_000);
+        assert_eq!(
+            exec.apply(&mut state, &stx, &env()),
+            Err(ExecError::FeatureDisabled("evm"))
+        );
+        exec.feature_gates.evm = true;
+        let out = exec.apply(&mut state, &stx, &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(out.gas_used, 21_000);
+        assert_eq!(state.balance(&to), 5);
+        assert_eq!(state.nonce(&stx.sender()), 1);
+    }
+
+    #[test]
+    fn logs_land_in_receipts_and_are_dropped_on_revert() {
+        // 发出 LOG1(topic 7, data 42)；带 calldata 时随后回滚
+        #[rustfmt::skip]
+        let code = vec![
+            op::PUSH, 1, 7, op::PUSH, 1, 42, op::LOG, 1,
+            op::CALLDATASIZE, op::PUSH, 1, 14, op::JUMPI, op::STOP,
+            op::JUMPDEST, op::PUSH, 1, 1, op::REVERT,
+        ];
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        let deploy = signed(1, 0, Action::Deploy { code }, 200_000);
+        let addr = exec
+            .apply(&mut state, &deploy, &env())
+            .unwrap()
+            .contract_address
+            .unwrap();
+
+        let call = |nonce, input| signed(1, nonce, Action::Call { to: addr, input }, 100_000);
+        let out = exec
+            .apply(&mut state, &call(1, Vec::new()), &env())
+            .unwrap();
+        assert_eq!(out.status, ExecStatus::Success);
+        assert_eq!(
+            out.logs,
+            vec![Log {
+                address: addr,
+                topics: vec![H256::from_u128(7)],
+                data: 42u128.to_be_bytes().to_vec(),
+            }]
+        );
+        let receipt = out.into_receipt(H256::ZERO, 0);
+        assert_eq!(receipt.logs.len(), 1);
+
+        let out = exec.apply(&mut state, &call(2, vec![1]), &env()).unwrap();
+        assert_eq!(out.status, ExecStatus::Reverted);
+        assert!(out.logs.is_empty());
+        assert!(state.take_logs().is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+//! 动态 base fee 市场（EIP-1559 风格）。
+//!
+//! - 每个区块根据父块 gas 使用量相对目标值（gas_limit / elasticity）调整 base fee，
+//!   单块最大变化 1 / base_fee_change_denominator，且不低于 gas_price_min
+//! - base fee 部分销毁或转入配置地址；小费（priority fee）归出块者
+//! - FeeHistory 记录近期区块的 base fee、gas 使用率与小费分布，供钱包估算费用
+use ark_types::{Address, Amount, ChainParams, Gas, Receipt, SignedTransaction};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 默认保留的历史区块数。
+pub const DEFAULT_HISTORY_BLOCKS: usize = 1024;
+/// 单次 fee history 查询的最大区块数。
+pub const MAX_HISTORY_QUERY: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub struct FeeMarket {
+    pub elasticity_multiplier: u64,
+    pub base_fee_change_denominator: u64,
+    pub min_base_fee: Amount,
+    pub base_fee_recipient: Option<Address>,
+}
+
+impl FeeMarket {
+    pub fn new(params: &ChainParams) -> Self {
+        let fm = &params.fee_market;
+        Self {
+            elasticity_multiplier: fm.elasticity_multiplier.max(1),
+            base_fee_change_denominator: fm.base_fee_change_denominator.max(1),
+            min_base_fee: params.gas_price_min,
+            base_fee_recipient: fm.base_fee_recipient,
+        }
+    }
+
+    pub fn gas_target(&self, gas_limit: Gas) -> Gas {
+        gas_limit / self.elasticity_multiplier
+    }
+
+    /// 由父块计算下一块的 base fee。
+    pub fn next_base_fee(
+        &self,
+        parent_base_fee: Amount,
+        parent_gas_used: Gas,
+        parent_gas_limit: Gas,
+    ) -> Amount {
+        let target = self.gas_target(parent_gas_limit) as Amount;
+        let used = parent_gas_used as Amount;
+        let denom = self.base_fee_change_denominator as Amount;
+        let next = if target == 0 || used == target {
+            parent_base_fee
+        } else if used > target {
+            let delta = (parent_base_fee.saturating_mul(used - target) / target / denom).max(1);
+            parent_base_fee.saturating_add(delta)
+        } else {
+            let delta = parent_base_fee.saturating_mul(target - used) / target / denom;
+            parent_base_fee.saturating_sub(delta)
+        };
+        next.max(self.min_base_fee)
+    }
+}
+
+/// 单个区块的费用摘要。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFees {
+    pub height: u64,
+    pub base_fee: Amount,
+    pub gas_used: Gas,
+    pub gas_limit: Gas,
+    /// (每单位小费, 该交易 gas 用量)，按小费升序
+    pub tips: Vec<(Amount, Gas)>,
+}
+
+impl BlockFees {
+    pub fn from_block(
+        height: u64,
+        base_fee: Amount,
+        gas_limit: Gas,
+        txs: &[SignedTransaction],
+        receipts: &[Receipt],
+    ) -> Self {
+        let mut tips: Vec<(Amount, Gas)> = txs
+            .iter()
+            .zip(receipts)
+            .map(|(t, r)| (t.tx.effective_tip(base_fee).unwrap_or(0), r.gas_used))
+            .collect();
+        tips.sort_unstable();
+        Self {
+            height,
+            base_fee,
+            gas_used: receipts.last().map(|r| r.cumulative_gas_used).unwrap_or(0),
+            gas_limit,
+            tips,
+        }
+    }
+
+    pub fn gas_used_ratio(&self) -> f64 {
+        if self.gas_limit == 0 {
+            0.0
+        } else {
+            self.gas_used as f64 / self.gas_limit as f64
+        }
+    }
+
+    /// 按 gas 加权的小费分位数（percentile 取值 0..=100）。
+    pub fn tip_percentile(&self, percentile: f64) -> Amount {
+        let total: Gas = self.tips.iter().map(|(_, g)| *g).sum();
+        if total == 0 {
+            return 0;
+        }
+        let threshold = (total as f64 * percentile.clamp(0.0, 100.0) / 100.0).ceil() as Gas;
+        let mut acc = 0;
+        for (tip, gas) in &self.tips {
+            acc += gas;
+            if acc >= threshold {
+                return *tip;
+            }
+        }
+        self.tips.last().map(|(t, _)| *t).unwrap_or(0)
+    }
+}
+
+/// fee history 查询结果（对应 eth_feeHistory 语义）。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeeHistoryResult {
+    pub oldest_block: u64,
+    /// 长度为 区块数 + 1，最后一项为下一块的 base fee
+    pub base_fee_per_gas: Vec<Amount>,
+    pub gas_used_ratio: Vec<f64>,
+    /// 每块在各请求分位数下的小费
+    pub reward: Vec<Vec<Amount>>,
+}
+
+/// 钱包建议费用。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSuggestion {
+    pub base_fee: Amount,
+    pub max_priority_fee_per_gas: Amount,
+    pub max_fee_per_gas: Amount,
+}
+
+#[derive(Clone, Debug)]
+pub struct FeeHistory {
+    market: FeeMarket,
+    capacity: usize,
+    blocks: VecDeque<BlockFees>,
+}
+
+impl FeeHistory {
+    pub fn new(market: FeeMarket, capacity: usize) -> Self {
+        Self {
+            market,
+            capacity: capacity.max(1),
+            blocks: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, fees: BlockFees) {
+        if self.blocks.len() == self.capacity {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(fees);
+    }
+
+    pub fn latest(&self) -> Option<&BlockFees> {
+        self.blocks.back()
+    }
+
+    /// 下一块的 base fee（无历史时返回 None）。
+    pub fn next_base_fee(&self) -> Option<Amount> {
+        self.latest().map(|b| {
+            self.market
+                .next_base_fee(b.base_fee, b.gas_used, b.gas_limit)
+        })
+    }
+
+    /// 查询截止 newest（含）之前的 block_count 个区块的费用历史。
+    pub fn query(
+        &self,
+        block_count: usize,
+        newest: Option<u64>,
+        percentiles: &[f64],
+    ) -> Option<FeeHistoryResult> {
+        let newest = newest.unwrap_or(self.latest()?.height);
+        let count = block_count.min(MAX_HISTORY_QUERY);
+        let selected: Vec<&BlockFees> = self
+            .blocks
+            .iter()
+            .filter(|b| b.height <= newest)
+            .rev()
+            .take(count)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let last = *selected.last()?;
+        let mut base_fee_per_gas: Vec<Amount> = selected.iter().map(|b| b.base_fee).collect();
+        base_fee_per_gas.push(self.market.next_base_fee(
+            last.base_fee,
+            last.gas_used,
+            last.gas_limit,
+        ));
+        Some(FeeHistoryResult {
+            oldest_block: selected[0].height,
+            base_fee_per_gas,
+            gas_used_ratio: selected.iter().map(|b| b.gas_used_ratio()).collect(),
+            reward: selected
+                .iter()
+                .map(|b| percentiles.iter().map(|p| b.tip_percentile(*p)).collect())
+                .collect(),
+        })
+    }
+
+    /// 建议费用：小费取近 20 块 60 分位的中位数；max_fee = 2 * 下一块 base fee + 小费。
+    pub fn suggest(&self) -> Option<FeeSuggestion> {
+        let base_fee = self.next_base_fee()?;
+        let mut tips: Vec<Amount> = self
+            .blocks
+            .iter()
+            .rev()
+            .take(20)
+            .filter(|b| !b.tips.is_empty())
+            .map(|b| b.tip_percentile(60.0))
+            .collect();
+        tips.sort_unstable();
+        let tip = tips.get(tips.len() / 2).copied().unwrap_or(0);
+        Some(FeeSuggestion {
+            base_fee,
+            max_priority_fee_per_gas: tip,
+            max_fee_per_gas: base_fee.saturating_mul(2).saturating_add(tip),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market() -> FeeMarket {
+        FeeMarket {
+            elasticity_multiplier: 2,
+            base_fee_change_denominator: 8,
+            min_base_fee: 1,
+            base_fee_recipient: None,
+        }
+    }
+
+    #[test]
+    fn base_fee_tracks_utilization() {
+        let m = market();
+        // 目标 10M：满块 +12.5%，空块 -12.5%，恰好目标不变
+        assert_eq!(m.next_base_fee(1000, 20_000_000, 20_000_000), 1125);
+        assert_eq!(m.next_base_fee(1000, 0, 20_000_000), 875);
+        assert_eq!(m.next_base_fee(1000, 10_000_000, 20_000_000), 1000);
+        // 不低于下限，且高于目标时至少 +1
+        assert_eq!(m.next_base_fee(1, 0, 20_000_000), 1);
+        assert_eq!(m.next_base_fee(2, 10_000_001, 20_000_000), 3);
+    }
+
+    #[test]
+    fn history_query_and_suggestion() {
+        let mut h = FeeHistory::new(market(), 3);
+        for height in 1..=4u64 {
+            h.push(BlockFees {
+                height,
+                base_fee: 1000,
+                gas_used: 10_000_000,
+                gas_limit: 20_000_000,
+                tips: vec![(1, 21_000), (5, 21_000), (9, 42_000)],
+            });
+        }
+        let r = h.query(10, None, &[10.0, 50.0, 90.0]).unwrap();
+        assert_eq!(r.oldest_block, 2);
+        assert_eq!(r.base_fee_per_gas, vec![1000, 1000, 1000, 1000]);
+        assert_eq!(r.gas_used_ratio, vec![0.5, 0.5, 0.5]);
+        assert_eq!(r.reward[0], vec![1, 5, 9]);
+
+        let r = h.query(1, Some(3), &[]).unwrap();
+        assert_eq!(r.oldest_block, 3);
+
+        let s = h.suggest().unwrap();
+        assert_eq!(s.max_priority_fee_per_gas, 9);
+        assert_eq!(s.max_fee_per_gas, 2009);
+    }
+}
@@ -0,0 +1,151 @@
+//! Gas 计价表与计量器。
+//!
+//! - GasSchedule：原生操作（交易基础费、数据字节、部署、存储读写）与 VM 指令单价
+//! - GasMeter：单笔交易的 gas 上限、已用量与退款计数；超限即 OutOfGas
+use ark_types::Gas;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSchedule {
+    /// 每笔交易的基础费用（含一次原生转账）
+    pub tx_base: Gas,
+    /// 交易负载每字节
+    pub tx_data_byte: Gas,
+    /// 部署合约基础费用
+    pub deploy_base: Gas,
+    /// 部署代码每字节（存储成本）
+    pub deploy_byte: Gas,
+    /// 质押操作（创建验证者 / 委托 / 解除委托 / 提取）
+    pub staking_op: Gas,
+    /// 治理操作（提交提案 / 投票）
+    pub governance_op: Gas,
+    /// VM 基础指令（栈操作、环境读取）
+    pub vm_step: Gas,
+    /// 算术 / 比较 / 位运算
+    pub vm_arith: Gas,
+    /// 乘除取模
+    pub vm_mul: Gas,
+    /// 跳转
+    pub vm_jump: Gas,
+    /// 读存储
+    pub sload: Gas,
+    /// 写存储：零 -> 非零
+    pub sstore_set: Gas,
+    /// 写存储：非零 -> 任意
+    pub sstore_reset: Gas,
+    /// 写存储：非零 -> 零 的退款
+    pub sstore_clear_refund: Gas,
+    /// 退款上限 = gas_used / max_refund_quotient
+    pub max_refund_quotient: Gas,
+    /// 事件日志：基础费、每个主题、数据每字节
+    pub log: Gas,
+    pub log_topic: Gas,
+    pub log_byte: Gas,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self {
+            tx_base: 21_000,
+            tx_data_byte: 16,
+            deploy_base: 32_000,
+            deploy_byte: 200,
+            staking_op: 20_000,
+            governance_op: 20_000,
+            vm_step: 2,
+            vm_arith: 3,
+            vm_mul: 5,
+            vm_jump: 8,
+            sload: 800,
+            sstore_set: 20_000,
+            sstore_reset: 5_000,
+            sstore_clear_refund: 4_800,
+            max_refund_quotient: 5,
+            log: 375,
+            log_topic: 375,
+            log_byte: 8,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("out of gas")]
+pub struct OutOfGas;
+
+#[derive(Debug, Clone)]
+pub struct GasMeter {
+    limit: Gas,
+    used: Gas,
+    refund: Gas,
+}
+
+impl GasMeter {
+    pub fn new(limit: Gas) -> Self {
+        Self {
+            limit,
+            used: 0,
+            refund: 0,
+        }
+    }
+
+    /// 扣除 gas；超出上限时把已用量置为上限并返回 OutOfGas。
+    pub fn charge(&mut self, amount: Gas) -> Result<(), OutOfGas> {
+        match self.used.checked_add(amount) {
+            Some(next) if next <= self.limit => {
+                self.used = next;
+                Ok(())
+            }
+            _ => {
+                self.used = self.limit;
+                Err(OutOfGas)
+            }
+        }
+    }
+
+    pub fn add_refund(&mut self, amount: Gas) {
+        self.refund = self.refund.saturating_add(amount);
+    }
+
+    pub fn refund(&self) -> Gas {
+        self.refund
+    }
+
+    pub fn limit(&self) -> Gas {
+        self.limit
+    }
+
+    pub fn used(&self) -> Gas {
+        self.used
+    }
+
+    pub fn remaining(&self) -> Gas {
+        self.limit - self.used
+    }
+
+    /// 成功执行后的最终计费：已用量减去封顶后的退款。
+    pub fn finalize(&self, max_refund_quotient: Gas) -> Gas {
+        let cap = self.used / max_refund_quotient.max(1);
+        self.used - self.refund.min(cap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charge_past_limit_is_out_of_gas() {
+        let mut m = GasMeter::new(100);
+        m.charge(60).unwrap();
+        assert_eq!(m.charge(41), Err(OutOfGas));
+        assert_eq!(m.used(), 100);
+    }
+
+    #[test]
+    fn refund_is_capped() {
+        let mut m = GasMeter::new(100_000);
+        m.charge(50_000).unwrap();
+        m.add_refund(40_000);
+        assert_eq!(m.finalize(5), 40_000);
+    }
+}
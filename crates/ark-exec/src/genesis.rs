@@ -0,0 +1,226 @@
+//! 创世状态初始化：入账初始余额、安装预部署合约、登记创世验证者（抵押计入质押池并写入
+//! 0 号 epoch 验证者集合）、校验预定的协议升级，并生成 0 号区块。
+use crate::staking::{self, Staking, Validator};
+use crate::state::State;
+use ark_types::{Address, Amount, Block, BlockHeader, Bloom, Genesis, H256};
+use std::collections::BTreeSet;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum GenesisError {
+    #[error("duplicate genesis balance for {0}")]
+    DuplicateBalance(Address),
+    #[error("duplicate predeploy at {0}")]
+    DuplicatePredeploy(Address),
+    #[error("predeploy {address} code size {size} exceeds limit {limit}")]
+    CodeTooLarge {
+        address: Address,
+        size: usize,
+        limit: usize,
+    },
+    #[error("duplicate genesis validator {0}")]
+    DuplicateValidator(String),
+    #[error("invalid validator pubkey for {0}: expected 33-byte compressed secp256k1 key")]
+    InvalidPubkey(Address),
+    #[error("validator {operator} stake {stake} below min_stake {min}")]
+    InsufficientStake {
+        operator: Address,
+        stake: Amount,
+        min: Amount,
+    },
+    #[error("too many genesis validators: {count} > max_validators {max}")]
+    TooManyValidators { count: usize, max: u32 },
+    #[error("validator {0} commission exceeds 10000 bps")]
+    InvalidCommission(Address),
+    #[error("duplicate upgrade {0}")]
+    DuplicateUpgrade(String),
+    #[error("upgrade {0} must activate at a height above 0")]
+    UpgradeAtGenesis(String),
+    #[error("{0}")]
+    Time(String),
+}
+
+/// 按创世配置构建初始状态（已 commit）。
+pub fn genesis_state(g: &Genesis) -> Result<State, GenesisError> {
+    let mut state = State::new();
+
+    let mut seen = BTreeSet::new();
+    for b in &g.balances {
+        if !seen.insert(b.address) {
+            return Err(GenesisError::DuplicateBalance(b.address));
+        }
+        state.add_balance(&b.address, b.amount);
+    }
+
+    let limit = g.params.wasm.max_code_size;
+    let mut seen = BTreeSet::new();
+    for p in &g.predeploy {
+        if !seen.insert(p.address) {
+            return Err(GenesisError::DuplicatePredeploy(p.address));
+        }
+        if p.code.len() > limit {
+            return Err(GenesisError::CodeTooLarge {
+                address: p.address,
+                size: p.code.len(),
+                limit,
+            });
+        }
+        state.add_balance(&p.address, p.balance);
+        state.set_code(&p.address, p.code.clone());
+        for (k, v) in &p.storage {
+            state.set_storage(&p.address, *k, *v);
+        }
+    }
+
+    let staking = &g.params.staking;
+    if g.validators.len() > staking.max_validators as usize {
+        return Err(GenesisError::TooManyValidators {
+            count: g.validators.len(),
+            max: staking.max_validators,
+        });
+    }
+    let mut operators = BTreeSet::new();
+    let mut pubkeys = BTreeSet::new();
+    for v in &g.validators {
+        if v.pubkey.len() != 33 || !matches!(v.pubkey[0], 0x02 | 0x03) {
+            return Err(GenesisError::InvalidPubkey(v.operator));
+        }
+        if !operators.insert(v.operator) {
+            return Err(GenesisError::DuplicateValidator(v.operator.to_string()));
+        }
+        if !pubkeys.insert(v.pubkey.clone()) {
+            return Err(GenesisError::DuplicateValidator(hex_pubkey(&v.pubkey)));
+        }
+        if v.stake < staking.min_stake {
+            return Err(GenesisError::InsufficientStake {
+                operator: v.operator,
+                stake: v.stake,
+                min: staking.min_stake,
+            });
+        }
+        if v.commission_bps > staking::MAX_COMMISSION_BPS {
+            return Err(GenesisError::InvalidCommission(v.operator));
+        }
+        state.set_validator(Validator {
+            operator: v.operator,
+            pubkey: v.pubkey.clone(),
+            stake: v.stake,
+            commission_bps: v.commission_bps,
+        });
+        staking::record_genesis_stake(&mut state, &v.operator, v.stake);
+    }
+    // 特性门名称不在此校验：允许预定本版本尚未实现的升级
+    let mut names = BTreeSet::new();
+    for u in &g.upgrades {
+        if !names.insert(u.name.as_str()) {
+            return Err(GenesisError::DuplicateUpgrade(u.name.clone()));
+        }
+        if u.height == 0 {
+            return Err(GenesisError::UpgradeAtGenesis(u.name.clone()));
+        }
+    }
+
+    let set = Staking::new(&g.params).select_set(&state, 0);
+    if !set.is_empty() {
+        staking::store_active_set(&mut state, &set);
+    }
+
+    state.commit();
+    Ok(state)
+}
+
+/// 0 号区块：无交易，状态根为创世状态根。
+pub fn genesis_block(g: &Genesis, state: &State) -> Result<Block, GenesisError> {
+    Ok(Block {
+        header: BlockHeader {
+            height: 0,
+            parent_hash: H256::ZERO,
+            timestamp_ms: g.timestamp_ms().map_err(GenesisError::Time)?,
+            proposer: Address::ZERO,
+            state_root: state.state_root(),
+            tx_root: H256::ZERO,
+            receipts_root: H256::ZERO,
+            gas_limit: g.params.gas_limit_block,
+            gas_used: 0,
+            base_fee: g.params.base_fee,
+            logs_bloom: Bloom::default(),
+        },
+        txs: Vec::new(),
+    })
+}
+
+pub fn build_genesis(g: &Genesis) -> Result<(State, Block), GenesisError> {
+    let state = genesis_state(g)?;
+    let block = genesis_block(g, &state)?;
+    Ok((state, block))
+}
+
+fn hex_pubkey(pk: &[u8]) -> String {
+    pk.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_types::genesis::{GenesisBalance, GenesisValidator, Predeploy};
+
+    fn repo_genesis() -> Genesis {
+        Genesis::from_json(include_str!("../../../config/genesis.json")).unwrap()
+    }
+
+    #[test]
+    fn empty_repo_genesis_builds_block_zero() {
+        let g = repo_genesis();
+        let (state, block) = build_genesis(&g).unwrap();
+        assert_eq!(block.height(), 0);
+        assert_eq!(block.header.state_root, H256::ZERO);
+        assert_eq!(block.header.base_fee, 1000);
+        assert_eq!(state.entries().len(), 0);
+    }
+
+    #[test]
+    fn applies_balances_predeploys_and_validators() {
+        let mut g = repo_genesis();
+        let alice = Address([1u8; 20]);
+        let contract = Address([2u8; 20]);
+        g.balances.push(GenesisBalance {
+            address: alice,
+            amount: 5_000,
+        });
+        g.predeploy.push(Predeploy {
+            address: contract,
+            code: vec![0x00],
+            storage: [(H256::from_u128(1), H256::from_u128(7))].into(),
+            balance: 10,
+        });
+        let mut pubkey = vec![0x02];
+        pubkey.extend_from_slice(&[3u8; 32]);
+        g.validators.push(GenesisValidator {
+            operator: alice,
+            pubkey,
+            stake: 1_000_000_000,
+            commission_bps: 500,
+        });
+
+        let (state, block) = build_genesis(&g).unwrap();
+        assert_eq!(state.balance(&alice), 5_000);
+        assert_eq!(state.balance(&contract), 10);
+        assert_eq!(state.code(&contract), Some(&[0x00][..]));
+        assert_eq!(
+            state.storage(&contract, &H256::from_u128(1)),
+            H256::from_u128(7)
+        );
+        assert_eq!(state.validator(&alice).unwrap().stake, 1_000_000_000);
+        assert_eq!(state.balance(&staking::STAKING_POOL), 1_000_000_000);
+        assert_eq!(staking::delegation(&state, &alice, &alice), 1_000_000_000);
+        let set = staking::active_set(&state);
+        assert_eq!((set.epoch, set.total_power()), (0, 1_000_000_000));
+        assert_eq!(block.header.state_root, state.state_root());
+        assert_ne!(block.header.state_root, H256::ZERO);
+
+        g.validators[0].stake = 1;
+        assert!(matches!(
+            build_genesis(&g),
+            Err(GenesisError::InsufficientStake { .. })
+        ));
+    }
+}
@@ -0,0 +1,619 @@
+//! 链上治理：提交提案（参数修改 / 特性门升级 / 文本）、按抵押加权投票、到期计票并自动执行。
+//!
+//! - 提交：押金取交易 value，不低于 min_deposit，转入 GOV_POOL；投票截止于提交高度 + voting_period_blocks
+//! - 投票：权重为投票人在各验证者处的委托额之和（含自抵押），按计票时的委托额计算；可改票
+//! - 计票：在截止区块末尾进行。参与投票的权重 ≥ 全部抵押 × quorum 且 赞成 > (赞成 + 反对) × threshold
+//!   时通过。达到法定投票率即退还押金，否则押金转入 REWARD_POOL
+//! - 参数修改：通过后在目标高度区块末尾写入新参数，从下一个区块起生效（见 Executor::at）
+//! - 特性门：通过后调度其中的升级计划，由 upgrade 模块在计划高度激活
+//!
+//! 模块存储键：
+//! - `gov/next_id` -> 下一个提案编号（u64 大端）
+//! - `gov/proposal/` + 编号(8B) -> Proposal JSON
+//! - `gov/vote/` + 编号(8B) + 投票人(20B) -> VoteOption JSON（计票后删除）
+//! - `gov/voting/` + 截止高度(8B) + 编号(8B) -> 空（待计票索引）
+//! - `gov/exec/` + 目标高度(8B) + 编号(8B) -> 空（待执行的参数修改索引）
+//! - `gov/params` -> 治理修改后的 ChainParams JSON
+use crate::executor::Executor;
+use crate::staking::{self, REWARD_POOL};
+use crate::state::State;
+use crate::upgrade;
+use ark_types::primitives::amount;
+use ark_types::{Address, Amount, ChainParams, GovernanceOp, ProposalContent, VoteOption};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// 提案押金的托管账户。
+pub const GOV_POOL: Address = Address(*b"ark.governance.pool\0");
+pub const MAX_TITLE_LEN: usize = 140;
+pub const MAX_DESCRIPTION_LEN: usize = 10_000;
+const MAX_BPS: u16 = 10_000;
+/// 不可由治理修改的参数：epoch 编号由高度直接计算，创世 base fee 只用于创世区块
+const FROZEN_PARAMS: &[&str] = &["epoch_blocks", "base_fee"];
+
+const KEY_NEXT_ID: &[u8] = b"gov/next_id";
+const KEY_PROPOSAL: &[u8] = b"gov/proposal/";
+const KEY_VOTE: &[u8] = b"gov/vote/";
+const KEY_VOTING: &[u8] = b"gov/voting/";
+const KEY_EXEC: &[u8] = b"gov/exec/";
+const KEY_PARAMS: &[u8] = b"gov/params";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum GovernanceError {
+    #[error("proposal title must not be empty")]
+    EmptyTitle,
+    #[error("proposal {field} exceeds {max} bytes")]
+    TooLong { field: &'static str, max: usize },
+    #[error("deposit {amount} below min_deposit {min}")]
+    DepositTooLow { amount: Amount, min: Amount },
+    #[error("insufficient balance")]
+    InsufficientBalance,
+    #[error("parameter change lists no parameters")]
+    EmptyChanges,
+    #[error(
+        "proposal takes effect at height {height}, which must be after voting ends at {voting_end}"
+    )]
+    TooEarly { height: u64, voting_end: u64 },
+    #[error("parameter {0} cannot be changed by governance")]
+    FrozenParam(String),
+    #[error("unknown parameter {0}")]
+    UnknownParam(String),
+    #[error("invalid parameters: {0}")]
+    InvalidParams(String),
+    #[error("unknown proposal {0}")]
+    UnknownProposal(u64),
+    #[error("voting on proposal {0} is closed")]
+    VotingClosed(u64),
+    #[error("voter has no stake")]
+    NoStake,
+    #[error("operation does not accept a value transfer")]
+    UnexpectedValue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Voting,
+    /// 已通过；参数修改等待目标高度，文本提案到此为止
+    Passed,
+    Rejected,
+    Executed,
+    /// 已通过但执行失败（如参数已被其他提案改得不再兼容）
+    Failed(String),
+}
+
+/// 计票结果（按投票权重）。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tally {
+    #[serde(with = "amount")]
+    pub yes: Amount,
+    #[serde(with = "amount")]
+    pub no: Amount,
+    #[serde(with = "amount")]
+    pub abstain: Amount,
+    /// 计票时的全部抵押
+    #[serde(with = "amount")]
+    pub total_stake: Amount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub title: String,
+    pub description: String,
+    pub content: ProposalContent,
+    #[serde(with = "amount")]
+    pub deposit: Amount,
+    pub submit_height: u64,
+    /// 在该区块末尾计票
+    pub voting_end: u64,
+    pub status: ProposalStatus,
+    #[serde(default)]
+    pub tally: Option<Tally>,
+}
+
+fn id_key(prefix: &[u8], id: u64) -> Vec<u8> {
+    [prefix, &id.to_be_bytes()].concat()
+}
+
+fn index_key(prefix: &[u8], height: u64, id: u64) -> Vec<u8> {
+    [prefix, &height.to_be_bytes(), &id.to_be_bytes()].concat()
+}
+
+fn vote_key(id: u64, voter: &Address) -> Vec<u8> {
+    [&id_key(KEY_VOTE, id)[..], voter.as_bytes()].concat()
+}
+
+/// 索引 prefix + height 下的提案编号。
+fn indexed(state: &State, prefix: &[u8], height: u64) -> Vec<u64> {
+    let prefix = id_key(prefix, height);
+    state
+        .module_prefix(&prefix)
+        .filter_map(|(k, _)| Some(u64::from_be_bytes(k.get(prefix.len()..)?.try_into().ok()?)))
+        .collect()
+}
+
+pub fn proposal(state: &State, id: u64) -> Option<Proposal> {
+    state
+        .module_get(&id_key(KEY_PROPOSAL, id))
+        .and_then(|v| serde_json::from_slice(v).ok())
+}
+
+/// 全部提案（编号升序）。
+pub fn proposals(state: &State) -> Vec<Proposal> {
+    state
+        .module_prefix(KEY_PROPOSAL)
+        .filter_map(|(_, v)| serde_json::from_slice(v).ok())
+        .collect()
+}
+
+fn store_proposal(state: &mut State, p: &Proposal) {
+    state.module_set(
+        id_key(KEY_PROPOSAL, p.id),
+        serde_json::to_vec(p).expect("proposal serializes"),
+    );
+}
+
+/// 投票期内 voter 当前的选择。
+pub fn vote(state: &State, id: u64, voter: &Address) -> Option<VoteOption> {
+    state
+        .module_get(&vote_key(id, voter))
+        .and_then(|v| serde_json::from_slice(v).ok())
+}
+
+/// 投票权重：voter 在全部验证者处的委托额之和。
+pub fn voting_power(state: &State, voter: &Address) -> Amount {
+    state
+        .validators()
+        .map(|v| staking::delegation(state, &v.operator, voter))
+        .sum()
+}
+
+/// 治理修改后的链参数；从未修改时为 None。
+pub fn params(state: &State) -> Option<ChainParams> {
+    state
+        .module_get(KEY_PARAMS)
+        .and_then(|v| serde_json::from_slice(v).ok())
+}
+
+/// 把 changes 应用到 params 并校验结果。键为以 "." 分隔的路径，须已存在于参数中。
+pub fn apply_changes(
+    params: &ChainParams,
+    changes: &BTreeMap<String, serde_json::Value>,
+) -> Result<ChainParams, GovernanceError> {
+    if changes.is_empty() {
+        return Err(GovernanceError::EmptyChanges);
+    }
+    let mut doc = serde_json::to_value(params).expect("chain params serialize");
+    for (key, value) in changes {
+        if FROZEN_PARAMS.contains(&key.as_str()) {
+            return Err(GovernanceError::FrozenParam(key.clone()));
+        }
+        let mut slot = &mut doc;
+        for part in key.split('.') {
+            slot = slot
+                .as_object_mut()
+                .and_then(|o| o.get_mut(part))
+                .ok_or_else(|| GovernanceError::UnknownParam(key.clone()))?;
+        }
+        *slot = value.clone();
+    }
+    let params: ChainParams =
+        serde_json::from_value(doc).map_err(|e| GovernanceError::InvalidParams(e.to_string()))?;
+    validate_params(&params)?;
+    Ok(params)
+}
+
+fn validate_params(p: &ChainParams) -> Result<(), GovernanceError> {
+    let invalid = |msg: &str| Err(GovernanceError::InvalidParams(msg.into()));
+    let g = &p.governance;
+    if g.voting_period_blocks == 0 {
+        return invalid("governance.voting_period_blocks must be positive");
+    }
+    if g.quorum_bps > MAX_BPS || g.threshold_bps > MAX_BPS {
+        return invalid("governance quorum_bps / threshold_bps exceed 10000");
+    }
+    if p.gas_limit_block == 0 || p.block_time_ms == 0 {
+        return invalid("gas_limit_block and block_time_ms must be positive");
+    }
+    if p.staking.max_validators == 0 {
+        return invalid("staking.max_validators must be positive");
+    }
+    if p.fee_market.elasticity_multiplier == 0 || p.fee_market.base_fee_change_denominator == 0 {
+        return invalid("fee_market parameters must be positive");
+    }
+    Ok(())
+}
+
+impl Executor {
+    /// 在 state 上生效的执行器：治理修改过链参数时按新参数重建（保留 gas 表、特性门与升级计划），
+    /// 否则直接借用 self。
+    pub fn at(&self, state: &State) -> Cow<'_, Executor> {
+        match params(state) {
+            Some(params) => Cow::Owned(Executor {
+                schedule: self.schedule.clone(),
+                feature_gates: self.feature_gates.clone(),
+                upgrades: self.upgrades.clone(),
+                ..Executor::new(&params)
+            }),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// 执行一笔治理交易。失败时调用方负责回滚状态。
+    pub fn apply_governance(
+        &self,
+        state: &mut State,
+        sender: &Address,
+        value: Amount,
+        op: &GovernanceOp,
+        height: u64,
+    ) -> Result<(), GovernanceError> {
+        match op {
+            GovernanceOp::Submit {
+                title,
+                description,
+                content,
+            } => self
+                .submit(state, sender, value, title, description, content, height)
+                .map(|_| ()),
+            GovernanceOp::Vote { proposal, option } => {
+                if value != 0 {
+                    return Err(GovernanceError::UnexpectedValue);
+                }
+                cast_vote(state, sender, *proposal, *option, height)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn submit(
+        &self,
+        state: &mut State,
+        sender: &Address,
+        deposit: Amount,
+        title: &str,
+        description: &str,
+        content: &ProposalContent,
+        height: u64,
+    ) -> Result<u64, GovernanceError> {
+        let gov = &self.params.governance;
+        if title.trim().is_empty() {
+            return Err(GovernanceError::EmptyTitle);
+        }
+        if title.len() > MAX_TITLE_LEN {
+            return Err(GovernanceError::TooLong {
+                field: "title",
+                max: MAX_TITLE_LEN,
+            });
+        }
+        if description.len() > MAX_DESCRIPTION_LEN {
+            return Err(GovernanceError::TooLong {
+                field: "description",
+                max: MAX_DESCRIPTION_LEN,
+            });
+        }
+        if deposit < gov.min_deposit {
+            return Err(GovernanceError::DepositTooLow {
+                amount: deposit,
+                min: gov.min_deposit,
+            });
+        }
+        let voting_end = height + gov.voting_period_blocks;
+        let takes_effect = match content {
+            ProposalContent::ParamChange { height, changes } => {
+                apply_changes(&self.current_params(state), changes)?;
+                Some(*height)
+            }
+            ProposalContent::FeatureGate { upgrade } => Some(upgrade.height),
+            ProposalContent::Text => None,
+        };
+        if let Some(at) = takes_effect.filter(|at| *at <= voting_end) {
+            return Err(GovernanceError::TooEarly {
+                height: at,
+                voting_end,
+            });
+        }
+        if !state.transfer(sender, &GOV_POOL, deposit) {
+            return Err(GovernanceError::InsufficientBalance);
+        }
+
+        let id = state
+            .module_get(KEY_NEXT_ID)
+            .and_then(|v| v.try_into().ok())
+            .map(u64::from_be_bytes)
+            .unwrap_or(1);
+        state.module_set(KEY_NEXT_ID.to_vec(), (id + 1).to_be_bytes().to_vec());
+        store_proposal(
+            state,
+            &Proposal {
+                id,
+                proposer: *sender,
+                title: title.to_string(),
+                description: description.to_string(),
+                content: content.clone(),
+                deposit,
+                submit_height: height,
+                voting_end,
+                status: ProposalStatus::Voting,
+                tally: None,
+            },
+        );
+        state.module_set(index_key(KEY_VOTING, voting_end, id), Vec::new());
+        Ok(id)
+    }
+
+    fn current_params(&self, state: &State) -> ChainParams {
+        params(state).unwrap_or_else(|| self.params.clone())
+    }
+
+    /// 区块末尾：对截止于本高度的提案计票，执行目标高度为本高度的参数修改。
+    pub(crate) fn end_block_governance(&self, state: &mut State, height: u64) {
+        for id in indexed(state, KEY_VOTING, height) {
+            state.module_delete(&index_key(KEY_VOTING, height, id));
+            if let Some(p) = proposal(state, id) {
+                self.tally(state, p, height);
+            }
+        }
+        for id in indexed(state, KEY_EXEC, height) {
+            state.module_delete(&index_key(KEY_EXEC, height, id));
+            let Some(mut p) = proposal(state, id) else {
+                continue;
+            };
+            if let ProposalContent::ParamChange { changes, .. } = &p.content {
+                p.status = match apply_changes(&self.current_params(state), changes) {
+                    Ok(params) => {
+                        state.module_set(
+                            KEY_PARAMS.to_vec(),
+                            serde_json::to_vec(&params).expect("chain params serialize"),
+                        );
+                        ProposalStatus::Executed
+                    }
+                    Err(e) => ProposalStatus::Failed(e.to_string()),
+                };
+                store_proposal(state, &p);
+            }
+        }
+    }
+
+    fn tally(&self, state: &mut State, mut p: Proposal, height: u64) {
+        let gov = &self.params.governance;
+        let prefix = id_key(KEY_VOTE, p.id);
+        let votes: Vec<(Vec<u8>, Address, VoteOption)> = state
+            .module_prefix(&prefix)
+            .filter_map(|(k, v)| {
+                let voter = Address(k.get(prefix.len()..)?.try_into().ok()?);
+                Some((k.to_vec(), voter, serde_json::from_slice(v).ok()?))
+            })
+            .collect();
+        let mut tally = Tally {
+            total_stake: state.validators().map(|v| v.stake).sum(),
+            ..Tally::default()
+        };
+        for (key, voter, option) in votes {
+            state.module_delete(&key);
+            let power = voting_power(state, &voter);
+            match option {
+                VoteOption::Yes => tally.yes += power,
+                VoteOption::No => tally.no += power,
+                VoteOption::Abstain => tally.abstain += power,
+            }
+        }
+
+        let bps = |a: Amount, bps: u16| a.saturating_mul(bps as Amount);
+        let voted = tally.yes + tally.no + tally.abstain;
+        let quorum = voted > 0
+            && voted.saturating_mul(MAX_BPS as Amount) >= bps(tally.total_stake, gov.quorum_bps);
+        let passed = quorum
+            && tally.yes.saturating_mul(MAX_BPS as Amount)
+                > bps(tally.yes + tally.no, gov.threshold_bps);
+        let refund_to = if quorum { p.proposer } else { REWARD_POOL };
+        state.transfer(&GOV_POOL, &refund_to, p.deposit);
+
+        p.status = match (&p.content, passed) {
+            (_, false) => ProposalStatus::Rejected,
+            (ProposalContent::ParamChange { height: at, .. }, true) => {
+                state.module_set(index_key(KEY_EXEC, *at, p.id), Vec::new());
+                ProposalStatus::Passed
+            }
+            (ProposalContent::FeatureGate { upgrade }, true) => {
+                match upgrade::schedule(state, upgrade.clone(), height) {
+                    Ok(()) => ProposalStatus::Executed,
+                    Err(e) => ProposalStatus::Failed(e.to_string()),
+                }
+            }
+            (ProposalContent::Text, true) => ProposalStatus::Passed,
+        };
+        p.tally = Some(tally);
+        store_proposal(state, &p);
+    }
+}
+
+fn cast_vote(
+    state: &mut State,
+    voter: &Address,
+    id: u64,
+    option: VoteOption,
+    height: u64,
+) -> Result<(), GovernanceError> {
+    let p = proposal(state, id).ok_or(GovernanceError::UnknownProposal(id))?;
+    if p.status != ProposalStatus::Voting || height > p.voting_end {
+        return Err(GovernanceError::VotingClosed(id));
+    }
+    if voting_power(state, voter) == 0 {
+        return Err(GovernanceError::NoStake);
+    }
+    state.module_set(
+        vote_key(id, voter),
+        serde_json::to_vec(&option).expect("vote serializes"),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::fill_block;
+    use crate::executor::tests::{env, funded, params, signed};
+    use ark_types::{Action, ExecStatus, SignedTransaction};
+
+    fn exec() -> Executor {
+        let mut params = params();
+        params.governance.min_deposit = 1_000;
+        params.governance.voting_period_blocks = 2;
+        Executor::new(&params)
+    }
+
+    fn gov(key: u8, nonce: u64, value: Amount, op: GovernanceOp) -> SignedTransaction {
+        let mut stx = signed(key, nonce, Action::Governance { op }, 100_000);
+        stx.tx.value = value;
+        stx
+    }
+
+    fn submit(content: ProposalContent) -> GovernanceOp {
+        GovernanceOp::Submit {
+            title: "raise gas limit".into(),
+            description: String::new(),
+            content,
+        }
+    }
+
+    fn ballot(proposal: u64, option: VoteOption) -> GovernanceOp {
+        GovernanceOp::Vote { proposal, option }
+    }
+
+    #[test]
+    fn stake_weighted_vote_passes_and_applies_params_at_target_height() {
+        let exec = exec();
+        let mut state = funded(&[1, 2, 3]);
+        for (key, stake) in [(1u8, 3_000_000_000), (2, 1_000_000_000)] {
+            let operator = Address::from_pubkey(&[key; 33]);
+            let mut pubkey = vec![0x02];
+            pubkey.extend_from_slice(&[key; 32]);
+            exec.staking
+                .create_validator(&mut state, &operator, &pubkey, 0, stake)
+                .unwrap();
+        }
+        state.commit();
+        let proposer = Address::from_pubkey(&[3; 33]);
+        let changes: BTreeMap<_, _> = [
+            ("gas_limit_block".to_string(), serde_json::json!(30_000_000)),
+            (
+                "governance.min_deposit".to_string(),
+                serde_json::json!("2000"),
+            ),
+        ]
+        .into();
+        let frozen: BTreeMap<_, _> = [("epoch_blocks".to_string(), serde_json::json!(10))].into();
+        let mut env = env();
+
+        let txs = vec![
+            gov(
+                3,
+                0,
+                1_000,
+                submit(ProposalContent::ParamChange {
+                    height: 5,
+                    changes: changes.clone(),
+                }),
+            ),
+            gov(3, 1, 1_000, submit(ProposalContent::Text)),
+            gov(3, 2, 999, submit(ProposalContent::Text)),
+            gov(
+                3,
+                3,
+                1_000,
+                submit(ProposalContent::ParamChange { height: 3, changes }),
+            ),
+            gov(
+                3,
+                4,
+                1_000,
+                submit(ProposalContent::ParamChange {
+                    height: 9,
+                    changes: frozen,
+                }),
+            ),
+        ];
+        let (built, _) = fill_block(&exec, &mut state, env.clone(), txs).unwrap();
+        let reasons: Vec<_> = built
+            .receipts
+            .iter()
+            .map(|r| r.revert_reason.clone())
+            .collect();
+        assert_eq!(reasons[..2], [None, None]);
+        assert!(reasons[2].as_deref().unwrap().contains("min_deposit"));
+        assert!(reasons[3]
+            .as_deref()
+            .unwrap()
+            .contains("after voting ends at 3"));
+        assert!(reasons[4].as_deref().unwrap().contains("cannot be changed"));
+        assert_eq!(state.balance(&GOV_POOL), 2_000);
+        assert_eq!(proposal(&state, 1).unwrap().voting_end, 3);
+
+        env.height = 2;
+        let txs = vec![
+            gov(1, 0, 0, ballot(1, VoteOption::No)),
+            gov(1, 1, 0, ballot(1, VoteOption::Yes)),
+            gov(2, 0, 0, ballot(1, VoteOption::No)),
+            gov(3, 5, 0, ballot(1, VoteOption::Yes)),
+        ];
+        let (built, _) = fill_block(&exec, &mut state, env.clone(), txs).unwrap();
+        assert_eq!(built.receipts[3].status, ExecStatus::Reverted);
+        assert_eq!(
+            vote(&state, 1, &Address::from_pubkey(&[1; 33])),
+            Some(VoteOption::Yes)
+        );
+
+        let before = state.balance(&proposer);
+        env.height = 3;
+        fill_block(&exec, &mut state, env.clone(), Vec::new()).unwrap();
+        let p = proposal(&state, 1).unwrap();
+        assert_eq!(p.status, ProposalStatus::Passed);
+        let tally = p.tally.unwrap();
+        assert_eq!(
+            (tally.yes, tally.no, tally.total_stake),
+            (3_000_000_000, 1_000_000_000, 4_000_000_000)
+        );
+        // 第二个提案无人投票：未达法定投票率，押金转入奖励池
+        assert_eq!(
+            proposal(&state, 2).unwrap().status,
+            ProposalStatus::Rejected
+        );
+        assert_eq!(state.balance(&proposer), before + 1_000);
+        assert_eq!(state.balance(&REWARD_POOL), 1_000);
+        assert_eq!(state.balance(&GOV_POOL), 0);
+        let closed = gov(1, 2, 0, ballot(1, VoteOption::No));
+        env.height = 4;
+        let (built, _) = fill_block(&exec, &mut state, env.clone(), vec![closed]).unwrap();
+        assert!(built.receipts[0]
+            .revert_reason
+            .as_deref()
+            .unwrap()
+            .contains("closed"));
+        assert_eq!(exec.at(&state).params.gas_limit_block, 20_000_000);
+
+        env.height = 5;
+        fill_block(&exec, &mut state, env.clone(), Vec::new()).unwrap();
+        assert_eq!(
+            proposal(&state, 1).unwrap().status,
+            ProposalStatus::Executed
+        );
+        let effective = exec.at(&state);
+        assert_eq!(effective.params.gas_limit_block, 30_000_000);
+        assert_eq!(effective.params.governance.min_deposit, 2_000);
+        assert_eq!(effective.params.epoch_blocks, exec.params.epoch_blocks);
+
+        // 新参数从下一个区块起生效
+        env.height = 6;
+        let low = gov(3, 6, 1_000, submit(ProposalContent::Text));
+        let (built, _) = fill_block(&exec, &mut state, env, vec![low]).unwrap();
+        assert!(built.receipts[0]
+            .revert_reason
+            .as_deref()
+            .unwrap()
+            .contains("min_deposit 2000"));
+    }
+}
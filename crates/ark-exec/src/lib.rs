@@ -1,2 +1,29 @@
-//! 执行占位：账户模型 + 并行调度
-pub struct Executor;
+//! 执行层：账户模型、gas 计量、费用市场、合约 VM（原生与 EVM）、区块组装、链上治理与协议升级
+pub mod builder;
+pub mod error;
+pub mod evm;
+pub mod executor;
+pub mod fee_market;
+pub mod gas;
+pub mod genesis;
+pub mod governance;
+pub mod mempool;
+pub mod metrics;
+pub mod simulate;
+pub mod staking;
+pub mod state;
+pub mod upgrade;
+pub mod vm;
+
+pub use builder::{fill_block, replay_block, BlockBuilder, BuiltBlock, ReplayError};
+pub use error::ExecError;
+pub use executor::{verify_signature, BlockEnv, Executor, TxOutcome};
+pub use fee_market::{BlockFees, FeeHistory, FeeMarket, FeeSuggestion};
+pub use gas::{GasMeter, GasSchedule};
+pub use genesis::{build_genesis, GenesisError};
+pub use governance::{GovernanceError, Proposal, ProposalStatus, Tally, GOV_POOL};
+pub use mempool::{Added, Mempool, MempoolConfig, MempoolError, PoolTx};
+pub use simulate::SimulateError;
+pub use staking::{EpochTransition, Staking, StakingError, Validator, REWARD_POOL, STAKING_POOL};
+pub use state::{Account, State};
+pub use upgrade::UpgradeError;
@@ -0,0 +1,662 @@
+//! 交易池：准入校验、按发送方 nonce 排队、全局按小费出块排序、替换（RBF）与淘汰。
+//!
+//! - 准入：签名、chain id、nonce 不低于账户当前值、固有 gas、费用下限与余额
+//! - 余额须覆盖发送方所有排队交易的最大花费之和（被替换的同 nonce 交易不计），
+//!   避免一串单独看都付得起、合起来却付不起的交易占满交易池
+//! - 同一发送方同一 nonce 的新交易需把 max_fee 与小费都提高 price_bump_percent 才能替换
+//! - 超出笔数 / 字节上限时淘汰出价最低的尾部交易（各发送方最高 nonce 者，避免留下 nonce 空洞）
+//! - 超龄交易连同其后续 nonce 一并移除
+//!
+//! 区块导入即最终确定，节点没有链重组，因此不存在需要重新入池的孤块交易。
+use crate::executor::{max_cost, verify_signature, Executor};
+use crate::metrics;
+use crate::state::State;
+use ark_types::{Action, Address, Amount, Block, ChainParams, Gas, SignedTransaction, H256};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+#[derive(Clone, Debug)]
+pub struct MempoolConfig {
+    pub max_txs: usize,
+    pub max_bytes: usize,
+    pub max_per_sender: usize,
+    /// 单笔交易序列化后的最大字节数
+    pub max_tx_bytes: usize,
+    pub max_age_ms: u64,
+    /// 替换交易须提高的费用百分比
+    pub price_bump_percent: u128,
+    pub min_fee_per_gas: Amount,
+    pub block_gas_limit: Gas,
+}
+
+impl MempoolConfig {
+    pub fn from_params(params: &ChainParams) -> Self {
+        Self {
+            min_fee_per_gas: params.gas_price_min,
+            block_gas_limit: params.gas_limit_block,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_txs: 10_000,
+            max_bytes: 64 << 20,
+            max_per_sender: 64,
+            max_tx_bytes: 128 << 10,
+            max_age_ms: 3 * 60 * 60 * 1000,
+            price_bump_percent: 10,
+            min_fee_per_gas: 1,
+            block_gas_limit: 20_000_000,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum MempoolError {
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("transaction already known")]
+    AlreadyKnown,
+    #[error("chain id mismatch: expected {expected}, got {got}")]
+    ChainIdMismatch { expected: String, got: String },
+    #[error("nonce too low: expected at least {expected}, got {got}")]
+    NonceTooLow { expected: u64, got: u64 },
+    #[error("transaction size {size} exceeds limit {limit}")]
+    TooLarge { size: usize, limit: usize },
+    #[error("gas limit {gas_limit} exceeds block gas limit {block_limit}")]
+    ExceedsBlockGasLimit { gas_limit: Gas, block_limit: Gas },
+    #[error("intrinsic gas too low: limit {limit}, required {required}")]
+    IntrinsicGas { limit: Gas, required: Gas },
+    #[error("max fee per gas {max_fee} below pool minimum {min}")]
+    Underpriced { max_fee: Amount, min: Amount },
+    #[error("priority fee {tip} exceeds max fee {max_fee}")]
+    TipAboveFeeCap { tip: Amount, max_fee: Amount },
+    #[error("insufficient funds: need {need}, have {have}")]
+    InsufficientFunds { need: Amount, have: Amount },
+    #[error("fee overflow")]
+    FeeOverflow,
+    #[error("feature gate {0} is not active")]
+    FeatureDisabled(&'static str),
+    #[error("replacement transaction underpriced: requires {bump}% fee bump")]
+    ReplacementUnderpriced { bump: u128 },
+    #[error("sender has too many pending transactions (limit {0})")]
+    SenderLimit(usize),
+    #[error("mempool full and fee too low to evict")]
+    PoolFull,
+}
+
+/// 池中交易。
+#[derive(Clone, Debug)]
+pub struct PoolTx {
+    pub stx: SignedTransaction,
+    pub hash: H256,
+    pub sender: Address,
+    pub size: usize,
+    pub received_ms: u64,
+    /// 最大花费：gas_limit * max_fee + value
+    pub cost: Amount,
+}
+
+/// 准入结果。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Added {
+    pub hash: H256,
+    /// 被替换的同 nonce 交易
+    pub replaced: Option<H256>,
+    /// 因容量淘汰的交易
+    pub evicted: Vec<H256>,
+}
+
+pub struct Mempool {
+    config: MempoolConfig,
+    chain_id: String,
+    exec: Executor,
+    txs: HashMap<H256, PoolTx>,
+    by_sender: BTreeMap<Address, BTreeMap<u64, H256>>,
+    /// 各发送方排队交易的最大花费之和
+    pending_cost: HashMap<Address, Amount>,
+    bytes: usize,
+}
+
+impl Mempool {
+    pub fn new(config: MempoolConfig, chain_id: impl Into<String>, exec: Executor) -> Self {
+        Self {
+            config,
+            chain_id: chain_id.into(),
+            exec,
+            txs: HashMap::new(),
+            by_sender: BTreeMap::new(),
+            pending_cost: HashMap::new(),
+            bytes: 0,
+        }
+    }
+
+    pub fn config(&self) -> &MempoolConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    pub fn size_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn contains(&self, hash: &H256) -> bool {
+        self.txs.contains_key(hash)
+    }
+
+    pub fn get(&self, hash: &H256) -> Option<&PoolTx> {
+        self.txs.get(hash)
+    }
+
+    /// 全部交易（按发送方、nonce 排序）。
+    pub fn iter(&self) -> impl Iterator<Item = &PoolTx> {
+        self.by_sender
+            .values()
+            .flat_map(|q| q.values())
+            .map(|h| &self.txs[h])
+    }
+
+    /// 发送方池内下一个可用 nonce（无排队交易时为账户 nonce）。
+    pub fn next_nonce(&self, state: &State, sender: &Address) -> u64 {
+        let base = state.nonce(sender);
+        let Some(queue) = self.by_sender.get(sender) else {
+            return base;
+        };
+        let mut next = base;
+        while queue.contains_key(&next) {
+            next += 1;
+        }
+        next
+    }
+
+    /// 校验并加入交易池。
+    pub fn add(
+        &mut self,
+        stx: SignedTransaction,
+        state: &State,
+        now_ms: u64,
+    ) -> Result<Added, MempoolError> {
+        let result = self.admit(stx, state, now_ms);
+        match result {
+            Ok(_) => metrics::MEMPOOL_ADMITTED.inc(),
+            Err(_) => metrics::MEMPOOL_REJECTED.inc(),
+        }
+        result
+    }
+
+    fn admit(
+        &mut self,
+        stx: SignedTransaction,
+        state: &State,
+        now_ms: u64,
+    ) -> Result<Added, MempoolError> {
+        let hash = stx.hash();
+        if self.txs.contains_key(&hash) {
+            return Err(MempoolError::AlreadyKnown);
+        }
+        let size = serde_json::to_vec(&stx)
+            .expect("transaction serializes")
+            .len();
+        let cost = self.check(&stx, size, state)?;
+        let sender = stx.sender();
+        let nonce = stx.tx.nonce;
+
+        let existing = self
+            .by_sender
+            .get(&sender)
+            .and_then(|q| q.get(&nonce))
+            .copied();
+        let queued = self.pending_cost(&sender) - existing.map_or(0, |h| self.txs[&h].cost);
+        let need = queued.checked_add(cost).ok_or(MempoolError::FeeOverflow)?;
+        let have = state.balance(&sender);
+        if have < need {
+            return Err(MempoolError::InsufficientFunds { need, have });
+        }
+        if let Some(old) = existing {
+            let old_tx = &self.txs[&old].stx.tx;
+            let bump = self.config.price_bump_percent;
+            if stx.tx.max_fee_per_gas < bumped(old_tx.max_fee_per_gas, bump)
+                || stx.tx.max_priority_fee_per_gas < bumped(old_tx.max_priority_fee_per_gas, bump)
+            {
+                return Err(MempoolError::ReplacementUnderpriced { bump });
+            }
+        } else if self.by_sender.get(&sender).map_or(0, BTreeMap::len) >= self.config.max_per_sender
+        {
+            return Err(MempoolError::SenderLimit(self.config.max_per_sender));
+        }
+
+        let replaced = existing.and_then(|old| self.remove_one(&old));
+        self.insert(PoolTx {
+            stx,
+            hash,
+            sender,
+            size,
+            received_ms: now_ms,
+            cost,
+        });
+
+        let mut evicted = Vec::new();
+        while self.txs.len() > self.config.max_txs || self.bytes > self.config.max_bytes {
+            let victim = self
+                .eviction_candidate()
+                .expect("pool over capacity is non-empty");
+            if victim == hash {
+                // 新交易本身出价最低：拒绝准入，恢复被替换与已淘汰的交易
+                self.remove_one(&hash);
+                for ptx in evicted.into_iter().chain(replaced) {
+                    self.insert(ptx);
+                }
+                return Err(MempoolError::PoolFull);
+            }
+            evicted.extend(self.remove_one(&victim));
+        }
+        Ok(Added {
+            hash,
+            replaced: existing,
+            evicted: evicted.iter().map(|p| p.hash).collect(),
+        })
+    }
+
+    /// 单笔交易的准入校验，返回其最大花费（余额在 admit 中与排队交易合并校验）。
+    fn check(
+        &self,
+        stx: &SignedTransaction,
+        size: usize,
+        state: &State,
+    ) -> Result<Amount, MempoolError> {
+        let tx = &stx.tx;
+        if size > self.config.max_tx_bytes {
+            return Err(MempoolError::TooLarge {
+                size,
+                limit: self.config.max_tx_bytes,
+            });
+        }
+        if tx.chain_id != self.chain_id {
+            return Err(MempoolError::ChainIdMismatch {
+                expected: self.chain_id.clone(),
+                got: tx.chain_id.clone(),
+            });
+        }
+        if !verify_signature(stx) {
+            return Err(MempoolError::InvalidSignature);
+        }
+        let sender = stx.sender();
+        let expected = state.nonce(&sender);
+        if tx.nonce < expected {
+            return Err(MempoolError::NonceTooLow {
+                expected,
+                got: tx.nonce,
+            });
+        }
+        if tx.gas_limit > self.config.block_gas_limit {
+            return Err(MempoolError::ExceedsBlockGasLimit {
+                gas_limit: tx.gas_limit,
+                block_limit: self.config.block_gas_limit,
+            });
+        }
+        let evm_only = stx.is_eth() || matches!(tx.action, Action::EvmCreate { .. });
+        if evm_only && !self.exec.feature_gates(state).evm {
+            return Err(MempoolError::FeatureDisabled("evm"));
+        }
+        let required = self.exec.intrinsic_gas(&tx.action);
+        if tx.gas_limit < required {
+            return Err(MempoolError::IntrinsicGas {
+                limit: tx.gas_limit,
+                required,
+            });
+        }
+        if tx.max_fee_per_gas < self.config.min_fee_per_gas {
+            return Err(MempoolError::Underpriced {
+                max_fee: tx.max_fee_per_gas,
+                min: self.config.min_fee_per_gas,
+            });
+        }
+        if tx.max_priority_fee_per_gas > tx.max_fee_per_gas {
+            return Err(MempoolError::TipAboveFeeCap {
+                tip: tx.max_priority_fee_per_gas,
+                max_fee: tx.max_fee_per_gas,
+            });
+        }
+        max_cost(tx.gas_limit, tx.max_fee_per_gas, tx.value).map_err(|_| MempoolError::FeeOverflow)
+    }
+
+    /// 发送方池内交易的最大花费之和。
+    pub fn pending_cost(&self, sender: &Address) -> Amount {
+        self.pending_cost.get(sender).copied().unwrap_or(0)
+    }
+
+    fn insert(&mut self, ptx: PoolTx) {
+        self.bytes += ptx.size;
+        // 准入时已校验总和不超过余额，不会溢出
+        *self.pending_cost.entry(ptx.sender).or_default() += ptx.cost;
+        self.by_sender
+            .entry(ptx.sender)
+            .or_default()
+            .insert(ptx.stx.tx.nonce, ptx.hash);
+        self.txs.insert(ptx.hash, ptx);
+        self.report_metrics();
+    }
+
+    fn remove_one(&mut self, hash: &H256) -> Option<PoolTx> {
+        let ptx = self.txs.remove(hash)?;
+        self.bytes -= ptx.size;
+        if let Some(queue) = self.by_sender.get_mut(&ptx.sender) {
+            queue.remove(&ptx.stx.tx.nonce);
+            if queue.is_empty() {
+                self.by_sender.remove(&ptx.sender);
+                self.pending_cost.remove(&ptx.sender);
+            } else if let Some(cost) = self.pending_cost.get_mut(&ptx.sender) {
+                *cost -= ptx.cost;
+            }
+        }
+        self.report_metrics();
+        Some(ptx)
+    }
+
+    fn report_metrics(&self) {
+        metrics::MEMPOOL_TXS.set(self.txs.len() as i64);
+        metrics::MEMPOOL_BYTES.set(self.bytes as i64);
+    }
+
+    /// 移除交易及同一发送方的后续 nonce（它们已无法按序执行）。
+    pub fn remove(&mut self, hash: &H256) -> Vec<H256> {
+        let Some(ptx) = self.txs.get(hash) else {
+            return Vec::new();
+        };
+        let (sender, nonce) = (ptx.sender, ptx.stx.tx.nonce);
+        let doomed: Vec<H256> = self.by_sender[&sender]
+            .range(nonce..)
+            .map(|(_, h)| *h)
+            .collect();
+        for h in &doomed {
+            self.remove_one(h);
+        }
+        doomed
+    }
+
+    /// 各发送方最高 nonce 的交易中出价最低者；同价时淘汰较新的。
+    fn eviction_candidate(&self) -> Option<H256> {
+        self.by_sender
+            .values()
+            .filter_map(|q| q.values().next_back())
+            .map(|h| &self.txs[h])
+            .min_by_key(|p| {
+                (
+                    p.stx.tx.max_fee_per_gas,
+                    p.stx.tx.max_priority_fee_per_gas,
+                    Reverse(p.received_ms),
+                )
+            })
+            .map(|p| p.hash)
+    }
+
+    /// 移除超龄交易，返回被移除的哈希。
+    pub fn evict_expired(&mut self, now_ms: u64) -> Vec<H256> {
+        let expired: Vec<H256> = self
+            .txs
+            .values()
+            .filter(|p| now_ms.saturating_sub(p.received_ms) > self.config.max_age_ms)
+            .map(|p| p.hash)
+            .collect();
+        let mut removed = Vec::new();
+        for h in expired {
+            removed.extend(self.remove(&h));
+        }
+        removed
+    }
+
+    /// 出块候选：每个发送方从账户 nonce 起连续的交易，按 base fee 下的实际小费全局降序交错排列。
+    /// 付不起 base fee 的交易及其后续 nonce 暂不入选。
+    pub fn pending(&self, state: &State, base_fee: Amount, max: usize) -> Vec<SignedTransaction> {
+        let mut heap = BinaryHeap::new();
+        for (sender, queue) in &self.by_sender {
+            let nonce = state.nonce(sender);
+            if let Some(p) = queue.get(&nonce).map(|h| &self.txs[h]) {
+                if let Some(tip) = p.stx.tx.effective_tip(base_fee) {
+                    heap.push((tip, Reverse(p.received_ms), *sender, nonce));
+                }
+            }
+        }
+        let mut out = Vec::new();
+        while let Some((_, _, sender, nonce)) = heap.pop() {
+            if out.len() >= max {
+                break;
+            }
+            let queue = &self.by_sender[&sender];
+            out.push(self.txs[&queue[&nonce]].stx.clone());
+            if let Some(p) = queue.get(&(nonce + 1)).map(|h| &self.txs[h]) {
+                if let Some(tip) = p.stx.tx.effective_tip(base_fee) {
+                    heap.push((tip, Reverse(p.received_ms), sender, nonce + 1));
+                }
+            }
+        }
+        out
+    }
+
+    /// 新区块上链后：移除已打包交易与 nonce 已过期的交易，区块 gas 上限跟随治理修改后的参数。
+    pub fn on_block(&mut self, block: &Block, state: &State) {
+        for stx in &block.txs {
+            self.remove_one(&stx.hash());
+        }
+        self.prune_stale(state);
+        self.config.block_gas_limit = self.exec.at(state).params.gas_limit_block;
+    }
+
+    /// 移除 nonce 低于账户当前 nonce 的交易。
+    pub fn prune_stale(&mut self, state: &State) {
+        let stale: Vec<H256> = self
+            .by_sender
+            .iter()
+            .flat_map(|(sender, q)| q.range(..state.nonce(sender)).map(|(_, h)| *h))
+            .collect();
+        for h in stale {
+            self.remove_one(&h);
+        }
+    }
+}
+
+fn bumped(fee: Amount, percent: u128) -> Amount {
+    fee.saturating_mul(100 + percent).div_ceil(100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::tests::params;
+    use ark_crypto::{SecretKey, Signer};
+    use ark_types::{Action, Transaction};
+
+    fn key(k: u8) -> SecretKey {
+        SecretKey::from_bytes(&[k; 32]).unwrap()
+    }
+
+    fn tx(k: u8, nonce: u64, max_fee: Amount, tip: Amount) -> SignedTransaction {
+        signed(
+            k,
+            Transaction {
+                chain_id: "ark-astra-1".into(),
+                nonce,
+                action: Action::Transfer {
+                    to: Address([9u8; 20]),
+                },
+                value: 1,
+                gas_limit: 21_000,
+                max_fee_per_gas: max_fee,
+                max_priority_fee_per_gas: tip,
+            },
+        )
+    }
+
+    fn signed(k: u8, tx: Transaction) -> SignedTransaction {
+        let sk = key(k);
+        let signature = sk.sign(&tx.signing_hash().0).unwrap().to_vec();
+        SignedTransaction {
+            tx,
+            pubkey: sk.public_key().to_vec(),
+            signature,
+        }
+    }
+
+    fn state(keys: &[u8]) -> State {
+        let mut s = State::new();
+        for k in keys {
+            s.add_balance(&Address::from_pubkey(&key(*k).public_key()), 1_000_000_000);
+        }
+        s
+    }
+
+    fn pool(config: MempoolConfig) -> Mempool {
+        Mempool::new(config, "ark-astra-1", Executor::new(&params()))
+    }
+
+    #[test]
+    fn admission_checks_signature_nonce_and_replacement() {
+        let mut s = state(&[1]);
+        let mut p = pool(MempoolConfig::default());
+
+        let mut forged = tx(1, 0, 100, 10);
+        forged.tx.value = 2;
+        assert_eq!(p.add(forged, &s, 0), Err(MempoolError::InvalidSignature));
+
+        let first = p.add(tx(1, 0, 100, 10), &s, 0).unwrap();
+        assert_eq!(
+            p.add(tx(1, 0, 100, 10), &s, 0),
+            Err(MempoolError::AlreadyKnown)
+        );
+        assert_eq!(
+            p.add(tx(1, 0, 105, 11), &s, 0),
+            Err(MempoolError::ReplacementUnderpriced { bump: 10 })
+        );
+        let r = p.add(tx(1, 0, 110, 11), &s, 0).unwrap();
+        assert_eq!(r.replaced, Some(first.hash));
+        assert_eq!(p.len(), 1);
+
+        p.add(tx(1, 1, 100, 10), &s, 0).unwrap();
+        let sender = Address::from_pubkey(&key(1).public_key());
+        assert_eq!(p.next_nonce(&s, &sender), 2);
+
+        s.increment_nonce(&sender);
+        p.prune_stale(&s);
+        assert_eq!(p.len(), 1);
+        assert!(matches!(
+            p.add(tx(1, 0, 500, 50), &s, 0),
+            Err(MempoolError::NonceTooLow { .. })
+        ));
+    }
+
+    #[test]
+    fn balance_must_cover_all_queued_transactions() {
+        let s = state(&[1]);
+        let mut p = pool(MempoolConfig::default());
+        let sender = Address::from_pubkey(&key(1).public_key());
+        // 每笔最大花费 21_000 * 20_000 + 1，余额只够两笔
+        p.add(tx(1, 0, 20_000, 1), &s, 0).unwrap();
+        p.add(tx(1, 1, 20_000, 1), &s, 0).unwrap();
+        assert_eq!(p.pending_cost(&sender), 2 * 420_000_001);
+        assert_eq!(
+            p.add(tx(1, 2, 20_000, 1), &s, 0),
+            Err(MempoolError::InsufficientFunds {
+                need: 3 * 420_000_001,
+                have: 1_000_000_000
+            })
+        );
+        // 替换只计新交易的花费
+        let r = p.add(tx(1, 1, 22_000, 2), &s, 0).unwrap();
+        assert!(r.replaced.is_some());
+        assert_eq!(p.pending_cost(&sender), 420_000_001 + 462_000_001);
+        assert!(matches!(
+            p.add(tx(1, 1, 30_000, 3), &s, 0),
+            Err(MempoolError::InsufficientFunds { .. })
+        ));
+        p.remove(&tx(1, 0, 20_000, 1).hash());
+        assert_eq!(p.pending_cost(&sender), 0);
+        p.add(tx(1, 2, 20_000, 1), &s, 0).unwrap();
+    }
+
+    #[test]
+    fn pending_orders_by_tip_respecting_sender_nonces() {
+        let s = state(&[1, 2]);
+        let mut p = pool(MempoolConfig::default());
+        p.add(tx(1, 0, 100, 5), &s, 0).unwrap();
+        p.add(tx(1, 1, 100, 50), &s, 0).unwrap();
+        p.add(tx(2, 0, 100, 20), &s, 0).unwrap();
+        // nonce 不连续的交易不会入选
+        p.add(tx(2, 2, 100, 90), &s, 0).unwrap();
+
+        let order: Vec<(Address, u64)> = p
+            .pending(&s, 10, 10)
+            .iter()
+            .map(|t| (t.sender(), t.tx.nonce))
+            .collect();
+        let a1 = Address::from_pubkey(&key(1).public_key());
+        let a2 = Address::from_pubkey(&key(2).public_key());
+        assert_eq!(order, vec![(a2, 0), (a1, 0), (a1, 1)]);
+        assert!(p.pending(&s, 101, 10).is_empty());
+    }
+
+    #[test]
+    fn evicts_cheapest_tail_and_expired() {
+        let s = state(&[1, 2, 3]);
+        let mut p = pool(MempoolConfig {
+            max_txs: 2,
+            max_age_ms: 1_000,
+            ..MempoolConfig::default()
+        });
+        let cheap = p.add(tx(1, 0, 50, 1), &s, 0).unwrap().hash;
+        p.add(tx(2, 0, 100, 1), &s, 500).unwrap();
+        let added = p.add(tx(3, 0, 80, 1), &s, 600).unwrap();
+        assert_eq!(added.evicted, vec![cheap]);
+        assert_eq!(p.add(tx(1, 0, 60, 1), &s, 700), Err(MempoolError::PoolFull));
+
+        assert_eq!(p.evict_expired(1_550).len(), 1);
+        assert_eq!(p.len(), 1);
+    }
+
+    #[test]
+    fn rejected_replacement_restores_replaced_and_evicted() {
+        let s = state(&[1, 2, 3, 4]);
+        let small = [
+            tx(1, 0, 50, 1),
+            tx(2, 0, 60, 1),
+            tx(3, 0, 1_000, 1),
+            tx(4, 0, 70, 7),
+        ];
+        let size = |stx: &SignedTransaction| serde_json::to_vec(stx).unwrap().len();
+        let mut p = pool(MempoolConfig {
+            max_bytes: small.iter().map(size).sum(),
+            ..MempoolConfig::default()
+        });
+        for stx in &small {
+            p.add(stx.clone(), &s, 0).unwrap();
+        }
+        let before: Vec<H256> = p.iter().map(|p| p.hash).collect();
+
+        // 替换交易体积过大：淘汰两笔最便宜的交易后仍超限，自身成为淘汰对象
+        let big = signed(
+            4,
+            Transaction {
+                action: Action::Call {
+                    to: Address([9u8; 20]),
+                    input: vec![7; 1_000],
+                },
+                gas_limit: 1_000_000,
+                max_fee_per_gas: 80,
+                max_priority_fee_per_gas: 8,
+                ..small[3].tx.clone()
+            },
+        );
+        assert_eq!(p.add(big, &s, 1), Err(MempoolError::PoolFull));
+        assert_eq!(p.iter().map(|p| p.hash).collect::<Vec<_>>(), before);
+        assert_eq!(p.size_bytes(), small.iter().map(size).sum::<usize>());
+    }
+}
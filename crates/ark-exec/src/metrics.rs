@@ -0,0 +1,56 @@
+//! 执行层指标：区块 / 交易执行耗时与交易池规模。
+use ark_metrics::{exponential_buckets, Histogram, IntCounter, IntGauge};
+use std::sync::LazyLock;
+
+pub(crate) static BLOCK_SECONDS: LazyLock<Histogram> = LazyLock::new(|| {
+    ark_metrics::histogram(
+        "exec_block_seconds",
+        "Time to execute a block, including end-of-block processing",
+        exponential_buckets(0.001, 2.0, 14).expect("valid buckets"),
+    )
+});
+pub(crate) static TX_SECONDS: LazyLock<Histogram> = LazyLock::new(|| {
+    ark_metrics::histogram(
+        "exec_tx_seconds",
+        "Time to execute a single transaction in a block",
+        exponential_buckets(0.00001, 4.0, 10).expect("valid buckets"),
+    )
+});
+pub(crate) static TXS_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    ark_metrics::int_counter("exec_txs_total", "Transactions included in built blocks")
+});
+pub(crate) static GAS_USED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    ark_metrics::int_counter(
+        "exec_gas_used_total",
+        "Gas used by transactions in built blocks",
+    )
+});
+pub(crate) static MEMPOOL_TXS: LazyLock<IntGauge> =
+    LazyLock::new(|| ark_metrics::int_gauge("mempool_transactions", "Transactions in the mempool"));
+pub(crate) static MEMPOOL_BYTES: LazyLock<IntGauge> = LazyLock::new(|| {
+    ark_metrics::int_gauge("mempool_bytes", "Encoded size of mempool transactions")
+});
+pub(crate) static MEMPOOL_ADMITTED: LazyLock<IntCounter> = LazyLock::new(|| {
+    ark_metrics::int_counter(
+        "mempool_admitted_total",
+        "Transactions accepted into the mempool",
+    )
+});
+pub(crate) static MEMPOOL_REJECTED: LazyLock<IntCounter> = LazyLock::new(|| {
+    ark_metrics::int_counter(
+        "mempool_rejected_total",
+        "Transactions rejected by the mempool",
+    )
+});
+
+/// 在全局注册表中注册本 crate 的指标。
+pub fn register() {
+    LazyLock::force(&BLOCK_SECONDS);
+    LazyLock::force(&TX_SECONDS);
+    LazyLock::force(&TXS_TOTAL);
+    LazyLock::force(&GAS_USED_TOTAL);
+    LazyLock::force(&MEMPOOL_TXS);
+    LazyLock::force(&MEMPOOL_BYTES);
+    LazyLock::force(&MEMPOOL_ADMITTED);
+    LazyLock::force(&MEMPOOL_REJECTED);
+}
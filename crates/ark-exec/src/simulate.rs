@@ -0,0 +1,228 @@
+//! 交易模拟与 gas 估算：在调用方提供的状态上执行后整体回滚（checkpoint / revert_to），
+//! 调用方通常传入最新状态的副本，链上状态不受影响。
+use crate::error::ExecError;
+use crate::executor::{BlockEnv, Executor, TxOutcome};
+use crate::state::State;
+use ark_types::{Amount, CallRequest, CallResult, ExecStatus, Gas, Transaction};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SimulateError {
+    #[error(transparent)]
+    Invalid(#[from] ExecError),
+    #[error("execution reverted: {0}")]
+    Reverted(String),
+    #[error("out of gas at gas limit {0}")]
+    OutOfGas(Gas),
+}
+
+impl Executor {
+    /// 由模拟请求构造完整交易：nonce 取当前值，缺省费用为刚好可打包，
+    /// 缺省 gas_limit 为区块上限与发送方余额可负担量中的较小者。
+    pub fn call_transaction(
+        &self,
+        state: &State,
+        call: &CallRequest,
+        env: &BlockEnv,
+    ) -> Transaction {
+        let tip = call.max_priority_fee_per_gas.unwrap_or(0);
+        let max_fee = call
+            .max_fee_per_gas
+            .unwrap_or_else(|| env.base_fee.saturating_add(tip));
+        let gas_limit = call.gas_limit.unwrap_or_else(|| {
+            let spendable = state.balance(&call.from).saturating_sub(call.value);
+            match spendable.checked_div(max_fee) {
+                // 余额连固有 gas 都不够时保留固有 gas，让校验报告余额不足
+                Some(affordable) => (affordable.min(env.gas_limit as Amount) as Gas)
+                    .max(self.intrinsic_gas(&call.action)),
+                None => env.gas_limit,
+            }
+        });
+        Transaction {
+            chain_id: env.chain_id.clone(),
+            nonce: state.nonce(&call.from),
+            action: call.action.clone(),
+            value: call.value,
+            gas_limit,
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: tip,
+        }
+    }
+
+    /// 执行模拟请求并返回结果；state 执行后恢复原样。
+    pub fn simulate(
+        &self,
+        state: &mut State,
+        call: &CallRequest,
+        env: &BlockEnv,
+    ) -> Result<CallResult, ExecError> {
+        let tx = self.call_transaction(state, call, env);
+        let out = self.dry_run(state, call, &tx, env)?;
+        Ok(CallResult {
+            status: out.status,
+            gas_used: out.gas_used,
+            gas_limit: tx.gas_limit,
+            output: out.output,
+            logs: out.logs,
+            contract_address: out.contract_address,
+            revert_reason: out.revert_reason,
+        })
+    }
+
+    /// 估算使交易成功所需的最小 gas_limit。
+    ///
+    /// 存储退款使实际计费低于执行峰值，因此在 [固有 gas, 上限] 间二分查找，
+    /// 而不是直接返回 gas_used。
+    pub fn estimate_gas(
+        &self,
+        state: &mut State,
+        call: &CallRequest,
+        env: &BlockEnv,
+    ) -> Result<Gas, SimulateError> {
+        let mut tx = self.call_transaction(state, call, env);
+        let cap = tx.gas_limit;
+        let out = self.dry_run(state, call, &tx, env)?;
+        match out.status {
+            ExecStatus::Success => {}
+            ExecStatus::Reverted => {
+                return Err(SimulateError::Reverted(
+                    out.revert_reason.unwrap_or_default(),
+                ))
+            }
+            ExecStatus::OutOfGas => return Err(SimulateError::OutOfGas(cap)),
+        }
+
+        // 不变式：lo 失败，hi 成功
+        let mut lo = self.intrinsic_gas(&tx.action).saturating_sub(1);
+        let mut hi = cap;
+        let mut succeeds = |gas_limit: Gas, state: &mut State| {
+            tx.gas_limit = gas_limit;
+            matches!(
+                self.dry_run(state, call, &tx, env),
+                Ok(TxOutcome {
+                    status: ExecStatus::Success,
+                    ..
+                })
+            )
+        };
+        // 无退款时 gas_used 即为答案，先试一次以减少迭代
+        if out.gas_used > lo && out.gas_used < hi {
+            if succeeds(out.gas_used, state) {
+                hi = out.gas_used;
+            } else {
+                lo = out.gas_used;
+            }
+        }
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if succeeds(mid, state) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        Ok(hi)
+    }
+
+    fn dry_run(
+        &self,
+        state: &mut State,
+        call: &CallRequest,
+        tx: &Transaction,
+        env: &BlockEnv,
+    ) -> Result<TxOutcome, ExecError> {
+        let cp = state.checkpoint();
+        let out = self.apply_as(state, call.from, tx, env);
+        state.revert_to(cp);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::tests::{env, funded, params, signed};
+    use crate::vm::op;
+    use ark_types::{Action, Address, H256};
+
+    #[test]
+    fn simulation_leaves_state_untouched_and_estimates_with_refunds() {
+        let exec = Executor::new(&params());
+        let mut state = funded(&[1]);
+        let from = Address::from_pubkey(&[1; 33]);
+        // 输入第 0 字写到键 1；先写入 1，再估算清零调用（有退款）
+        let code = vec![op::PUSH, 1, 0, op::CALLDATALOAD, op::PUSH, 1, 1, op::SSTORE];
+        let deploy = signed(1, 0, Action::Deploy { code }, 200_000);
+        let to = exec
+            .apply(&mut state, &deploy, &env())
+            .unwrap()
+            .contract_address
+            .unwrap();
+        let mut input = vec![0u8; 16];
+        input[15] = 1;
+        exec.apply(
+            &mut state,
+            &signed(1, 1, Action::Call { to, input }, 100_000),
+            &env(),
+        )
+        .unwrap();
+        state.commit();
+        let root = state.state_root();
+
+        let clear = CallRequest {
+            from,
+            action: Action::Call {
+                to,
+                input: vec![0u8; 16],
+            },
+            value: 0,
+            gas_limit: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let result = exec.simulate(&mut state, &clear, &env()).unwrap();
+        assert_eq!(result.status, ExecStatus::Success);
+        let raw = 21_000 + 16 * 16 + 2 + 3 + 2 + 5_000;
+        assert_eq!(result.gas_used, raw - 4_800);
+        assert_eq!(state.state_root(), root);
+
+        let estimate = exec.estimate_gas(&mut state, &clear, &env()).unwrap();
+        assert_eq!(estimate, raw);
+        assert_eq!(state.state_root(), root);
+        assert_eq!(state.storage(&to, &H256::from_u128(1)), H256::from_u128(1));
+
+        // 回滚的合约：估算报告原因
+        let revert = signed(
+            1,
+            2,
+            Action::Deploy {
+                code: vec![op::PUSH, 1, 42, op::REVERT],
+            },
+            200_000,
+        );
+        let bad = exec
+            .apply(&mut state, &revert, &env())
+            .unwrap()
+            .contract_address
+            .unwrap();
+        let call = CallRequest {
+            action: Action::Call {
+                to: bad,
+                input: vec![],
+            },
+            ..clear
+        };
+        assert_eq!(
+            exec.estimate_gas(&mut state, &call, &env()),
+            Err(SimulateError::Reverted("reverted: revert code 42".into()))
+        );
+        let poor = CallRequest {
+            from: Address([3; 20]),
+            max_fee_per_gas: Some(10),
+            ..call
+        };
+        assert!(matches!(
+            exec.simulate(&mut state, &poor, &env()),
+            Err(ExecError::InsufficientFunds { .. })
+        ));
+    }
+}
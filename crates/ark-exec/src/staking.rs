@@ -0,0 +1,519 @@
+//! 原生质押模块：创建验证者、委托、解除委托（解绑期）、提取，以及按 epoch 分配奖励并轮换验证者集合。
+//!
+//! 资金流：
+//! - 抵押 / 委托金额转入 STAKING_POOL；解绑到期后由 STAKING_POOL 转回委托人
+//! - 每个 epoch 结束时向 REWARD_POOL 增发 epoch_reward，池内全部余额（含配置为 base fee
+//!   接收地址时收到的费用）按投票权分给当期活跃验证者；运营者先取佣金，其余按委托额比例入账委托人
+//! - 随后按抵押额选出下一 epoch 的验证者集合（抵押额不低于 min_stake，至多 max_validators 个）
+//!
+//! 模块存储键：
+//! - `staking/del/` + 验证者(20B) + 委托人(20B) -> 委托额（u128 大端）
+//! - `staking/unb/` + 委托人(20B) -> 解绑队列 JSON
+//! - `staking/set` -> 当前 epoch 生效的 ValidatorSet JSON
+use crate::state::State;
+use ark_types::primitives::{amount, hex_bytes};
+use ark_types::{Address, Amount, ChainParams, StakingOp, ValidatorInfo, ValidatorSet};
+use serde::{Deserialize, Serialize};
+
+/// 抵押与解绑中资金的托管账户。
+pub const STAKING_POOL: Address = Address(*b"ark.staking.pool\0\0\0\0");
+/// 待分配奖励账户。
+pub const REWARD_POOL: Address = Address(*b"ark.staking.reward\0\0");
+pub const MAX_COMMISSION_BPS: u16 = 10_000;
+
+const KEY_DELEGATION: &[u8] = b"staking/del/";
+const KEY_UNBONDING: &[u8] = b"staking/unb/";
+const KEY_SET: &[u8] = b"staking/set";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum StakingError {
+    #[error("validator {0} already exists")]
+    ValidatorExists(Address),
+    #[error("unknown validator {0}")]
+    UnknownValidator(Address),
+    #[error("invalid consensus pubkey: expected 33-byte compressed secp256k1 key")]
+    InvalidPubkey,
+    #[error("consensus pubkey already registered")]
+    PubkeyInUse,
+    #[error("commission {0} bps exceeds 10000")]
+    InvalidCommission(u16),
+    #[error("self stake {amount} below min_stake {min}")]
+    BelowMinStake { amount: Amount, min: Amount },
+    #[error("amount must be positive")]
+    ZeroAmount,
+    #[error("operation does not accept a value transfer")]
+    UnexpectedValue,
+    #[error("insufficient balance")]
+    InsufficientBalance,
+    #[error("insufficient delegation: have {have}, want {want}")]
+    InsufficientDelegation { have: Amount, want: Amount },
+    #[error("no matured unbonding entries")]
+    NothingToWithdraw,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validator {
+    pub operator: Address,
+    /// 共识公钥（压缩 secp256k1）
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    /// 已抵押总额（自抵押 + 委托）
+    #[serde(with = "amount")]
+    pub stake: Amount,
+    /// 佣金比例（万分比）
+    #[serde(default)]
+    pub commission_bps: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnbondingEntry {
+    pub validator: Address,
+    #[serde(with = "amount")]
+    pub amount: Amount,
+    /// 该 epoch 起可提取
+    pub complete_epoch: u64,
+}
+
+/// epoch 结束时的结算结果。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpochTransition {
+    pub ended_epoch: u64,
+    pub distributed: Amount,
+    pub next_set: ValidatorSet,
+}
+
+#[derive(Clone, Debug)]
+pub struct Staking {
+    pub min_stake: Amount,
+    pub unbonding_epochs: u64,
+    pub max_validators: u32,
+    pub epoch_reward: Amount,
+    pub epoch_blocks: u64,
+}
+
+impl Staking {
+    pub fn new(params: &ChainParams) -> Self {
+        Self {
+            min_stake: params.staking.min_stake,
+            unbonding_epochs: params.staking.unbonding_epochs,
+            max_validators: params.staking.max_validators,
+            epoch_reward: params.staking.epoch_reward,
+            epoch_blocks: params.epoch_blocks.max(1),
+        }
+    }
+
+    pub fn epoch_of(&self, height: u64) -> u64 {
+        height / self.epoch_blocks
+    }
+
+    /// height 是否为所在 epoch 的最后一个区块。
+    pub fn is_epoch_end(&self, height: u64) -> bool {
+        (height + 1) % self.epoch_blocks == 0
+    }
+
+    /// 执行一笔质押交易。失败时调用方负责回滚状态。
+    pub fn apply(
+        &self,
+        state: &mut State,
+        sender: &Address,
+        value: Amount,
+        op: &StakingOp,
+        height: u64,
+    ) -> Result<(), StakingError> {
+        match op {
+            StakingOp::CreateValidator {
+                pubkey,
+                commission_bps,
+            } => self.create_validator(state, sender, pubkey, *commission_bps, value),
+            StakingOp::Delegate { validator } => self.delegate(state, sender, validator, value),
+            StakingOp::Undelegate { validator, amount } => {
+                if value != 0 {
+                    return Err(StakingError::UnexpectedValue);
+                }
+                self.undelegate(state, sender, validator, *amount, self.epoch_of(height))
+            }
+            StakingOp::Withdraw => {
+                if value != 0 {
+                    return Err(StakingError::UnexpectedValue);
+                }
+                self.withdraw(state, sender, self.epoch_of(height))
+                    .map(|_| ())
+            }
+        }
+    }
+
+    pub fn create_validator(
+        &self,
+        state: &mut State,
+        operator: &Address,
+        pubkey: &[u8],
+        commission_bps: u16,
+        stake: Amount,
+    ) -> Result<(), StakingError> {
+        if state.validator(operator).is_some() {
+            return Err(StakingError::ValidatorExists(*operator));
+        }
+        if pubkey.len() != 33 || !matches!(pubkey[0], 0x02 | 0x03) {
+            return Err(StakingError::InvalidPubkey);
+        }
+        if state.validators().any(|v| v.pubkey == pubkey) {
+            return Err(StakingError::PubkeyInUse);
+        }
+        if commission_bps > MAX_COMMISSION_BPS {
+            return Err(StakingError::InvalidCommission(commission_bps));
+        }
+        if stake < self.min_stake {
+            return Err(StakingError::BelowMinStake {
+                amount: stake,
+                min: self.min_stake,
+            });
+        }
+        if !state.transfer(operator, &STAKING_POOL, stake) {
+            return Err(StakingError::InsufficientBalance);
+        }
+        state.set_validator(Validator {
+            operator: *operator,
+            pubkey: pubkey.to_vec(),
+            stake,
+            commission_bps,
+        });
+        set_delegation(state, operator, operator, stake);
+        Ok(())
+    }
+
+    pub fn delegate(
+        &self,
+        state: &mut State,
+        delegator: &Address,
+        validator: &Address,
+        amount: Amount,
+    ) -> Result<(), StakingError> {
+        if amount == 0 {
+            return Err(StakingError::ZeroAmount);
+        }
+        let mut v = state
+            .validator(validator)
+            .cloned()
+            .ok_or(StakingError::UnknownValidator(*validator))?;
+        if !state.transfer(delegator, &STAKING_POOL, amount) {
+            return Err(StakingError::InsufficientBalance);
+        }
+        v.stake += amount;
+        state.set_validator(v);
+        let current = delegation(state, validator, delegator);
+        set_delegation(state, validator, delegator, current + amount);
+        Ok(())
+    }
+
+    pub fn undelegate(
+        &self,
+        state: &mut State,
+        delegator: &Address,
+        validator: &Address,
+        amount: Amount,
+        current_epoch: u64,
+    ) -> Result<(), StakingError> {
+        if amount == 0 {
+            return Err(StakingError::ZeroAmount);
+        }
+        let mut v = state
+            .validator(validator)
+            .cloned()
+            .ok_or(StakingError::UnknownValidator(*validator))?;
+        let have = delegation(state, validator, delegator);
+        if have < amount {
+            return Err(StakingError::InsufficientDelegation { have, want: amount });
+        }
+        set_delegation(state, validator, delegator, have - amount);
+        v.stake -= amount;
+        state.set_validator(v);
+
+        let mut queue = unbonding(state, delegator);
+        queue.push(UnbondingEntry {
+            validator: *validator,
+            amount,
+            complete_epoch: current_epoch + self.unbonding_epochs,
+        });
+        set_unbonding(state, delegator, &queue);
+        Ok(())
+    }
+
+    /// 提取全部已到期的解绑金额，返回提取总额。
+    pub fn withdraw(
+        &self,
+        state: &mut State,
+        delegator: &Address,
+        current_epoch: u64,
+    ) -> Result<Amount, StakingError> {
+        let (matured, pending): (Vec<_>, Vec<_>) = unbonding(state, delegator)
+            .into_iter()
+            .partition(|e| e.complete_epoch <= current_epoch);
+        let total: Amount = matured.iter().map(|e| e.amount).sum();
+        if total == 0 {
+            return Err(StakingError::NothingToWithdraw);
+        }
+        if !state.transfer(&STAKING_POOL, delegator, total) {
+            return Err(StakingError::InsufficientBalance);
+        }
+        set_unbonding(state, delegator, &pending);
+        Ok(total)
+    }
+
+    /// 按抵押额选出指定 epoch 的验证者集合。
+    pub fn select_set(&self, state: &State, epoch: u64) -> ValidatorSet {
+        let mut eligible: Vec<&Validator> = state
+            .validators()
+            .filter(|v| v.stake >= self.min_stake && v.stake > 0)
+            .collect();
+        eligible.sort_by(|a, b| b.stake.cmp(&a.stake).then(a.operator.cmp(&b.operator)));
+        ValidatorSet {
+            epoch,
+            validators: eligible
+                .into_iter()
+                .take(self.max_validators as usize)
+                .map(|v| ValidatorInfo {
+                    operator: v.operator,
+                    pubkey: v.pubkey.clone(),
+                    power: v.stake,
+                })
+                .collect(),
+        }
+    }
+
+    /// epoch 结算：分配奖励，并写入下一 epoch 的验证者集合。
+    pub fn end_epoch(&self, state: &mut State, epoch: u64) -> EpochTransition {
+        state.add_balance(&REWARD_POOL, self.epoch_reward);
+        let pool = state.balance(&REWARD_POOL);
+        let set = active_set(state);
+        let total_power = set.total_power();
+        let mut distributed = 0;
+
+        if pool > 0 && total_power > 0 {
+            for info in &set.validators {
+                let Some(v) = state.validator(&info.operator).cloned() else {
+                    continue;
+                };
+                let share = mul_div(pool, info.power, total_power);
+                let commission = mul_div(share, v.commission_bps as Amount, 10_000);
+                let mut paid = pay_reward(state, &v.operator, commission);
+
+                let rest = share - commission;
+                let delegations = delegations_of(state, &v.operator);
+                let bonded: Amount = delegations.iter().map(|(_, a)| *a).sum();
+                if bonded == 0 {
+                    paid += pay_reward(state, &v.operator, rest);
+                } else {
+                    for (delegator, amount) in delegations {
+                        paid += pay_reward(state, &delegator, mul_div(rest, amount, bonded));
+                    }
+                }
+                distributed += paid;
+            }
+        }
+
+        let next_set = self.select_set(state, epoch + 1);
+        store_active_set(state, &next_set);
+        EpochTransition {
+            ended_epoch: epoch,
+            distributed,
+            next_set,
+        }
+    }
+}
+
+fn pay_reward(state: &mut State, to: &Address, amount: Amount) -> Amount {
+    if state.transfer(&REWARD_POOL, to, amount) {
+        amount
+    } else {
+        0
+    }
+}
+
+/// a * b / c（向下取整），乘法溢出时退化为先除后乘。
+fn mul_div(a: Amount, b: Amount, c: Amount) -> Amount {
+    if c == 0 {
+        return 0;
+    }
+    match a.checked_mul(b) {
+        Some(p) => p / c,
+        None => a / c * b,
+    }
+}
+
+fn delegation_key(validator: &Address, delegator: &Address) -> Vec<u8> {
+    let mut k = KEY_DELEGATION.to_vec();
+    k.extend_from_slice(validator.as_bytes());
+    k.extend_from_slice(delegator.as_bytes());
+    k
+}
+
+pub fn delegation(state: &State, validator: &Address, delegator: &Address) -> Amount {
+    state
+        .module_get(&delegation_key(validator, delegator))
+        .and_then(|v| v.try_into().ok())
+        .map(u128::from_be_bytes)
+        .unwrap_or(0)
+}
+
+fn set_delegation(state: &mut State, validator: &Address, delegator: &Address, amount: Amount) {
+    let key = delegation_key(validator, delegator);
+    if amount == 0 {
+        state.module_delete(&key);
+    } else {
+        state.module_set(key, amount.to_be_bytes().to_vec());
+    }
+}
+
+/// 创世抵押：直接入账质押池并记为自委托（不从运营者余额扣除）。
+pub fn record_genesis_stake(state: &mut State, operator: &Address, stake: Amount) {
+    state.add_balance(&STAKING_POOL, stake);
+    let current = delegation(state, operator, operator);
+    set_delegation(state, operator, operator, current + stake);
+}
+
+/// 某验证者的全部委托（委托人地址升序）。
+pub fn delegations_of(state: &State, validator: &Address) -> Vec<(Address, Amount)> {
+    let mut prefix = KEY_DELEGATION.to_vec();
+    prefix.extend_from_slice(validator.as_bytes());
+    state
+        .module_prefix(&prefix)
+        .filter_map(|(k, v)| {
+            let mut d = [0u8; 20];
+            d.copy_from_slice(k.get(prefix.len()..)?);
+            Some((Address(d), u128::from_be_bytes(v.try_into().ok()?)))
+        })
+        .collect()
+}
+
+fn unbonding_key(delegator: &Address) -> Vec<u8> {
+    let mut k = KEY_UNBONDING.to_vec();
+    k.extend_from_slice(delegator.as_bytes());
+    k
+}
+
+pub fn unbonding(state: &State, delegator: &Address) -> Vec<UnbondingEntry> {
+    state
+        .module_get(&unbonding_key(delegator))
+        .and_then(|v| serde_json::from_slice(v).ok())
+        .unwrap_or_default()
+}
+
+fn set_unbonding(state: &mut State, delegator: &Address, queue: &[UnbondingEntry]) {
+    let key = unbonding_key(delegator);
+    if queue.is_empty() {
+        state.module_delete(&key);
+    } else {
+        state.module_set(
+            key,
+            serde_json::to_vec(queue).expect("unbonding serializes"),
+        );
+    }
+}
+
+/// 当前 epoch 生效的验证者集合。
+pub fn active_set(state: &State) -> ValidatorSet {
+    state
+        .module_get(KEY_SET)
+        .and_then(|v| serde_json::from_slice(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn store_active_set(state: &mut State, set: &ValidatorSet) {
+    state.module_set(
+        KEY_SET.to_vec(),
+        serde_json::to_vec(set).expect("validator set serializes"),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn staking() -> Staking {
+        Staking {
+            min_stake: 1_000,
+            unbonding_epochs: 2,
+            max_validators: 2,
+            epoch_reward: 1_000,
+            epoch_blocks: 10,
+        }
+    }
+
+    fn pubkey(b: u8) -> Vec<u8> {
+        let mut pk = vec![0x02];
+        pk.extend_from_slice(&[b; 32]);
+        pk
+    }
+
+    fn addr(b: u8) -> Address {
+        Address([b; 20])
+    }
+
+    fn funded() -> State {
+        let mut s = State::new();
+        for b in 1..=4 {
+            s.add_balance(&addr(b), 100_000);
+        }
+        s
+    }
+
+    #[test]
+    fn delegate_undelegate_and_withdraw_after_unbonding() {
+        let st = staking();
+        let mut s = funded();
+        st.create_validator(&mut s, &addr(1), &pubkey(1), 1_000, 5_000)
+            .unwrap();
+        st.delegate(&mut s, &addr(2), &addr(1), 3_000).unwrap();
+        assert_eq!(s.validator(&addr(1)).unwrap().stake, 8_000);
+        assert_eq!(s.balance(&STAKING_POOL), 8_000);
+
+        st.undelegate(&mut s, &addr(2), &addr(1), 1_000, 5).unwrap();
+        assert_eq!(delegation(&s, &addr(1), &addr(2)), 2_000);
+        assert_eq!(
+            st.withdraw(&mut s, &addr(2), 6),
+            Err(StakingError::NothingToWithdraw)
+        );
+        assert_eq!(st.withdraw(&mut s, &addr(2), 7), Ok(1_000));
+        assert_eq!(s.balance(&addr(2)), 100_000 - 3_000 + 1_000);
+        assert!(unbonding(&s, &addr(2)).is_empty());
+
+        assert!(matches!(
+            st.undelegate(&mut s, &addr(2), &addr(1), 5_000, 7),
+            Err(StakingError::InsufficientDelegation { .. })
+        ));
+        assert_eq!(
+            st.create_validator(&mut s, &addr(3), &pubkey(1), 0, 5_000),
+            Err(StakingError::PubkeyInUse)
+        );
+    }
+
+    #[test]
+    fn epoch_rewards_pay_commission_and_rotate_set() {
+        let st = staking();
+        let mut s = funded();
+        st.create_validator(&mut s, &addr(1), &pubkey(1), 1_000, 3_000)
+            .unwrap();
+        st.create_validator(&mut s, &addr(3), &pubkey(3), 0, 1_000)
+            .unwrap();
+        st.delegate(&mut s, &addr(2), &addr(1), 1_000).unwrap();
+        let set = st.select_set(&s, 0);
+        store_active_set(&mut s, &set);
+
+        let before1 = s.balance(&addr(1));
+        let before2 = s.balance(&addr(2));
+        let t = st.end_epoch(&mut s, 0);
+        // 验证者 1 份额 800：佣金 80，其余 720 按 3:1 分给运营者与委托人
+        assert_eq!(s.balance(&addr(1)) - before1, 80 + 540);
+        assert_eq!(s.balance(&addr(2)) - before2, 180);
+        assert_eq!(t.distributed, 1_000);
+        assert_eq!(s.balance(&REWARD_POOL), 0);
+
+        // 新验证者抵押更多，下一 epoch 挤出抵押最少的验证者
+        st.create_validator(&mut s, &addr(4), &pubkey(4), 0, 2_000)
+            .unwrap();
+        let t = st.end_epoch(&mut s, 1);
+        let ops: Vec<Address> = t.next_set.validators.iter().map(|v| v.operator).collect();
+        assert_eq!(ops, vec![addr(1), addr(4)]);
+        assert_eq!(active_set(&s).epoch, 2);
+    }
+}
@@ -0,0 +1,378 @@
+//! 账户状态（内存实现）与回滚日志。
+//!
+//! - 账户：nonce / balance / code_hash；代码按哈希去重存放
+//! - 合约存储：(地址, 键) -> 值，值为零即删除
+//! - 验证者记录：运营者地址 -> Validator
+//! - 原生模块存储：任意键 -> 字节（质押委托、解绑队列、验证者集合等）
+//! - 执行期间发出的事件日志：同样记入 journal，回滚时一并撤销；不属于状态条目
+//! - 每次修改写入 journal；checkpoint/revert_to 用于交易或调用级回滚
+//! - entries()：状态的扁平键值编码，既是持久化格式，也是状态根的 Merkle 叶子
+//!   （编码见 `ark_types::account`）
+//! - 各表为结构共享的持久化有序映射：clone 为 O(1)，副本上的修改只复制被改动的路径，
+//!   因此出块、导入与模拟执行都在最新状态之上的写时复制副本上进行
+use crate::staking::Validator;
+use ark_types::account::{account_key, prefixed, storage_key};
+use ark_types::{merkle, sha256, Address, Amount, Log, H256};
+use imbl::OrdMap;
+
+pub use ark_types::account::{
+    decode_account, encode_account, Account, PREFIX_ACCOUNT, PREFIX_CODE, PREFIX_MODULE,
+    PREFIX_STORAGE, PREFIX_VALIDATOR,
+};
+
+#[derive(Clone, Debug)]
+enum Journal {
+    Account(Address, Option<Account>),
+    Storage(Address, H256, Option<H256>),
+    Code(H256),
+    Validator(Address, Option<Validator>),
+    Module(Vec<u8>, Option<Vec<u8>>),
+    Log,
+}
+
+/// 回滚点（journal 长度）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+#[derive(Clone, Debug, Default)]
+pub struct State {
+    accounts: OrdMap<Address, Account>,
+    code: OrdMap<H256, Vec<u8>>,
+    storage: OrdMap<(Address, H256), H256>,
+    validators: OrdMap<Address, Validator>,
+    modules: OrdMap<Vec<u8>, Vec<u8>>,
+    logs: Vec<Log>,
+    journal: Vec<Journal>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn account(&self, addr: &Address) -> Account {
+        self.accounts.get(addr).cloned().unwrap_or_default()
+    }
+
+    pub fn balance(&self, addr: &Address) -> Amount {
+        self.accounts.get(addr).map(|a| a.balance).unwrap_or(0)
+    }
+
+    pub fn nonce(&self, addr: &Address) -> u64 {
+        self.accounts.get(addr).map(|a| a.nonce).unwrap_or(0)
+    }
+
+    pub fn set_account(&mut self, addr: Address, account: Account) {
+        let prev = self.accounts.insert(addr, account);
+        self.journal.push(Journal::Account(addr, prev));
+    }
+
+    pub fn add_balance(&mut self, addr: &Address, amount: Amount) {
+        if amount == 0 {
+            return;
+        }
+        let mut acc = self.account(addr);
+        acc.balance = acc.balance.saturating_add(amount);
+        self.set_account(*addr, acc);
+    }
+
+    /// 扣减余额；不足时返回 false 且不修改状态。
+    pub fn sub_balance(&mut self, addr: &Address, amount: Amount) -> bool {
+        if amount == 0 {
+            return true;
+        }
+        let mut acc = self.account(addr);
+        match acc.balance.checked_sub(amount) {
+            Some(b) => {
+                acc.balance = b;
+                self.set_account(*addr, acc);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn transfer(&mut self, from: &Address, to: &Address, amount: Amount) -> bool {
+        if !self.sub_balance(from, amount) {
+            return false;
+        }
+        self.add_balance(to, amount);
+        true
+    }
+
+    pub fn increment_nonce(&mut self, addr: &Address) {
+        let mut acc = self.account(addr);
+        acc.nonce += 1;
+        self.set_account(*addr, acc);
+    }
+
+    pub fn code(&self, addr: &Address) -> Option<&[u8]> {
+        let h = self.accounts.get(addr)?.code_hash?;
+        self.code.get(&h).map(Vec::as_slice)
+    }
+
+    pub fn set_code(&mut self, addr: &Address, code: Vec<u8>) {
+        let h = sha256(&code);
+        if !self.code.contains_key(&h) {
+            self.code.insert(h, code);
+            self.journal.push(Journal::Code(h));
+        }
+        let mut acc = self.account(addr);
+        acc.code_hash = Some(h);
+        self.set_account(*addr, acc);
+    }
+
+    pub fn storage(&self, addr: &Address, key: &H256) -> H256 {
+        self.storage
+            .get(&(*addr, *key))
+            .copied()
+            .unwrap_or(H256::ZERO)
+    }
+
+    pub fn set_storage(&mut self, addr: &Address, key: H256, value: H256) {
+        let prev = if value.is_zero() {
+            self.storage.remove(&(*addr, key))
+        } else {
+            self.storage.insert((*addr, key), value)
+        };
+        self.journal.push(Journal::Storage(*addr, key, prev));
+    }
+
+    pub fn validator(&self, operator: &Address) -> Option<&Validator> {
+        self.validators.get(operator)
+    }
+
+    pub fn validators(&self) -> impl Iterator<Item = &Validator> {
+        self.validators.values()
+    }
+
+    pub fn set_validator(&mut self, validator: Validator) {
+        let operator = validator.operator;
+        let prev = self.validators.insert(operator, validator);
+        self.journal.push(Journal::Validator(operator, prev));
+    }
+
+    pub fn remove_validator(&mut self, operator: &Address) {
+        if let Some(prev) = self.validators.remove(operator) {
+            self.journal.push(Journal::Validator(*operator, Some(prev)));
+        }
+    }
+
+    pub fn module_get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.modules.get(key).map(Vec::as_slice)
+    }
+
+    pub fn module_set(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        let prev = self.modules.insert(key.clone(), value);
+        self.journal.push(Journal::Module(key, prev));
+    }
+
+    pub fn module_delete(&mut self, key: &[u8]) {
+        if let Some(prev) = self.modules.remove(key) {
+            self.journal.push(Journal::Module(key.to_vec(), Some(prev)));
+        }
+    }
+
+    /// 以 prefix 开头的模块条目（按键升序）。
+    pub fn module_prefix<'a>(
+        &'a self,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = (&'a [u8], &'a [u8])> + 'a {
+        self.modules
+            .range(prefix.to_vec()..)
+            .take_while(move |(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.as_slice(), v.as_slice()))
+    }
+
+    /// 记录一条事件日志。
+    pub fn emit_log(&mut self, log: Log) {
+        self.logs.push(log);
+        self.journal.push(Journal::Log);
+    }
+
+    /// 取出已记录的日志（交易执行结束时调用）。
+    pub fn take_logs(&mut self) -> Vec<Log> {
+        std::mem::take(&mut self.logs)
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.journal.len())
+    }
+
+    /// 撤销 checkpoint 之后的全部修改。
+    pub fn revert_to(&mut self, cp: Checkpoint) {
+        while self.journal.len() > cp.0 {
+            match self.journal.pop().expect("journal entry") {
+                Journal::Account(addr, prev) => match prev {
+                    Some(acc) => {
+                        self.accounts.insert(addr, acc);
+                    }
+                    None => {
+                        self.accounts.remove(&addr);
+                    }
+                },
+                Journal::Storage(addr, key, prev) => match prev {
+                    Some(v) => {
+                        self.storage.insert((addr, key), v);
+                    }
+                    None => {
+                        self.storage.remove(&(addr, key));
+                    }
+                },
+                Journal::Code(h) => {
+                    self.code.remove(&h);
+                }
+                Journal::Module(key, prev) => match prev {
+                    Some(v) => {
+                        self.modules.insert(key, v);
+                    }
+                    None => {
+                        self.modules.remove(&key);
+                    }
+                },
+                Journal::Log => {
+                    self.logs.pop();
+                }
+                Journal::Validator(operator, prev) => match prev {
+                    Some(v) => {
+                        self.validators.insert(operator, v);
+                    }
+                    None => {
+                        self.validators.remove(&operator);
+                    }
+                },
+            }
+        }
+    }
+
+    /// 确认当前全部修改（清空回滚日志）。
+    pub fn commit(&mut self) {
+        self.journal.clear();
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = (&Address, &Account)> {
+        self.accounts.iter()
+    }
+
+    /// 按键升序输出全部状态条目。
+    pub fn entries(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut out = Vec::with_capacity(
+            self.accounts.len()
+                + self.code.len()
+                + self.modules.len()
+                + self.storage.len()
+                + self.validators.len(),
+        );
+        for (addr, acc) in &self.accounts {
+            out.push((account_key(addr), encode_account(acc)));
+        }
+        for (h, code) in &self.code {
+            out.push((prefixed(PREFIX_CODE, &[h.as_bytes()]), code.clone()));
+        }
+        for (k, v) in &self.modules {
+            out.push((prefixed(PREFIX_MODULE, &[k]), v.clone()));
+        }
+        for ((addr, key), value) in &self.storage {
+            out.push((storage_key(addr, key), value.0.to_vec()));
+        }
+        for (addr, v) in &self.validators {
+            out.push((
+                prefixed(PREFIX_VALIDATOR, &[addr.as_bytes()]),
+                serde_json::to_vec(v).expect("validator serializes"),
+            ));
+        }
+        out
+    }
+
+    /// 状态根：entries() 的 Merkle 根。
+    pub fn state_root(&self) -> H256 {
+        let entries = self.entries();
+        merkle::root(entries.iter().map(|(k, v)| (k.as_slice(), v.as_slice())))
+    }
+
+    /// 由 entries() 的输出重建状态。
+    pub fn from_entries<I>(entries: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+    {
+        let mut s = State::new();
+        for (k, v) in entries {
+            let (prefix, body) = k.split_first().ok_or("empty state key")?;
+            match (*prefix, body.len()) {
+                (PREFIX_ACCOUNT, 20) => {
+                    s.accounts.insert(address_at(body), decode_account(&v)?);
+                }
+                (PREFIX_CODE, 32) => {
+                    s.code.insert(h256_at(body), v);
+                }
+                (PREFIX_MODULE, _) => {
+                    s.modules.insert(body.to_vec(), v);
+                }
+                (PREFIX_STORAGE, 52) => {
+                    let value: [u8; 32] = v
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| "invalid storage value")?;
+                    s.storage
+                        .insert((address_at(body), h256_at(&body[20..])), H256(value));
+                }
+                (PREFIX_VALIDATOR, 20) => {
+                    let val: Validator = serde_json::from_slice(&v)
+                        .map_err(|e| format!("invalid validator entry: {e}"))?;
+                    s.validators.insert(address_at(body), val);
+                }
+                _ => return Err(format!("unknown state key: {}", hex_key(&k))),
+            }
+        }
+        Ok(s)
+    }
+}
+
+fn address_at(b: &[u8]) -> Address {
+    let mut a = [0u8; 20];
+    a.copy_from_slice(&b[..20]);
+    Address(a)
+}
+
+fn h256_at(b: &[u8]) -> H256 {
+    let mut h = [0u8; 32];
+    h.copy_from_slice(&b[..32]);
+    H256(h)
+}
+
+fn hex_key(k: &[u8]) -> String {
+    k.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_roundtrip_and_root_changes() {
+        let mut s = State::new();
+        let a = Address([1u8; 20]);
+        s.add_balance(&a, 100);
+        s.set_code(&a, vec![0x00]);
+        s.set_storage(&a, H256::from_u128(1), H256::from_u128(2));
+        let root = s.state_root();
+
+        let restored = State::from_entries(s.entries()).unwrap();
+        assert_eq!(restored.state_root(), root);
+        assert_eq!(restored.code(&a), Some(&[0x00][..]));
+
+        let cp = s.checkpoint();
+        s.add_balance(&a, 1);
+        assert_ne!(s.state_root(), root);
+        s.revert_to(cp);
+        assert_eq!(s.state_root(), root);
+
+        // 副本与原状态共享数据，修改互不影响
+        let mut overlay = s.clone();
+        overlay.set_storage(&a, H256::from_u128(1), H256::ZERO);
+        overlay.module_set(b"k".to_vec(), b"v".to_vec());
+        assert_eq!(s.storage(&a, &H256::from_u128(1)), H256::from_u128(2));
+        assert_eq!(s.module_get(b"k"), None);
+        assert_eq!(s.state_root(), root);
+    }
+}
@@ -0,0 +1,240 @@
+//! 协议升级与特性门。
+//!
+//! - 生效的特性门 = 创世 feature_gates ∪ 已激活升级开启的特性门，按区块查询
+//! - 升级计划来自创世 `upgrades` 或链上调度（治理），在计划高度的区块执行任何交易前激活
+//! - 计划中含本版本未实现的特性门时拒绝执行该区块：节点应停止并提示安装新版本，
+//!   而不是按旧规则继续出块造成分叉
+//!
+//! 模块存储键：
+//! - `upgrade/plan/` + 名称 -> 链上调度的 UpgradePlan JSON
+//! - `upgrade/done/` + 名称 -> 激活高度（u64 大端）
+//! - `upgrade/gates` -> 已激活的特性门名称列表 JSON
+use crate::executor::{BlockEnv, Executor};
+use crate::state::State;
+use ark_types::{FeatureGates, UpgradePlan};
+
+const KEY_PLAN: &[u8] = b"upgrade/plan/";
+const KEY_DONE: &[u8] = b"upgrade/done/";
+const KEY_GATES: &[u8] = b"upgrade/gates";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum UpgradeError {
+    #[error(
+        "upgrade {name} activates feature gate {gate} at height {height}, which this binary \
+         does not implement; install a release that supports {name} and restart"
+    )]
+    Unsupported {
+        name: String,
+        height: u64,
+        gate: String,
+    },
+    #[error(
+        "state has feature gate {0} activated by a newer release; this binary cannot run this chain"
+    )]
+    UnknownActivated(String),
+    #[error("upgrade {0} is already scheduled or applied")]
+    Duplicate(String),
+    #[error("upgrade {name} height {height} must be above the current height {current}")]
+    PastHeight {
+        name: String,
+        height: u64,
+        current: u64,
+    },
+}
+
+fn key(prefix: &[u8], name: &str) -> Vec<u8> {
+    [prefix, name.as_bytes()].concat()
+}
+
+/// 链上调度一次升级（供治理模块调用）；current 为当前已提交高度。
+pub fn schedule(state: &mut State, plan: UpgradePlan, current: u64) -> Result<(), UpgradeError> {
+    if plan.height <= current {
+        return Err(UpgradeError::PastHeight {
+            name: plan.name,
+            height: plan.height,
+            current,
+        });
+    }
+    if state.module_get(&key(KEY_PLAN, &plan.name)).is_some()
+        || applied(state, &plan.name).is_some()
+    {
+        return Err(UpgradeError::Duplicate(plan.name));
+    }
+    state.module_set(
+        key(KEY_PLAN, &plan.name),
+        serde_json::to_vec(&plan).expect("upgrade plan serializes"),
+    );
+    Ok(())
+}
+
+/// 链上调度且尚未激活的升级。
+pub fn scheduled(state: &State) -> Vec<UpgradePlan> {
+    state
+        .module_prefix(KEY_PLAN)
+        .filter_map(|(_, v)| serde_json::from_slice(v).ok())
+        .collect()
+}
+
+/// 升级的激活高度；尚未激活时为 None。
+pub fn applied(state: &State, name: &str) -> Option<u64> {
+    let raw = state.module_get(&key(KEY_DONE, name))?;
+    Some(u64::from_be_bytes(raw.try_into().ok()?))
+}
+
+fn activated_gates(state: &State) -> Vec<String> {
+    state
+        .module_get(KEY_GATES)
+        .and_then(|v| serde_json::from_slice(v).ok())
+        .unwrap_or_default()
+}
+
+fn check_supported(plan: &UpgradePlan) -> Result<(), UpgradeError> {
+    match plan
+        .gates
+        .iter()
+        .find(|g| !FeatureGates::KNOWN.contains(&g.as_str()))
+    {
+        Some(gate) => Err(UpgradeError::Unsupported {
+            name: plan.name.clone(),
+            height: plan.height,
+            gate: gate.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+impl Executor {
+    /// 在 state 上生效的特性门。
+    pub fn feature_gates(&self, state: &State) -> FeatureGates {
+        let mut gates = self.feature_gates.clone();
+        for gate in activated_gates(state) {
+            gates.enable(&gate);
+        }
+        gates
+    }
+
+    /// 在 height 区块开始时到期的升级（创世预定与链上调度，按名称排序）。
+    pub fn due_upgrades(&self, state: &State, height: u64) -> Vec<UpgradePlan> {
+        let mut due: Vec<_> = self
+            .upgrades
+            .iter()
+            .cloned()
+            .chain(scheduled(state))
+            .filter(|p| p.height == height && applied(state, &p.name).is_none())
+            .collect();
+        due.sort_by(|a, b| a.name.cmp(&b.name));
+        due
+    }
+
+    /// 出块 / 导入前检查：本版本能否执行 height 区块。
+    pub fn check_upgrades(&self, state: &State, height: u64) -> Result<(), UpgradeError> {
+        self.due_upgrades(state, height)
+            .iter()
+            .try_for_each(check_supported)
+    }
+
+    /// 启动时检查：状态中已激活的特性门本版本都认识。
+    pub fn check_activated(&self, state: &State) -> Result<(), UpgradeError> {
+        match activated_gates(state)
+            .into_iter()
+            .find(|g| !FeatureGates::KNOWN.contains(&g.as_str()))
+        {
+            Some(gate) => Err(UpgradeError::UnknownActivated(gate)),
+            None => Ok(()),
+        }
+    }
+
+    /// 区块开头：激活到期的升级，返回激活的升级名称；evm 特性门开启后另记录父区块哈希供 BLOCKHASH 查询。
+    pub fn begin_block(
+        &self,
+        state: &mut State,
+        env: &BlockEnv,
+    ) -> Result<Vec<String>, UpgradeError> {
+        let due = self.due_upgrades(state, env.height);
+        due.iter().try_for_each(check_supported)?;
+        let mut gates = activated_gates(state);
+        let mut names = Vec::with_capacity(due.len());
+        for plan in due {
+            for gate in plan.gates {
+                if !gates.contains(&gate) {
+                    gates.push(gate);
+                }
+            }
+            state.module_delete(&key(KEY_PLAN, &plan.name));
+            state.module_set(key(KEY_DONE, &plan.name), env.height.to_be_bytes().to_vec());
+            names.push(plan.name);
+        }
+        if !names.is_empty() {
+            state.module_set(
+                KEY_GATES.to_vec(),
+                serde_json::to_vec(&gates).expect("gate list serializes"),
+            );
+        }
+        if self.feature_gates(state).evm {
+            crate::evm::record_block_hash(state, env);
+        }
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::fill_block;
+    use crate::executor::tests::{env, funded, params};
+
+    fn plan(name: &str, height: u64, gates: &[&str]) -> UpgradePlan {
+        UpgradePlan {
+            name: name.into(),
+            height,
+            gates: gates.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn activates_scheduled_gates_and_halts_on_unknown_ones() {
+        let mut exec = Executor::new(&params());
+        exec.feature_gates.wasm_vm = true;
+        exec.upgrades = vec![plan("evm-1", 2, &["evm"])];
+        let mut state = funded(&[1]);
+        let mut env = env();
+
+        let (built, _) = fill_block(&exec, &mut state, env.clone(), Vec::new()).unwrap();
+        assert!(built.upgrades.is_empty());
+        assert!(!exec.feature_gates(&state).evm);
+
+        schedule(
+            &mut state,
+            plan("bridge", 3, &["ibc_bridge", "sharding"]),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            schedule(&mut state, plan("bridge", 4, &[]), 1),
+            Err(UpgradeError::Duplicate("bridge".into()))
+        );
+        assert!(matches!(
+            schedule(&mut state, plan("late", 1, &[]), 1),
+            Err(UpgradeError::PastHeight { .. })
+        ));
+
+        env.height = 2;
+        let (built, _) = fill_block(&exec, &mut state, env.clone(), Vec::new()).unwrap();
+        assert_eq!(built.upgrades, vec!["evm-1".to_string()]);
+        let gates = exec.feature_gates(&state);
+        assert!(gates.evm && gates.wasm_vm && !gates.ibc_bridge);
+        assert_eq!(applied(&state, "evm-1"), Some(2));
+        assert!(exec.due_upgrades(&state, 2).is_empty());
+
+        env.height = 3;
+        let err = exec.check_upgrades(&state, 3).unwrap_err();
+        assert!(matches!(&err, UpgradeError::Unsupported { gate, .. } if gate == "sharding"));
+        let before = state.state_root();
+        assert_eq!(
+            fill_block(&exec, &mut state, env, Vec::new()).unwrap_err(),
+            err
+        );
+        assert_eq!(state.state_root(), before);
+        exec.check_activated(&state).unwrap();
+    }
+}
@@ -0,0 +1,368 @@
+//! 极简栈式合约 VM（u128 字长），每条指令按 GasSchedule 计费。
+//!
+//! 字节码约定：
+//! - PUSH n b1..bn：压入 n(1..=16) 字节大端整数
+//! - DUP n / SWAP n：复制第 n 项 / 栈顶与第 n+1 项交换（n 从 1 开始）
+//! - JUMP / JUMPI 只能跳到 JUMPDEST
+//! - LOG n：弹出一个数据字与 n(0..=4) 个主题，发出事件日志（数据为 16 字节大端，主题零扩展到 32 字节）
+//! - RETURN 弹出一个字作为 16 字节返回值；REVERT 弹出错误码并回滚
+use crate::gas::{GasMeter, GasSchedule, OutOfGas};
+use crate::state::State;
+use ark_types::{Address, Amount, Gas, Log, H256};
+use std::collections::HashSet;
+
+pub mod op {
+    pub const STOP: u8 = 0x00;
+    pub const ADD: u8 = 0x01;
+    pub const SUB: u8 = 0x02;
+    pub const MUL: u8 = 0x03;
+    pub const DIV: u8 = 0x04;
+    pub const MOD: u8 = 0x05;
+    pub const LT: u8 = 0x10;
+    pub const GT: u8 = 0x11;
+    pub const EQ: u8 = 0x12;
+    pub const ISZERO: u8 = 0x13;
+    pub const AND: u8 = 0x16;
+    pub const OR: u8 = 0x17;
+    pub const CALLER: u8 = 0x33;
+    pub const CALLVALUE: u8 = 0x34;
+    pub const CALLDATALOAD: u8 = 0x35;
+    pub const CALLDATASIZE: u8 = 0x36;
+    pub const POP: u8 = 0x50;
+    pub const SLOAD: u8 = 0x54;
+    pub const SSTORE: u8 = 0x55;
+    pub const JUMP: u8 = 0x56;
+    pub const JUMPI: u8 = 0x57;
+    pub const JUMPDEST: u8 = 0x5b;
+    pub const PUSH: u8 = 0x60;
+    pub const DUP: u8 = 0x80;
+    pub const SWAP: u8 = 0x90;
+    pub const LOG: u8 = 0xa0;
+    pub const RETURN: u8 = 0xf3;
+    pub const REVERT: u8 = 0xfd;
+}
+
+pub const STACK_LIMIT: usize = 1024;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    #[error("out of gas")]
+    OutOfGas,
+    #[error("reverted: {0}")]
+    Revert(String),
+    #[error("stack underflow at pc {0}")]
+    StackUnderflow(usize),
+    #[error("stack overflow at pc {0}")]
+    StackOverflow(usize),
+    #[error("invalid jump destination {0}")]
+    BadJump(u128),
+    #[error("invalid opcode {op:#04x} at pc {pc}")]
+    InvalidOpcode { op: u8, pc: usize },
+}
+
+impl From<OutOfGas> for VmError {
+    fn from(_: OutOfGas) -> Self {
+        VmError::OutOfGas
+    }
+}
+
+/// 合约调用上下文。
+pub struct CallContext<'a> {
+    pub address: Address,
+    pub caller: Address,
+    pub value: Amount,
+    pub input: &'a [u8],
+}
+
+/// 调用者地址映射为 u128：取地址前 16 字节。
+pub fn address_word(addr: &Address) -> u128 {
+    let mut b = [0u8; 16];
+    b.copy_from_slice(&addr.0[..16]);
+    u128::from_be_bytes(b)
+}
+
+pub struct Vm<'s> {
+    schedule: &'s GasSchedule,
+}
+
+impl<'s> Vm<'s> {
+    pub fn new(schedule: &'s GasSchedule) -> Self {
+        Self { schedule }
+    }
+
+    /// 执行字节码，返回 RETURN 的数据（STOP 或执行到末尾时为空）。
+    pub fn run(
+        &self,
+        code: &[u8],
+        ctx: &CallContext<'_>,
+        state: &mut State,
+        meter: &mut GasMeter,
+    ) -> Result<Vec<u8>, VmError> {
+        let jumpdests = jump_destinations(code);
+        let mut stack: Vec<u128> = Vec::new();
+        let mut pc = 0usize;
+        let g = self.schedule;
+
+        macro_rules! pop {
+            () => {
+                stack.pop().ok_or(VmError::StackUnderflow(pc))?
+            };
+        }
+        macro_rules! push {
+            ($v:expr) => {{
+                if stack.len() >= STACK_LIMIT {
+                    return Err(VmError::StackOverflow(pc));
+                }
+                stack.push($v);
+            }};
+        }
+
+        while pc < code.len() {
+            let opcode = code[pc];
+            let mut next = pc + 1;
+            match opcode {
+                op::STOP => return Ok(Vec::new()),
+                op::ADD | op::SUB | op::LT | op::GT | op::EQ | op::AND | op::OR => {
+                    meter.charge(g.vm_arith)?;
+                    let a = pop!();
+                    let b = pop!();
+                    push!(match opcode {
+                        op::ADD => a.wrapping_add(b),
+                        op::SUB => a.wrapping_sub(b),
+                        op::LT => (a < b) as u128,
+                        op::GT => (a > b) as u128,
+                        op::EQ => (a == b) as u128,
+                        op::AND => a & b,
+                        _ => a | b,
+                    });
+                }
+                op::MUL | op::DIV | op::MOD => {
+                    meter.charge(g.vm_mul)?;
+                    let a = pop!();
+                    let b = pop!();
+                    push!(match opcode {
+                        op::MUL => a.wrapping_mul(b),
+                        op::DIV => a.checked_div(b).unwrap_or(0),
+                        _ => a.checked_rem(b).unwrap_or(0),
+                    });
+                }
+                op::ISZERO => {
+                    meter.charge(g.vm_arith)?;
+                    let a = pop!();
+                    push!((a == 0) as u128);
+                }
+                op::CALLER => {
+                    meter.charge(g.vm_step)?;
+                    push!(address_word(&ctx.caller));
+                }
+                op::CALLVALUE => {
+                    meter.charge(g.vm_step)?;
+                    push!(ctx.value);
+                }
+                op::CALLDATASIZE => {
+                    meter.charge(g.vm_step)?;
+                    push!(ctx.input.len() as u128);
+                }
+                op::CALLDATALOAD => {
+                    meter.charge(g.vm_arith)?;
+                    let off = pop!();
+                    let mut b = [0u8; 16];
+                    let start = usize::try_from(off).unwrap_or(usize::MAX);
+                    for (i, slot) in b.iter_mut().enumerate() {
+                        if let Some(v) = start.checked_add(i).and_then(|j| ctx.input.get(j)) {
+                            *slot = *v;
+                        }
+                    }
+                    push!(u128::from_be_bytes(b));
+                }
+                op::POP => {
+                    meter.charge(g.vm_step)?;
+                    pop!();
+                }
+                op::SLOAD => {
+                    meter.charge(g.sload)?;
+                    let key = pop!();
+                    let v = state.storage(&ctx.address, &H256::from_u128(key));
+                    push!(v.low_u128());
+                }
+                op::SSTORE => {
+                    let key = H256::from_u128(pop!());
+                    let value = H256::from_u128(pop!());
+                    let current = state.storage(&ctx.address, &key);
+                    if current.is_zero() && !value.is_zero() {
+                        meter.charge(g.sstore_set)?;
+                    } else {
+                        meter.charge(g.sstore_reset)?;
+                        if !current.is_zero() && value.is_zero() {
+                            meter.add_refund(g.sstore_clear_refund);
+                        }
+                    }
+                    state.set_storage(&ctx.address, key, value);
+                }
+                op::JUMP => {
+                    meter.charge(g.vm_jump)?;
+                    next = checked_dest(pop!(), &jumpdests)?;
+                }
+                op::JUMPI => {
+                    meter.charge(g.vm_jump)?;
+                    let dest = pop!();
+                    let cond = pop!();
+                    if cond != 0 {
+                        next = checked_dest(dest, &jumpdests)?;
+                    }
+                }
+                op::JUMPDEST => {
+                    meter.charge(1)?;
+                }
+                op::PUSH => {
+                    meter.charge(g.vm_step)?;
+                    let n = *code
+                        .get(pc + 1)
+                        .ok_or(VmError::InvalidOpcode { op: opcode, pc })?
+                        as usize;
+                    let bytes = code
+                        .get(pc + 2..pc + 2 + n)
+                        .filter(|_| (1..=16).contains(&n))
+                        .ok_or(VmError::InvalidOpcode { op: opcode, pc })?;
+                    let mut b = [0u8; 16];
+                    b[16 - n..].copy_from_slice(bytes);
+                    push!(u128::from_be_bytes(b));
+                    next = pc + 2 + n;
+                }
+                op::DUP | op::SWAP => {
+                    meter.charge(g.vm_step)?;
+                    let n = *code
+                        .get(pc + 1)
+                        .ok_or(VmError::InvalidOpcode { op: opcode, pc })?
+                        as usize;
+                    if n == 0 {
+                        return Err(VmError::InvalidOpcode { op: opcode, pc });
+                    }
+                    let len = stack.len();
+                    if opcode == op::DUP {
+                        if n > len {
+                            return Err(VmError::StackUnderflow(pc));
+                        }
+                        push!(stack[len - n]);
+                    } else {
+                        if n >= len {
+                            return Err(VmError::StackUnderflow(pc));
+                        }
+                        stack.swap(len - 1, len - 1 - n);
+                    }
+                    next = pc + 2;
+                }
+                op::LOG => {
+                    let n = *code
+                        .get(pc + 1)
+                        .filter(|n| **n <= 4)
+                        .ok_or(VmError::InvalidOpcode { op: opcode, pc })?
+                        as usize;
+                    meter.charge(g.log + g.log_topic * n as Gas + g.log_byte * 16)?;
+                    let data = pop!().to_be_bytes().to_vec();
+                    let mut topics = Vec::with_capacity(n);
+                    for _ in 0..n {
+                        topics.push(H256::from_u128(pop!()));
+                    }
+                    state.emit_log(Log {
+                        address: ctx.address,
+                        topics,
+                        data,
+                    });
+                    next = pc + 2;
+                }
+                op::RETURN => {
+                    meter.charge(g.vm_step)?;
+                    return Ok(pop!().to_be_bytes().to_vec());
+                }
+                op::REVERT => {
+                    meter.charge(g.vm_step)?;
+                    let code = pop!();
+                    return Err(VmError::Revert(format!("revert code {code}")));
+                }
+                other => return Err(VmError::InvalidOpcode { op: other, pc }),
+            }
+            pc = next;
+        }
+        Ok(Vec::new())
+    }
+}
+
+fn checked_dest(dest: u128, jumpdests: &HashSet<usize>) -> Result<usize, VmError> {
+    usize::try_from(dest)
+        .ok()
+        .filter(|d| jumpdests.contains(d))
+        .ok_or(VmError::BadJump(dest))
+}
+
+/// 扫描合法跳转目标（跳过 PUSH/DUP/SWAP/LOG 的立即数）。
+fn jump_destinations(code: &[u8]) -> HashSet<usize> {
+    let mut out = HashSet::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        match code[pc] {
+            op::JUMPDEST => {
+                out.insert(pc);
+                pc += 1;
+            }
+            op::PUSH => pc += 2 + code.get(pc + 1).copied().unwrap_or(0) as usize,
+            op::DUP | op::SWAP | op::LOG => pc += 2,
+            _ => pc += 1,
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(code: &[u8], limit: u64) -> (Result<Vec<u8>, VmError>, GasMeter, State) {
+        let schedule = GasSchedule::default();
+        let mut state = State::new();
+        let mut meter = GasMeter::new(limit);
+        let ctx = CallContext {
+            address: Address([1u8; 20]),
+            caller: Address([2u8; 20]),
+            value: 0,
+            input: &[],
+        };
+        let r = Vm::new(&schedule).run(code, &ctx, &mut state, &mut meter);
+        (r, meter, state)
+    }
+
+    #[test]
+    fn arithmetic_and_return() {
+        // 2 + 3 -> RETURN
+        let code = [op::PUSH, 1, 2, op::PUSH, 1, 3, op::ADD, op::RETURN];
+        let (r, meter, _) = run(&code, 1_000);
+        assert_eq!(u128::from_be_bytes(r.unwrap().try_into().unwrap()), 5);
+        assert_eq!(meter.used(), 2 + 2 + 3 + 2);
+    }
+
+    #[test]
+    fn sstore_charges_and_persists() {
+        // store 7 at key 1
+        let code = [op::PUSH, 1, 7, op::PUSH, 1, 1, op::SSTORE, op::STOP];
+        let (r, meter, state) = run(&code, 100_000);
+        r.unwrap();
+        assert_eq!(meter.used(), 4 + 20_000);
+        let v = state.storage(&Address([1u8; 20]), &H256::from_u128(1));
+        assert_eq!(v.low_u128(), 7);
+    }
+
+    #[test]
+    fn infinite_loop_runs_out_of_gas() {
+        let code = [op::JUMPDEST, op::PUSH, 1, 0, op::JUMP];
+        let (r, meter, _) = run(&code, 10_000);
+        assert_eq!(r, Err(VmError::OutOfGas));
+        assert_eq!(meter.used(), 10_000);
+    }
+
+    #[test]
+    fn jump_into_push_data_is_rejected() {
+        let code = [op::PUSH, 1, op::JUMPDEST, op::PUSH, 1, 2, op::JUMP];
+        let (r, _, _) = run(&code, 1_000);
+        assert_eq!(r, Err(VmError::BadJump(2)));
+    }
+}
@@ -0,0 +1,171 @@
+//! 全局指标注册表与 `/metrics` 端点（Prometheus 文本格式）。
+//!
+//! - 各 crate 在自己的 `metrics` 模块中以惰性静态量注册计数器 / 仪表 / 直方图，名称统一加 `ark_` 前缀
+//! - 各 crate 提供 `metrics::register()` 强制初始化，保证尚未更新过的指标也出现在输出中
+//! - `on_collect` 注册采集前回调，用于按需计算的值（如运行时长）
+//! - `serve` 只响应 `GET /metrics`，其他路径 404
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use prometheus::{Encoder, Opts, Registry, TextEncoder};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{LazyLock, Mutex};
+
+pub use prometheus::{
+    exponential_buckets, Gauge, Histogram, HistogramOpts, HistogramTimer, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge, IntGaugeVec,
+};
+
+/// 指标名前缀
+pub const NAMESPACE: &str = "ark";
+
+type Hook = Box<dyn Fn() + Send + Sync>;
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+static HOOKS: LazyLock<Mutex<Vec<Hook>>> = LazyLock::new(Default::default);
+
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+/// 注册指标；同名重复注册说明指标定义有误，直接 panic。
+fn register<C: prometheus::core::Collector + Clone + 'static>(metric: C) -> C {
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("metric registered twice");
+    metric
+}
+
+fn opts(name: &str, help: &str) -> Opts {
+    Opts::new(name, help).namespace(NAMESPACE)
+}
+
+pub fn int_counter(name: &str, help: &str) -> IntCounter {
+    register(IntCounter::with_opts(opts(name, help)).expect("valid metric"))
+}
+
+pub fn int_counter_vec(name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    register(IntCounterVec::new(opts(name, help), labels).expect("valid metric"))
+}
+
+pub fn int_gauge(name: &str, help: &str) -> IntGauge {
+    register(IntGauge::with_opts(opts(name, help)).expect("valid metric"))
+}
+
+pub fn int_gauge_vec(name: &str, help: &str, labels: &[&str]) -> IntGaugeVec {
+    register(IntGaugeVec::new(opts(name, help), labels).expect("valid metric"))
+}
+
+pub fn gauge(name: &str, help: &str) -> Gauge {
+    register(Gauge::with_opts(opts(name, help)).expect("valid metric"))
+}
+
+/// 直方图；buckets 单位与指标一致（时间统一用秒）。
+pub fn histogram(name: &str, help: &str, buckets: Vec<f64>) -> Histogram {
+    let opts = HistogramOpts::new(name, help)
+        .namespace(NAMESPACE)
+        .buckets(buckets);
+    register(Histogram::with_opts(opts).expect("valid metric"))
+}
+
+/// 每次采集前调用 f。
+pub fn on_collect(f: impl Fn() + Send + Sync + 'static) {
+    HOOKS.lock().unwrap().push(Box::new(f));
+}
+
+/// 以文本格式输出全部指标。
+pub fn gather_text() -> String {
+    for hook in HOOKS.lock().unwrap().iter() {
+        hook();
+    }
+    let mut buf = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buf)
+        .expect("text encoding never fails");
+    String::from_utf8(buf).expect("text format is utf-8")
+}
+
+fn handle(req: Request<Body>) -> Response<Body> {
+    let (status, body) = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics") => {
+            let mut resp = Response::new(Body::from(gather_text()));
+            resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+            );
+            return resp;
+        }
+        (_, "/metrics") => (StatusCode::METHOD_NOT_ALLOWED, "method not allowed"),
+        _ => (StatusCode::NOT_FOUND, "not found"),
+    };
+    let mut resp = Response::new(Body::from(body));
+    *resp.status_mut() = status;
+    resp
+}
+
+/// 启动 `/metrics` 服务，直到 shutdown 完成。
+pub async fn serve(
+    addr: SocketAddr,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let (addr, server) = bind(addr, shutdown)?;
+    tracing::info!(%addr, "metrics server listening");
+    server.await
+}
+
+/// 绑定端口，返回实际监听地址与服务 future（端口为 0 时由系统分配）。
+pub fn bind(
+    addr: SocketAddr,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<(SocketAddr, impl Future<Output = anyhow::Result<()>>)> {
+    let make = make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|req| async { Ok::<_, Infallible>(handle(req)) }))
+    });
+    let server = hyper::Server::try_bind(&addr)?.serve(make);
+    let addr = server.local_addr();
+    let server = server.with_graceful_shutdown(shutdown);
+    Ok((addr, async move { server.await.map_err(Into::into) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_registered_metrics_at_metrics_path() {
+        let height = int_gauge("test_height", "Test gauge");
+        height.set(7);
+        let hits = int_counter("test_collects_total", "Collect hook calls");
+        let hook = hits.clone();
+        on_collect(move || hook.inc());
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let (addr, server) = bind("127.0.0.1:0".parse().unwrap(), async {
+            let _ = rx.await;
+        })
+        .unwrap();
+        let task = tokio::spawn(server);
+        let client = hyper::Client::new();
+
+        let resp = client
+            .get(format!("http://{addr}/metrics").parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE ark_test_height gauge"), "{text}");
+        assert!(text.contains("ark_test_height 7"), "{text}");
+        assert!(text.contains("ark_test_collects_total 1"), "{text}");
+
+        let resp = client
+            .get(format!("http://{addr}/").parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        tx.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+//! admin RPC 后端：节点表、同步进度、运行时日志过滤、快照与交易池导出。
+use crate::node::Node;
+use ark_p2p::{PeerInfo, PeerSource};
+use ark_rpc::{AdminBackend, PoolEntry, SnapshotInfo, SyncStatus};
+use ark_storage::Snapshot;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+pub type LogHandle = reload::Handle<EnvFilter, Registry>;
+
+pub struct NodeAdmin {
+    pub node: Arc<Node>,
+    pub log: LogHandle,
+    pub snapshot_dir: PathBuf,
+}
+
+impl AdminBackend for NodeAdmin {
+    fn peers(&self) -> Vec<PeerInfo> {
+        self.node.peers.lock().unwrap().list()
+    }
+
+    fn add_peer(&self, addr: &str) -> Result<bool, String> {
+        let _span = tracing::info_span!("peer", peer = %addr).entered();
+        let mut peers = self.node.peers.lock().unwrap();
+        let added = peers
+            .add(addr, PeerSource::Admin)
+            .map_err(|e| e.to_string())?;
+        if added {
+            tracing::info!(peers = peers.len(), "peer added");
+        }
+        Ok(added)
+    }
+
+    fn remove_peer(&self, addr: &str) -> bool {
+        let _span = tracing::info_span!("peer", peer = %addr).entered();
+        let removed = self.node.peers.lock().unwrap().remove(addr);
+        if removed {
+            tracing::info!("peer removed");
+        }
+        removed
+    }
+
+    fn sync_status(&self) -> SyncStatus {
+        self.node.sync_status()
+    }
+
+    fn log_filter(&self) -> String {
+        self.log.with_current(|f| f.to_string()).unwrap_or_default()
+    }
+
+    fn set_log_filter(&self, directives: &str) -> Result<(), String> {
+        let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+        self.log.reload(filter).map_err(|e| e.to_string())
+    }
+
+    fn snapshot(&self) -> anyhow::Result<SnapshotInfo> {
+        // 只在截取时持有读锁，写文件不阻塞出块
+        let snap = Snapshot::capture(&self.node.store.read().unwrap())?;
+        let path = snap.write_to_dir(&self.snapshot_dir)?;
+        Ok(SnapshotInfo {
+            height: snap.height(),
+            block_hash: snap.meta.header.hash(),
+            state_root: snap.meta.header.state_root,
+            entries: snap.meta.entries,
+            path: path.display().to_string(),
+        })
+    }
+
+    fn mempool(&self) -> Vec<PoolEntry> {
+        let pool = self.node.mempool.lock().unwrap();
+        pool.iter()
+            .map(|p| PoolEntry {
+                hash: p.hash,
+                sender: p.sender,
+                nonce: p.stx.tx.nonce,
+                size: p.size,
+                received_ms: p.received_ms,
+                transaction: p.stx.clone(),
+            })
+            .collect()
+    }
+}
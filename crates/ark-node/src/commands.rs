@@ -0,0 +1,322 @@
+//! 运维子命令：初始化节点目录、区块导出 / 导入、快照导出 / 导入与重置数据。
+//!
+//! 除 init 外均直接读写 db.path，须在节点停止时执行。
+use crate::config::NodeConfig;
+use crate::validator;
+use anyhow::Context;
+use ark_consensus::SignGuard;
+use ark_crypto::{SecretKey, Signer};
+use ark_exec::{replay_block, Executor, State};
+use ark_storage::{BlockReader, ChainStore, Snapshot};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const NODE_TOML: &str = include_str!("../../../config/node.toml");
+pub(crate) const GENESIS_JSON: &str = include_str!("../../../config/genesis.json");
+/// 导入时每隔多少个区块输出一次进度
+const PROGRESS_EVERY: u64 = 1_000;
+
+/// 在 home 下写入 config/node.toml、config/genesis.json 与 P2P 节点密钥。
+///
+/// 生成的配置中数据与配置路径均改写为 home 下的绝对路径。`force` 只覆盖配置文件，
+/// 已存在的节点密钥始终保留。
+pub fn init(home: &Path, chain_id: Option<&str>, force: bool) -> anyhow::Result<()> {
+    fs::create_dir_all(home.join("config"))?;
+    fs::create_dir_all(home.join("data"))?;
+    let home = home.canonicalize()?;
+    let config_path = home.join("config/node.toml");
+    let genesis_path = home.join("config/genesis.json");
+    if !force {
+        for path in [&config_path, &genesis_path] {
+            anyhow::ensure!(
+                !path.exists(),
+                "{} already exists; pass --force to overwrite",
+                path.display()
+            );
+        }
+    }
+
+    let prefix = home.display().to_string();
+    let config = NODE_TOML
+        .replace("\"data/", &format!("\"{prefix}/data/"))
+        .replace("\"config/", &format!("\"{prefix}/config/"));
+    fs::write(&config_path, config)?;
+
+    let mut genesis = ark_types::Genesis::from_json(GENESIS_JSON).expect("bundled genesis parses");
+    if let Some(id) = chain_id {
+        genesis.chain_id = id.to_string();
+    }
+    genesis.genesis_time = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    fs::write(
+        &genesis_path,
+        serde_json::to_string_pretty(&genesis)? + "\n",
+    )?;
+
+    let key_path = home.join("config/node_key");
+    let key = match read_node_key(&key_path)? {
+        Some(key) => key,
+        None => {
+            let key = SecretKey::generate();
+            write_private(&key_path, &(hex::encode(key.to_bytes()) + "\n"))?;
+            key
+        }
+    };
+
+    println!("initialized {}", home.display());
+    println!("  config:   {}", config_path.display());
+    println!(
+        "  genesis:  {} (chain_id {})",
+        genesis_path.display(),
+        genesis.chain_id
+    );
+    println!("  node key: {}", key_path.display());
+    println!("  node id:  {}", hex::encode(key.public_key()));
+    println!("start with: ark-node --config {}", config_path.display());
+    Ok(())
+}
+
+/// 读取 P2P 节点密钥（hex）；文件不存在时返回 None。
+pub fn read_node_key(path: &Path) -> anyhow::Result<Option<SecretKey>> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("read node key {}", path.display())),
+    };
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(raw.trim(), &mut bytes)
+        .with_context(|| format!("node key {} is not 32-byte hex", path.display()))?;
+    Ok(Some(SecretKey::from_bytes(&bytes)?))
+}
+
+fn write_private(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("create {}", path.display()))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// 导出 [from, to] 区块（to 缺省为当前 head）。
+pub fn export_blocks(
+    cfg: &NodeConfig,
+    out: &Path,
+    from: u64,
+    to: Option<u64>,
+) -> anyhow::Result<()> {
+    let store = open_existing(cfg)?;
+    let head = store.head().unwrap_or(0);
+    let to = to.unwrap_or(head);
+    anyhow::ensure!(to <= head, "--to {to} is above head {head}");
+    let count = ark_storage::export_blocks(&store, from, to, out)?;
+    println!(
+        "exported {count} blocks ({from}..={to}) to {}",
+        out.display()
+    );
+    Ok(())
+}
+
+/// 逐块重放导入；已有高度核对哈希后跳过，中断后可重复执行。
+pub fn import_blocks(cfg: &NodeConfig, file: &Path) -> anyhow::Result<()> {
+    let (mut store, genesis) = crate::init_chain(cfg)?;
+    let reader =
+        BlockReader::open(file).with_context(|| format!("open block file {}", file.display()))?;
+    anyhow::ensure!(
+        Some(reader.genesis_hash()) == store.genesis_hash(),
+        "block file belongs to genesis {}, database has {}",
+        reader.genesis_hash(),
+        store.genesis_hash().unwrap_or_default()
+    );
+    let exec = Executor::for_genesis(&genesis);
+    let mut state = State::from_entries(store.state_entries()).map_err(anyhow::Error::msg)?;
+    let mut head = store.head().unwrap_or(0);
+    let mut parent = store
+        .header(head)?
+        .with_context(|| format!("missing header for head {head}"))?;
+    let (mut imported, mut skipped) = (0u64, 0u64);
+    for block in reader {
+        let block = block?;
+        let height = block.height();
+        if height <= head {
+            if let Some(known) = store.header(height)? {
+                anyhow::ensure!(
+                    known.hash() == block.hash(),
+                    "block {height} conflicts with the local chain"
+                );
+            }
+            skipped += 1;
+            continue;
+        }
+        // 出错时直接返回：此前的区块已提交，state 副本随之丢弃
+        let built = replay_block(&exec, &mut state, &genesis.chain_id, &parent, &block)?;
+        store.commit_block(&block, &built.receipts, state.entries())?;
+        head = height;
+        parent = block.header;
+        imported += 1;
+        if imported % PROGRESS_EVERY == 0 {
+            tracing::info!(height, imported, "importing blocks");
+        }
+    }
+    store.flush()?;
+    println!("imported {imported} blocks, skipped {skipped} known; head {head}");
+    Ok(())
+}
+
+/// 截取当前 head 的快照，写入 out（缺省为 db.snapshot_dir）。
+pub fn snapshot_export(cfg: &NodeConfig, out: Option<&Path>) -> anyhow::Result<()> {
+    let store = open_existing(cfg)?;
+    let snap = Snapshot::capture(&store)?;
+    let dir = out
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(&cfg.db.snapshot_dir));
+    let path = snap.write_to_dir(&dir)?;
+    println!(
+        "snapshot at height {} ({} entries) written to {}",
+        snap.height(),
+        snap.meta.entries,
+        path.display()
+    );
+    Ok(())
+}
+
+/// 用快照初始化空数据库；之后的区块可用 import-blocks 补齐。
+pub fn snapshot_import(cfg: &NodeConfig, file: &Path) -> anyhow::Result<()> {
+    let snap = Snapshot::read(file)?;
+    let (_, _, genesis_block) = crate::load_genesis(cfg)?;
+    anyhow::ensure!(
+        snap.meta.genesis_hash == genesis_block.hash(),
+        "snapshot belongs to genesis {}, {} produces {}",
+        snap.meta.genesis_hash,
+        cfg.genesis.file,
+        genesis_block.hash()
+    );
+    let mut store = ChainStore::open(&cfg.db.path)?;
+    anyhow::ensure!(
+        store.head().is_none(),
+        "database {} is not empty; run unsafe-reset first",
+        cfg.db.path
+    );
+    let height = snap.height();
+    store.init_from_snapshot(snap.meta.genesis_hash, &snap.meta.header, snap.entries)?;
+    println!("database initialized from snapshot at height {height}");
+    Ok(())
+}
+
+/// 删除链数据库；密钥、双签保护状态与快照目录保持不动。
+pub fn unsafe_reset(cfg: &NodeConfig, yes: bool) -> anyhow::Result<()> {
+    let db = Path::new(&cfg.db.path);
+    anyhow::ensure!(
+        yes,
+        "unsafe-reset deletes {} and all chain data; pass --yes to confirm",
+        db.display()
+    );
+    if db.exists() {
+        fs::remove_dir_all(db).with_context(|| format!("remove {}", db.display()))?;
+        println!("removed {}", db.display());
+    } else {
+        println!("{} does not exist; nothing to remove", db.display());
+    }
+    if let Some(v) = &cfg.validator {
+        println!("kept sign state {}", v.sign_state);
+    }
+    println!(
+        "kept node key {} and snapshots in {}",
+        cfg.p2p.node_key, cfg.db.snapshot_dir
+    );
+    Ok(())
+}
+
+fn open_existing(cfg: &NodeConfig) -> anyhow::Result<ChainStore> {
+    let store = ChainStore::open(&cfg.db.path)?;
+    anyhow::ensure!(
+        store.genesis_hash().is_some(),
+        "database {} is not initialized",
+        cfg.db.path
+    );
+    Ok(store)
+}
+
+/// 远程签名器进程：绑定 socket 后持续服务，直到进程被终止。
+pub fn remote_signer(
+    keystore: &str,
+    socket: &str,
+    sign_state: &str,
+    password_file: Option<&str>,
+) -> anyhow::Result<()> {
+    let password = validator::read_password(password_file)?;
+    let key = validator::load_keystore(keystore, &password)?;
+    let guard =
+        SignGuard::open(sign_state).with_context(|| format!("open sign state {sign_state}"))?;
+    serve_signer(key, guard, socket)
+}
+
+#[cfg(unix)]
+fn serve_signer(key: SecretKey, guard: SignGuard, socket: &str) -> anyhow::Result<()> {
+    let pubkey = hex::encode(key.public_key());
+    let listener =
+        ark_consensus::signer::bind(socket).with_context(|| format!("bind signer {socket}"))?;
+    tracing::info!(%socket, %pubkey, "remote signer listening");
+    ark_consensus::signer::serve(listener, ark_consensus::LocalSigner::new(key, guard))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_signer(_key: SecretKey, _guard: SignGuard, socket: &str) -> anyhow::Result<()> {
+    anyhow::bail!("remote signer {socket}: Unix domain sockets are unsupported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_then_export_reset_and_restore() {
+        let home = std::env::temp_dir().join(format!("ark-home-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        init(&home, Some("ark-test-1"), false).unwrap();
+        assert!(init(&home, None, false).is_err());
+        let key = read_node_key(&home.join("config/node_key"))
+            .unwrap()
+            .unwrap();
+        init(&home, Some("ark-test-1"), true).unwrap();
+        let kept = read_node_key(&home.join("config/node_key"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.to_bytes(), key.to_bytes());
+
+        let config = home.join("config/node.toml");
+        let cfg = NodeConfig::load(config.to_str(), Vec::new(), &[]).unwrap();
+        assert!(cfg
+            .db
+            .path
+            .starts_with(&*home.canonicalize().unwrap().to_string_lossy()));
+
+        let (store, genesis) = crate::init_chain(&cfg).unwrap();
+        assert_eq!(genesis.chain_id, "ark-test-1");
+        drop(store);
+        snapshot_export(&cfg, None).unwrap();
+        let snap = fs::read_dir(&cfg.db.snapshot_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert!(snapshot_import(&cfg, &snap).is_err());
+
+        assert!(unsafe_reset(&cfg, false).is_err());
+        unsafe_reset(&cfg, true).unwrap();
+        assert!(!Path::new(&cfg.db.path).exists());
+        snapshot_import(&cfg, &snap).unwrap();
+        let (store, _) = crate::init_chain(&cfg).unwrap();
+        assert_eq!(store.head(), Some(0));
+        assert!(home.join("config/node_key").exists());
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
@@ -0,0 +1,373 @@
+//! 节点配置：内置默认值 → 配置文件 → `ARK_NODE__SECTION__KEY` 环境变量 → 命令行 `--set`，后者覆盖前者。
+//!
+//! - 合并在 TOML 值树上完成，再统一反序列化为强类型配置（监听地址为 `SocketAddr`）
+//! - 环境变量名去掉前缀后按 `__` 分段、转小写，如 `ARK_NODE__RPC__RATE_LIMIT__PER_IP_BURST`
+//! - 覆盖值按 TOML 字面量解析（`10`、`true`、`["a", "b"]`）；默认值为字符串或未设置的键按原样作字符串
+//! - 加载后立即校验 multiaddr、端口冲突与互斥项，错误在启动任何服务之前暴露
+use crate::health::HealthConfig;
+use crate::logging::LogConfig;
+use crate::validator::ValidatorConfig;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::net::SocketAddr;
+
+/// 环境变量覆盖的前缀
+pub const ENV_PREFIX: &str = "ARK_NODE__";
+/// 未指定 --config 时尝试读取的文件；不存在则只用默认值
+pub const DEFAULT_PATH: &str = "config/node.toml";
+
+const REDACTED: &str = "<redacted>";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeConfig {
+    pub p2p: P2p,
+    pub rpc: Rpc,
+    pub db: Db,
+    pub genesis: Genesis,
+    pub health: HealthConfig,
+    pub lifecycle: Lifecycle,
+    pub log: LogConfig,
+    /// 验证者节点专有；观察者节点不得配置
+    pub validator: Option<ValidatorConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct P2p {
+    pub listen_addr: String,
+    pub bootnodes: Vec<String>,
+    /// 节点身份密钥（hex），由 `ark-node init` 生成
+    pub node_key: String,
+}
+
+impl Default for P2p {
+    fn default() -> Self {
+        Self {
+            listen_addr: "/ip4/0.0.0.0/udp/30333/quic-v1".into(),
+            bootnodes: Vec::new(),
+            node_key: "config/node_key".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rpc {
+    pub http: SocketAddr,
+    pub ws: SocketAddr,
+    pub grpc: SocketAddr,
+    pub metrics: SocketAddr,
+    pub health: SocketAddr,
+    /// [rpc.cors] / [rpc.rate_limit] / [rpc.auth] / [rpc.methods.*]
+    #[serde(flatten)]
+    pub access: ark_rpc::AccessConfig,
+}
+
+impl Default for Rpc {
+    fn default() -> Self {
+        let local = |port| SocketAddr::from(([127, 0, 0, 1], port));
+        Self {
+            http: local(8545),
+            ws: local(8546),
+            grpc: local(50051),
+            metrics: local(19100),
+            health: local(18080),
+            access: ark_rpc::AccessConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Db {
+    pub path: String,
+    /// admin_snapshot 写入的目录
+    pub snapshot_dir: String,
+}
+
+impl Default for Db {
+    fn default() -> Self {
+        Self {
+            path: "data/db".into(),
+            snapshot_dir: "data/snapshots".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Genesis {
+    pub file: String,
+}
+
+impl Default for Genesis {
+    fn default() -> Self {
+        Self {
+            file: "config/genesis.json".into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lifecycle {
+    /// 每个服务停止（排空请求、刷盘）的最长等待时间
+    pub stop_timeout_secs: u64,
+    /// 非关键服务（mempool 维护、health、metrics）意外退出后的最大重启次数
+    pub max_restarts: u32,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self {
+            stop_timeout_secs: 10,
+            max_restarts: 3,
+        }
+    }
+}
+
+impl NodeConfig {
+    /// 按层合并并校验。`path` 为 None 时读取 DEFAULT_PATH（不存在则跳过）。
+    pub fn load(
+        path: Option<&str>,
+        env: impl IntoIterator<Item = (String, String)>,
+        overrides: &[String],
+    ) -> anyhow::Result<Self> {
+        let defaults = toml::Table::try_from(Self::default()).expect("defaults serialize");
+        let mut merged = defaults.clone();
+
+        let file = path.unwrap_or(DEFAULT_PATH);
+        match fs::read_to_string(file) {
+            Ok(raw) => {
+                let table: toml::Table =
+                    toml::from_str(&raw).with_context(|| format!("parse config {file}"))?;
+                merge(&mut merged, table);
+            }
+            Err(e) if path.is_none() && e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("read config {file}")),
+        }
+
+        let mut env: Vec<_> = env
+            .into_iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix(ENV_PREFIX)?.to_string(), v)))
+            .collect();
+        env.sort();
+        for (key, raw) in env {
+            let path: Vec<String> = key.split("__").map(str::to_ascii_lowercase).collect();
+            set(&mut merged, &defaults, &path, &raw)
+                .with_context(|| format!("environment override {ENV_PREFIX}{key}"))?;
+        }
+        for item in overrides {
+            let (key, raw) = item
+                .split_once('=')
+                .with_context(|| format!("--set {item}: expected section.key=value"))?;
+            let path: Vec<String> = key.trim().split('.').map(str::to_string).collect();
+            set(&mut merged, &defaults, &path, raw.trim())
+                .with_context(|| format!("--set {item}"))?;
+        }
+
+        // 经文本往返反序列化，类型错误可带上出错的键与行
+        let text = toml::to_string(&merged).expect("merged config serializes");
+        let cfg: Self = toml::from_str(&text).context("invalid config")?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ark_p2p::validate_addr(&self.p2p.listen_addr).context("p2p.listen_addr")?;
+        for addr in &self.p2p.bootnodes {
+            ark_p2p::validate_addr(addr).context("p2p.bootnodes")?;
+        }
+
+        let listeners = [
+            ("rpc.http", self.rpc.http),
+            ("rpc.ws", self.rpc.ws),
+            ("rpc.grpc", self.rpc.grpc),
+            ("rpc.metrics", self.rpc.metrics),
+            ("rpc.health", self.rpc.health),
+        ];
+        let mut seen = BTreeSet::new();
+        for (name, addr) in listeners {
+            if addr.port() != 0 && !seen.insert(addr.port()) {
+                anyhow::bail!(
+                    "{name}: port {} is already used by another listener",
+                    addr.port()
+                );
+            }
+        }
+
+        anyhow::ensure!(!self.db.path.is_empty(), "db.path must not be empty");
+        anyhow::ensure!(
+            !self.genesis.file.is_empty(),
+            "genesis.file must not be empty"
+        );
+        anyhow::ensure!(
+            self.lifecycle.stop_timeout_secs > 0,
+            "lifecycle.stop_timeout_secs must be positive"
+        );
+        self.log.validate()?;
+        if let Some(v) = &self.validator {
+            v.validate()?;
+        }
+        Ok(())
+    }
+
+    /// 生效配置的 TOML 文本；口令与 API key 以占位符代替。
+    pub fn to_toml_redacted(&self) -> String {
+        let mut cfg = self.clone();
+        let auth = &mut cfg.rpc.access.auth;
+        if auth.admin_token.is_some() {
+            auth.admin_token = Some(REDACTED.into());
+        }
+        for key in &mut auth.api_keys {
+            *key = REDACTED.into();
+        }
+        toml::to_string(&cfg).expect("config serializes")
+    }
+}
+
+/// 将 `over` 递归合并进 `base`：表逐键合并，其余值整体替换。
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn set(
+    root: &mut toml::Table,
+    defaults: &toml::Table,
+    path: &[String],
+    raw: &str,
+) -> anyhow::Result<()> {
+    let (last, parents) = path.split_last().context("empty key")?;
+    anyhow::ensure!(
+        path.iter().all(|p| !p.is_empty()),
+        "empty segment in key {}",
+        path.join(".")
+    );
+    let value = match lookup(defaults, path) {
+        None | Some(toml::Value::String(_)) => toml::Value::String(raw.to_string()),
+        Some(_) => parse_literal(raw),
+    };
+    let mut table = root;
+    for part in parents {
+        let entry = table
+            .entry(part.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        table = entry
+            .as_table_mut()
+            .with_context(|| format!("{part} is not a section"))?;
+    }
+    table.insert(last.clone(), value);
+    Ok(())
+}
+
+fn lookup<'a>(table: &'a toml::Table, path: &[String]) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_first()?;
+    let value = table.get(first)?;
+    match (rest.is_empty(), value) {
+        (true, v) => Some(v),
+        (false, toml::Value::Table(t)) => lookup(t, rest),
+        (false, _) => None,
+    }
+}
+
+/// 按 TOML 字面量解析；不合法时当作字符串（由后续反序列化报类型错误）。
+fn parse_literal(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("v = {raw}"))
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn layers_override_in_order() {
+        let dir = std::env::temp_dir().join(format!("ark-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("node.toml");
+        fs::write(
+            &file,
+            "[rpc]\nhttp = \"127.0.0.1:9000\"\nws = \"127.0.0.1:9001\"\n[db]\npath = \"file/db\"\n",
+        )
+        .unwrap();
+
+        let cfg = NodeConfig::load(
+            Some(file.to_str().unwrap()),
+            env(&[
+                ("ARK_NODE__RPC__WS", "127.0.0.1:9002"),
+                ("ARK_NODE__RPC__RATE_LIMIT__PER_IP_BURST", "7"),
+                ("ARK_NODE__RPC__AUTH__ADMIN_TOKEN", "12345"),
+                (
+                    "ARK_NODE__P2P__BOOTNODES",
+                    r#"["/ip4/10.0.0.1/udp/30333/quic-v1"]"#,
+                ),
+                ("OTHER__RPC__HTTP", "ignored"),
+            ]),
+            &["db.path=cli/db".into(), "lifecycle.max_restarts=9".into()],
+        )
+        .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(cfg.rpc.http.port(), 9000);
+        assert_eq!(cfg.rpc.ws.port(), 9002);
+        assert_eq!(cfg.rpc.grpc.port(), 50051);
+        assert_eq!(cfg.rpc.access.rate_limit.per_ip_burst, 7);
+        assert_eq!(cfg.rpc.access.auth.admin_token.as_deref(), Some("12345"));
+        assert_eq!(cfg.p2p.bootnodes.len(), 1);
+        assert_eq!(cfg.db.path, "cli/db");
+        assert_eq!(cfg.lifecycle.max_restarts, 9);
+        assert!(cfg.to_toml_redacted().contains(REDACTED));
+        assert!(!cfg.to_toml_redacted().contains("12345"));
+    }
+
+    #[test]
+    fn rejects_invalid_values_early() {
+        let load = |sets: &[&str]| {
+            let sets: Vec<String> = sets.iter().map(|s| s.to_string()).collect();
+            NodeConfig::load(Some("/nonexistent/node.toml"), Vec::new(), &sets)
+        };
+        assert!(load(&[]).is_err());
+
+        let load = |sets: &[&str]| {
+            let sets: Vec<String> = sets.iter().map(|s| s.to_string()).collect();
+            NodeConfig::load(None, env(&[]), &sets)
+        };
+        assert!(load(&["rpc.http=not-an-addr"]).is_err());
+        assert!(load(&["rpc.ws=127.0.0.1:8545"]).is_err());
+        assert!(load(&["p2p.listen_addr=udp/30333"]).is_err());
+        assert!(load(&["lifecycle.max_restarts=many"]).is_err());
+        assert!(load(&["validator.sign_state=s.json"]).is_err());
+        assert!(load(&["rpc.http"]).is_err());
+    }
+
+    #[test]
+    fn shipped_config_matches_defaults() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../config/node.toml");
+        let cfg = NodeConfig::load(Some(path), Vec::new(), &[]).unwrap();
+        let defaults = NodeConfig::default();
+        assert_eq!(cfg.rpc.http, defaults.rpc.http);
+        assert_eq!(cfg.db.path, defaults.db.path);
+        assert_eq!(cfg.log.directives(), defaults.log.directives());
+        assert!(cfg.log.file.path.is_none());
+        assert!(cfg.validator.is_none());
+    }
+}
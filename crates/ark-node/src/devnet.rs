@@ -0,0 +1,474 @@
+//! 本地开发网：由固定助记词派生验证者密钥与测试账户，生成对应创世，
+//! 在同一进程内运行全部验证者，按短出块间隔轮流出块。
+//!
+//! - 测试账户：`m/44'/7777'/0'/0/i`（与钱包默认路径一致，导入同一助记词即可使用）
+//! - 验证者密钥：`m/44'/7777'/1'/0/i`
+//! - 每个验证者持有独立的内存数据库与本地签名器；区块须经超过 2/3 投票权的
+//!   precommit 签名后才由全部节点导入
+//!
+//! 助记词公开、数据不落盘，只用于开发与测试。
+use crate::node::{Node, Role};
+use anyhow::Context;
+use ark_consensus::{LocalSigner, SignGuard, SignPosition, Step, ValidatorSigner};
+use ark_crypto::{SecretKey, Signer};
+use ark_exec::staking;
+use ark_p2p::PeerBook;
+use ark_storage::ChainStore;
+use ark_types::genesis::{GenesisBalance, GenesisValidator};
+use ark_types::{Address, Amount, Block, Genesis};
+use ark_wallet_cli::wallet::hd;
+use std::sync::Arc;
+
+/// BIP39 标准测试助记词
+pub const DEVNET_MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+/// 每个测试账户的初始余额
+pub const ACCOUNT_BALANCE: Amount = 1_000_000_000_000_000_000_000;
+const ACCOUNT_PATH: &str = "m/44'/7777'/0'/0";
+const VALIDATOR_PATH: &str = "m/44'/7777'/1'/0";
+
+#[derive(Clone, Debug)]
+pub struct DevnetOptions {
+    pub validators: usize,
+    pub accounts: usize,
+    pub block_time_ms: u64,
+    pub chain_id: String,
+    pub mnemonic: String,
+}
+
+/// 由助记词派生的验证者与测试账户密钥。
+pub struct DevnetKeys {
+    pub validators: Vec<SecretKey>,
+    pub accounts: Vec<SecretKey>,
+}
+
+impl DevnetKeys {
+    pub fn derive(mnemonic: &str, validators: usize, accounts: usize) -> anyhow::Result<Self> {
+        let derive = |base: &str, count: usize| {
+            (0..count)
+                .map(|i| {
+                    let path = format!("{base}/{i}");
+                    let (secret, _) = hd::derive_priv_from_mnemonic(
+                        bip39::Language::English,
+                        mnemonic,
+                        "",
+                        &path,
+                    )
+                    .map_err(|e| anyhow::anyhow!("derive {path}: {e}"))?;
+                    Ok(SecretKey::from_bytes(&secret)?)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        Ok(Self {
+            validators: derive(VALIDATOR_PATH, validators)?,
+            accounts: derive(ACCOUNT_PATH, accounts)?,
+        })
+    }
+
+    /// 以内置创世模板为基础：验证者各质押 min_stake，测试账户各持 ACCOUNT_BALANCE。
+    pub fn genesis(&self, opts: &DevnetOptions) -> Genesis {
+        let mut genesis =
+            Genesis::from_json(crate::commands::GENESIS_JSON).expect("bundled genesis parses");
+        genesis.chain_id = opts.chain_id.clone();
+        genesis.genesis_time =
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        genesis.params.block_time_ms = opts.block_time_ms;
+        let stake = genesis.params.staking.min_stake;
+        genesis.validators = self
+            .validators
+            .iter()
+            .map(|key| GenesisValidator {
+                operator: Address::from_pubkey(&key.public_key()),
+                pubkey: key.public_key().to_vec(),
+                stake,
+                commission_bps: 0,
+            })
+            .collect();
+        genesis.balances = self
+            .accounts
+            .iter()
+            .map(|key| GenesisBalance {
+                address: Address::from_pubkey(&key.public_key()),
+                amount: ACCOUNT_BALANCE,
+            })
+            .collect();
+        genesis
+    }
+}
+
+/// 同一进程内的全部验证者节点。
+pub struct Devnet {
+    nodes: Vec<Arc<Node>>,
+    signers: Vec<LocalSigner>,
+}
+
+impl Devnet {
+    pub fn new(genesis: &Genesis, keys: &DevnetKeys) -> anyhow::Result<Self> {
+        let (state, block) = ark_exec::build_genesis(genesis)?;
+        let mut nodes = Vec::with_capacity(keys.validators.len());
+        let mut signers = Vec::with_capacity(keys.validators.len());
+        for key in &keys.validators {
+            let mut store = ChainStore::in_memory();
+            store.init_genesis(&block, state.entries())?;
+            nodes.push(Arc::new(Node::new(
+                Role::Validator,
+                genesis.clone(),
+                store,
+                PeerBook::new(),
+            )?));
+            signers.push(LocalSigner::new(
+                SecretKey::from_bytes(&key.to_bytes())?,
+                SignGuard::in_memory(),
+            ));
+        }
+        anyhow::ensure!(!nodes.is_empty(), "devnet needs at least one validator");
+        Ok(Self { nodes, signers })
+    }
+
+    pub fn node(&self, index: usize) -> Arc<Node> {
+        self.nodes[index].clone()
+    }
+
+    /// 出一个区块：同步各节点交易池，轮到的验证者提议，全体签名投票，
+    /// precommit 投票权达到法定值后由每个节点导入。
+    pub fn produce(&mut self, timestamp_ms: u64) -> anyhow::Result<Block> {
+        self.gossip_transactions();
+
+        let set = staking::active_set(&self.nodes[0].state.read().unwrap());
+        anyhow::ensure!(!set.is_empty(), "active validator set is empty");
+        let height = ark_rpc::ChainBackend::head(&*self.nodes[0]) + 1;
+        let _span = tracing::info_span!("consensus", height, round = 0u32).entered();
+        ark_consensus::metrics::enter(height, 0);
+        let proposer = &set.validators[(height % set.len() as u64) as usize];
+        let index = self
+            .signers
+            .iter()
+            .position(|s| s.public_key()[..] == proposer.pubkey[..])
+            .with_context(|| format!("no local signer for proposer {}", proposer.operator))?;
+        let block = self.nodes[index].propose(proposer.operator, timestamp_ms)?;
+        let digest = block.hash().0;
+        let position = |step| SignPosition {
+            height,
+            round: 0,
+            step,
+        };
+        self.signers[index].sign_vote(position(Step::Proposal), &digest)?;
+
+        let mut power: Amount = 0;
+        for signer in &mut self.signers {
+            signer.sign_vote(position(Step::Prevote), &digest)?;
+            let signature = signer.sign_vote(position(Step::Precommit), &digest)?;
+            let pubkey = signer.public_key();
+            if let Some(v) = set.by_pubkey(&pubkey) {
+                if ark_crypto::verify(&pubkey, &digest, &signature) {
+                    power += v.power;
+                }
+            }
+        }
+        anyhow::ensure!(
+            power >= set.quorum_power(),
+            "block {height} lacks quorum: {power} of {} power",
+            set.total_power()
+        );
+
+        for node in &self.nodes {
+            node.import_block(&block)
+                .with_context(|| format!("import block {height}"))?;
+        }
+        tracing::info!(
+            hash = %block.hash(),
+            proposer = %proposer.operator,
+            txs = block.txs.len(),
+            "devnet block committed"
+        );
+        Ok(block)
+    }
+
+    /// 把任一节点交易池中的交易广播到其余节点（重复或被拒的交易忽略）。
+    fn gossip_transactions(&self) {
+        let txs: Vec<_> = self
+            .nodes
+            .iter()
+            .flat_map(|n| {
+                let pool = n.mempool.lock().unwrap();
+                pool.iter().map(|p| p.stx.clone()).collect::<Vec<_>>()
+            })
+            .collect();
+        let now = crate::node::now_ms();
+        for node in &self.nodes {
+            let state = node.state.read().unwrap();
+            let mut pool = node.mempool.lock().unwrap();
+            for stx in &txs {
+                if !pool.contains(&stx.hash()) {
+                    let _ = pool.add(stx.clone(), &state, now);
+                }
+            }
+        }
+    }
+}
+
+/// 打印助记词、测试账户与验证者信息。
+pub fn print_summary(opts: &DevnetOptions, keys: &DevnetKeys, genesis: &Genesis) {
+    println!(
+        "devnet {} with {} validators, block time {} ms",
+        genesis.chain_id,
+        keys.validators.len(),
+        opts.block_time_ms
+    );
+    println!("mnemonic: {}", opts.mnemonic);
+    println!("accounts ({ACCOUNT_PATH}/i, balance {ACCOUNT_BALANCE}):");
+    for (i, key) in keys.accounts.iter().enumerate() {
+        println!(
+            "  [{i}] {}  private key {}",
+            Address::from_pubkey(&key.public_key()),
+            hex::encode(key.to_bytes())
+        );
+    }
+    println!("validators ({VALIDATOR_PATH}/i):");
+    for (i, key) in keys.validators.iter().enumerate() {
+        println!(
+            "  [{i}] {}  pubkey {}",
+            Address::from_pubkey(&key.public_key()),
+            hex::encode(key.public_key())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_rpc::ChainBackend;
+    use ark_types::{Action, SignedTransaction, Transaction};
+
+    fn options(validators: usize) -> DevnetOptions {
+        DevnetOptions {
+            validators,
+            accounts: 2,
+            block_time_ms: 200,
+            chain_id: "ark-devnet".into(),
+            mnemonic: DEVNET_MNEMONIC.into(),
+        }
+    }
+
+    #[test]
+    fn validators_rotate_and_include_transfers() {
+        let opts = options(3);
+        let keys = DevnetKeys::derive(&opts.mnemonic, opts.validators, opts.accounts).unwrap();
+        let again = DevnetKeys::derive(&opts.mnemonic, 1, 1).unwrap();
+        assert_eq!(again.accounts[0].to_bytes(), keys.accounts[0].to_bytes());
+        let genesis = keys.genesis(&opts);
+        let mut devnet = Devnet::new(&genesis, &keys).unwrap();
+
+        let (from, to) = (&keys.accounts[0], &keys.accounts[1]);
+        let tx = Transaction {
+            chain_id: genesis.chain_id.clone(),
+            nonce: 0,
+            action: Action::Transfer {
+                to: Address::from_pubkey(&to.public_key()),
+            },
+            value: 5,
+            gas_limit: 21_000,
+            max_fee_per_gas: 1_000_000,
+            max_priority_fee_per_gas: 1,
+        };
+        let signature = from.sign(&tx.signing_hash().0).unwrap().to_vec();
+        let stx = SignedTransaction {
+            tx,
+            pubkey: from.public_key().to_vec(),
+            signature,
+        };
+        // 只提交给最后一个节点，由 gossip 带给提议者
+        let hash = devnet.node(2).submit_transaction(stx).unwrap();
+
+        let mut proposers = std::collections::BTreeSet::new();
+        for i in 1..=3 {
+            let block = devnet.produce(crate::node::now_ms() + i).unwrap();
+            proposers.insert(block.header.proposer);
+        }
+        assert_eq!(proposers.len(), 3);
+        for i in 0..3 {
+            let node = devnet.node(i);
+            assert_eq!(node.head(), 3);
+            assert!(node.receipt(&hash).unwrap().is_some());
+            assert!(node.mempool.lock().unwrap().is_empty());
+            assert_eq!(
+                node.balance(&Address::from_pubkey(&to.public_key())),
+                ACCOUNT_BALANCE + 5
+            );
+        }
+    }
+
+    #[test]
+    fn state_proofs_cover_only_the_latest_root() {
+        let opts = options(1);
+        let keys = DevnetKeys::derive(&opts.mnemonic, 1, 1).unwrap();
+        let genesis = keys.genesis(&opts);
+        let mut devnet = Devnet::new(&genesis, &keys).unwrap();
+        let node = devnet.node(0);
+        let addr = Address::from_pubkey(&keys.accounts[0].public_key());
+        let key = ark_types::account::account_key(&addr);
+
+        let first = devnet.produce(crate::node::now_ms()).unwrap();
+        let root = first.header.state_root;
+        let proof = node.state_proof(&root, &key).unwrap();
+        assert_eq!(
+            proof.verify_account(&root, &addr).unwrap().balance,
+            ACCOUNT_BALANCE
+        );
+
+        // 新区块之后缓存的树作废：最新根可证明，旧根不再支持
+        let from = &keys.accounts[0];
+        let tx = Transaction {
+            chain_id: genesis.chain_id.clone(),
+            nonce: 0,
+            action: Action::Transfer { to: Address::ZERO },
+            value: 5,
+            gas_limit: 21_000,
+            max_fee_per_gas: 1_000_000,
+            max_priority_fee_per_gas: 1,
+        };
+        let signature = from.sign(&tx.signing_hash().0).unwrap().to_vec();
+        node.submit_transaction(SignedTransaction {
+            tx,
+            pubkey: from.public_key().to_vec(),
+            signature,
+        })
+        .unwrap();
+        let second = devnet.produce(crate::node::now_ms() + 1).unwrap();
+        assert_ne!(second.header.state_root, root);
+        assert!(node.state_proof(&root, &key).is_none());
+        let latest = second.header.state_root;
+        let proof = node.state_proof(&latest, &key).unwrap();
+        assert_eq!(proof.verify_account(&latest, &addr).unwrap().nonce, 1);
+    }
+
+    #[test]
+    fn simulates_against_recent_states() {
+        let opts = options(1);
+        let keys = DevnetKeys::derive(&opts.mnemonic, 1, 1).unwrap();
+        let genesis = keys.genesis(&opts);
+        let mut devnet = Devnet::new(&genesis, &keys).unwrap();
+        let node = devnet.node(0);
+        let from = &keys.accounts[0];
+        let tx = Transaction {
+            chain_id: genesis.chain_id.clone(),
+            nonce: 0,
+            action: Action::Transfer { to: Address::ZERO },
+            value: 5,
+            gas_limit: 21_000,
+            max_fee_per_gas: 1_000_000,
+            max_priority_fee_per_gas: 1,
+        };
+        let signature = from.sign(&tx.signing_hash().0).unwrap().to_vec();
+        node.submit_transaction(SignedTransaction {
+            tx,
+            pubkey: from.public_key().to_vec(),
+            signature,
+        })
+        .unwrap();
+        devnet.produce(crate::node::now_ms()).unwrap();
+
+        // 几乎转出全部初始余额：创世状态上可行，转账之后的最新状态上余额不足
+        let call = ark_types::CallRequest {
+            from: Address::from_pubkey(&from.public_key()),
+            action: Action::Transfer { to: Address::ZERO },
+            value: ACCOUNT_BALANCE - 21_000 * 1_000,
+            gas_limit: Some(21_000),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        assert_eq!(node.simulate(&call, 0).unwrap().gas_used, 21_000);
+        assert!(matches!(
+            node.simulate(&call, 1),
+            Err(ark_rpc::CallError::Rejected(_))
+        ));
+        assert_eq!(
+            node.simulate(&call, 2),
+            Err(ark_rpc::CallError::StateUnavailable(2))
+        );
+        // 模拟不改动链上状态
+        assert_eq!(node.nonce(&call.from, false), 1);
+    }
+
+    #[test]
+    fn contract_logs_are_indexed_and_queryable() {
+        use ark_exec::vm::op;
+        let opts = options(1);
+        let keys = DevnetKeys::derive(&opts.mnemonic, 1, 1).unwrap();
+        let genesis = keys.genesis(&opts);
+        let mut devnet = Devnet::new(&genesis, &keys).unwrap();
+        let node = devnet.node(0);
+        let key = &keys.accounts[0];
+        let send = |nonce, action| {
+            let tx = Transaction {
+                chain_id: genesis.chain_id.clone(),
+                nonce,
+                action,
+                value: 0,
+                gas_limit: 200_000,
+                max_fee_per_gas: 1_000_000,
+                max_priority_fee_per_gas: 1,
+            };
+            let signature = key.sign(&tx.signing_hash().0).unwrap().to_vec();
+            let stx = SignedTransaction {
+                tx,
+                pubkey: key.public_key().to_vec(),
+                signature,
+            };
+            node.submit_transaction(stx).unwrap()
+        };
+
+        let code = vec![op::PUSH, 1, 7, op::PUSH, 1, 42, op::LOG, 1, op::STOP];
+        let deploy = send(0, Action::Deploy { code });
+        devnet.produce(crate::node::now_ms()).unwrap();
+        let (receipt, _) = node.receipt(&deploy).unwrap().unwrap();
+        let contract = receipt.contract_address.unwrap();
+        send(
+            1,
+            Action::Call {
+                to: contract,
+                input: Vec::new(),
+            },
+        );
+        let block = devnet.produce(crate::node::now_ms() + 1).unwrap();
+        assert!(!block.header.logs_bloom.is_empty());
+
+        let filter = ark_types::LogFilter {
+            address: Some(ark_types::log::OneOrMany::One(contract)),
+            topics: Vec::new(),
+        };
+        let logs = node.logs(&filter, 0, node.head(), 10).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_height, 2);
+        assert_eq!(logs[0].log.topics, vec![ark_types::H256::from_u128(7)]);
+    }
+
+    #[test]
+    fn halts_at_an_upgrade_this_binary_lacks() {
+        let opts = options(1);
+        let keys = DevnetKeys::derive(&opts.mnemonic, 1, 0).unwrap();
+        let mut genesis = keys.genesis(&opts);
+        genesis.upgrades = vec![
+            ark_types::UpgradePlan {
+                name: "evm-1".into(),
+                height: 1,
+                gates: vec!["evm".into()],
+            },
+            ark_types::UpgradePlan {
+                name: "v9".into(),
+                height: 2,
+                gates: vec!["sharding".into()],
+            },
+        ];
+        let mut devnet = Devnet::new(&genesis, &keys).unwrap();
+        let node = devnet.node(0);
+        assert!(!node.feature_gates().evm);
+        devnet.produce(crate::node::now_ms()).unwrap();
+        assert!(node.feature_gates().evm);
+
+        let err = devnet.produce(crate::node::now_ms() + 1).unwrap_err();
+        assert!(node.halt_on_upgrade(&err));
+        assert!(node.halted().unwrap().contains("sharding"));
+        assert_eq!(node.head(), 1);
+    }
+}
@@ -0,0 +1,184 @@
+//! 健康检查端点（供 Kubernetes 探针使用）。
+//!
+//! - `GET /health/live`：进程能响应即 200
+//! - `GET /health/ready`：数据库可读、在线节点数不少于 min_peers、落后最高高度不超过
+//!   max_blocks_behind 时 200，否则 503
+//!
+//! 两个端点返回相同的 JSON 状态体。
+use crate::node::{Node, Role};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// 就绪所需的最少在线节点数（单节点 / 开发网为 0）
+    pub min_peers: usize,
+    /// 就绪时允许落后于最高已知高度的区块数
+    pub max_blocks_behind: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            min_peers: 0,
+            max_blocks_behind: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Checks {
+    pub database: bool,
+    pub peers: bool,
+    pub sync: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HealthStatus {
+    pub role: Role,
+    pub ready: bool,
+    pub height: u64,
+    pub highest_height: u64,
+    pub peers: usize,
+    pub syncing: bool,
+    pub checks: Checks,
+}
+
+pub fn status(node: &Node, config: &HealthConfig) -> HealthStatus {
+    // 锁被毒化（持锁线程 panic）或创世信息缺失都视为数据库不可用
+    let database = node
+        .store
+        .read()
+        .map(|s| s.genesis_hash().is_some())
+        .unwrap_or(false);
+    let sync = node.sync_status();
+    let checks = Checks {
+        database,
+        peers: sync.peers >= config.min_peers,
+        sync: sync.highest_height - sync.current_height <= config.max_blocks_behind,
+    };
+    HealthStatus {
+        role: node.role,
+        ready: checks.database && checks.peers && checks.sync,
+        height: sync.current_height,
+        highest_height: sync.highest_height,
+        peers: sync.peers,
+        syncing: sync.syncing,
+        checks,
+    }
+}
+
+fn handle(req: Request<Body>, node: &Node, config: &HealthConfig) -> Response<Body> {
+    let live = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/health/live") => true,
+        (&Method::GET, "/health/ready") => false,
+        (_, "/health/live" | "/health/ready") => {
+            return text(StatusCode::METHOD_NOT_ALLOWED, "method not allowed")
+        }
+        _ => return text(StatusCode::NOT_FOUND, "not found"),
+    };
+    let status = status(node, config);
+    let code = if live || status.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = serde_json::to_vec(&status).expect("health status serializes");
+    let mut resp = Response::new(Body::from(body));
+    *resp.status_mut() = code;
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        header::HeaderValue::from_static("no-store"),
+    );
+    resp
+}
+
+fn text(status: StatusCode, body: &'static str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(body));
+    *resp.status_mut() = status;
+    resp
+}
+
+/// 绑定端口，返回实际监听地址与服务 future（端口为 0 时由系统分配）。
+pub fn bind(
+    addr: SocketAddr,
+    node: Arc<Node>,
+    config: HealthConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<(SocketAddr, impl Future<Output = anyhow::Result<()>>)> {
+    let shared = Arc::new((node, config));
+    let make = make_service_fn(move |_| {
+        let shared = shared.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let shared = shared.clone();
+                async move { Ok::<_, Infallible>(handle(req, &shared.0, &shared.1)) }
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&addr)?.serve(make);
+    let addr = server.local_addr();
+    let server = server.with_graceful_shutdown(shutdown);
+    Ok((addr, async move { server.await.map_err(Into::into) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_p2p::{PeerBook, PeerSource};
+    use ark_storage::ChainStore;
+
+    fn node() -> Node {
+        let genesis = ark_types::Genesis::from_json(
+            &std::fs::read_to_string(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../config/genesis.json"
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        let (state, block) = ark_exec::build_genesis(&genesis).unwrap();
+        let mut store = ChainStore::in_memory();
+        store.init_genesis(&block, state.entries()).unwrap();
+        Node::new(Role::Observer, genesis, store, PeerBook::new()).unwrap()
+    }
+
+    #[test]
+    fn readiness_requires_peers_and_sync() {
+        let node = node();
+        let config = HealthConfig {
+            min_peers: 1,
+            max_blocks_behind: 5,
+        };
+        let s = status(&node, &config);
+        assert!(s.checks.database && s.checks.sync);
+        assert!(!s.ready, "no peers yet");
+
+        let peer = "/ip4/10.0.0.1/udp/30333/quic-v1";
+        {
+            let mut peers = node.peers.lock().unwrap();
+            peers.add(peer, PeerSource::Bootnode).unwrap();
+            peers.report_height(peer, 5, crate::node::now_ms());
+        }
+        let s = status(&node, &config);
+        assert!(s.ready && s.syncing);
+        assert_eq!((s.height, s.highest_height, s.peers), (0, 5, 1));
+
+        node.peers
+            .lock()
+            .unwrap()
+            .report_height(peer, 6, crate::node::now_ms());
+        let s = status(&node, &config);
+        assert!(!s.ready && !s.checks.sync);
+    }
+}
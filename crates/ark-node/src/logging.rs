@@ -0,0 +1,276 @@
+//! 日志：控制台与可选的滚动日志文件，格式为 compact（人读）或 json（供日志管道索引）。
+//!
+//! - 过滤：`[log] level` 加上 `[log.modules]` 的按模块级别，运行时可经 admin_setLogFilter 替换
+//! - json 每行一个事件，带 target 与当前 span 链上的字段（service、height、round、peer 等）
+//! - 日志文件按时间（每小时 / 每天）或大小滚动，保留最近 max_files 个旧文件：
+//!   node.log → node.log.1 → node.log.2 …
+use crate::admin::LogHandle;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::Layered;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Compact,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rotation {
+    /// 只按大小滚动
+    Never,
+    Hourly,
+    #[default]
+    Daily,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// 默认级别（EnvFilter 语法，如 "info"）
+    pub level: String,
+    pub format: LogFormat,
+    /// 按模块覆盖级别：ark_p2p = "debug"
+    pub modules: BTreeMap<String, String>,
+    pub file: LogFile,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".into(),
+            format: LogFormat::Compact,
+            modules: BTreeMap::new(),
+            file: LogFile::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogFile {
+    /// 未设置时只输出到控制台
+    pub path: Option<String>,
+    pub rotation: Rotation,
+    /// 单个文件超过该大小即滚动，0 表示不按大小滚动
+    pub max_size_mb: u64,
+    /// 保留的旧文件数
+    pub max_files: usize,
+}
+
+impl Default for LogFile {
+    fn default() -> Self {
+        Self {
+            path: None,
+            rotation: Rotation::Daily,
+            max_size_mb: 100,
+            max_files: 7,
+        }
+    }
+}
+
+impl LogConfig {
+    /// 合成 EnvFilter 指令：默认级别在前，按模块覆盖在后。
+    pub fn directives(&self) -> String {
+        let mut out = self.level.clone();
+        for (module, level) in &self.modules {
+            out.push_str(&format!(",{module}={level}"));
+        }
+        out
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        EnvFilter::try_new(self.directives())
+            .map_err(|e| anyhow::anyhow!("log.level / log.modules: {e}"))?;
+        if let Some(path) = &self.file.path {
+            anyhow::ensure!(!path.is_empty(), "log.file.path must not be empty");
+            anyhow::ensure!(
+                self.file.max_files > 0,
+                "log.file.max_files must be positive"
+            );
+        }
+        Ok(())
+    }
+}
+
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+
+/// 安装全局 subscriber，返回可在运行时替换过滤器的句柄。
+pub fn init(cfg: &LogConfig) -> anyhow::Result<LogHandle> {
+    let filter = EnvFilter::try_new(cfg.directives())?;
+    let (filter, handle) = reload::Layer::new(filter);
+    let mut layers = vec![format_layer(cfg.format, io::stdout, true)];
+    if cfg.file.path.is_some() {
+        let file = RotatingFile::open(&cfg.file)?;
+        layers.push(format_layer(cfg.format, Mutex::new(file), false));
+    }
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .init();
+    Ok(handle)
+}
+
+fn format_layer<W>(
+    format: LogFormat,
+    writer: W,
+    ansi: bool,
+) -> Box<dyn Layer<Filtered> + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Compact => fmt::layer()
+            .with_target(false)
+            .with_ansi(ansi)
+            .compact()
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+/// 按时间段或大小滚动的日志文件。每条事件一次写入，滚动只发生在事件之间。
+pub struct RotatingFile {
+    path: PathBuf,
+    rotation: Rotation,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+    period: i64,
+}
+
+impl RotatingFile {
+    pub fn open(cfg: &LogFile) -> io::Result<Self> {
+        let path = PathBuf::from(cfg.path.as_deref().unwrap_or_default());
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            period: period(cfg.rotation, chrono::Utc::now().timestamp()),
+            path,
+            rotation: cfg.rotation,
+            max_bytes: cfg.max_size_mb * 1024 * 1024,
+            max_files: cfg.max_files,
+            file,
+            size,
+        })
+    }
+
+    fn write_at(&mut self, buf: &[u8], now_secs: i64) -> io::Result<()> {
+        let period = period(self.rotation, now_secs);
+        let full = self.max_bytes > 0 && self.size + buf.len() as u64 > self.max_bytes;
+        if self.size > 0 && (period != self.period || full) {
+            self.rotate()?;
+        }
+        self.period = period;
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let numbered = |i: usize| {
+            let mut name = self.path.clone().into_os_string();
+            name.push(format!(".{i}"));
+            PathBuf::from(name)
+        };
+        let _ = fs::remove_file(numbered(self.max_files));
+        for i in (1..self.max_files).rev() {
+            let from = numbered(i);
+            if from.exists() {
+                fs::rename(from, numbered(i + 1))?;
+            }
+        }
+        fs::rename(&self.path, numbered(1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(buf, chrono::Utc::now().timestamp())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn period(rotation: Rotation, now_secs: i64) -> i64 {
+    match rotation {
+        Rotation::Never => 0,
+        Rotation::Hourly => now_secs.div_euclid(3600),
+        Rotation::Daily => now_secs.div_euclid(86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_by_size_and_period_keeping_max_files() {
+        let dir = std::env::temp_dir().join(format!("ark-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("node.log");
+        let mut file = RotatingFile::open(&LogFile {
+            path: Some(path.display().to_string()),
+            rotation: Rotation::Hourly,
+            max_size_mb: 0,
+            max_files: 2,
+        })
+        .unwrap();
+        file.max_bytes = 10;
+
+        let t0 = 3600 * 100;
+        file.write_at(b"aaaaaa\n", t0).unwrap();
+        file.write_at(b"bbbbbb\n", t0).unwrap(); // 超过 10 字节
+        file.write_at(b"cc\n", t0 + 3600).unwrap(); // 进入下一小时
+        file.write_at(b"dd\n", t0 + 3600).unwrap();
+        file.write_at(b"eeeeeeeeee\n", t0 + 3600).unwrap(); // 再次超限，最旧的文件被删除
+
+        let read = |p: &str| fs::read_to_string(dir.join(p)).unwrap();
+        assert_eq!(read("node.log"), "eeeeeeeeee\n");
+        assert_eq!(read("node.log.1"), "cc\ndd\n");
+        assert_eq!(read("node.log.2"), "bbbbbb\n");
+        assert!(!dir.join("node.log.3").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn module_levels_follow_the_default() {
+        let mut cfg = LogConfig::default();
+        cfg.modules.insert("ark_p2p".into(), "debug".into());
+        cfg.modules.insert("hyper".into(), "warn".into());
+        assert_eq!(cfg.directives(), "info,ark_p2p=debug,hyper=warn");
+        cfg.validate().unwrap();
+        cfg.modules.insert("ark_rpc".into(), "loud".into());
+        assert!(cfg.validate().is_err());
+    }
+}
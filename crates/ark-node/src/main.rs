@@ -1,71 +1,212 @@
-use clap::{ArgAction, Parser};
-use std::{fs, path::Path, time::Instant};
-use tracing_subscriber::EnvFilter;
+mod admin;
+mod commands;
+mod config;
+mod devnet;
+mod health;
+mod logging;
+mod node;
+mod producer;
+mod supervisor;
+mod validator;
+
+use anyhow::Context;
+use ark_exec::{build_genesis, State};
+use ark_p2p::PeerBook;
+use ark_rpc::{Access, GrpcConfig, HttpConfig, Methods, WsConfig};
+use ark_storage::ChainStore;
+use ark_types::Block;
+use clap::{ArgAction, Parser, Subcommand};
+use config::NodeConfig;
+use node::{Node, Role};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use supervisor::{Policy, Supervisor};
 
 #[derive(Parser, Debug)]
 #[command(name = "ark-node", version, about = "ArkProtocol-Astra Node")]
 struct Cli {
-    /// 配置文件路径
-    #[arg(long, default_value = "config/node.toml")]
-    config: String,
-    /// 日志级别
-    #[arg(long, default_value = "info")]
-    log: String,
-    /// 观察者模式
+    /// 配置文件路径（默认 config/node.toml，不存在时只用内置默认值）
+    #[arg(long, global = true)]
+    config: Option<String>,
+    /// 覆盖单个配置项，可重复：--set rpc.http=127.0.0.1:9545
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    overrides: Vec<String>,
+    /// 日志过滤（EnvFilter 语法），覆盖 [log] level 与 modules
+    #[arg(long)]
+    log: Option<String>,
+    /// 日志格式，覆盖 [log] format
+    #[arg(long, value_enum)]
+    log_format: Option<logging::LogFormat>,
+    /// 观察者模式：只同步与提供 RPC，不加载验证者密钥；配置中有 [validator] 时拒绝启动
     #[arg(long, action = ArgAction::SetTrue)]
     observer: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-#[derive(Debug, serde::Deserialize)]
-struct NodeConfig {
-    p2p: P2p,
-    rpc: Rpc,
-    db: Db,
-    genesis: Genesis,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 远程签名器：持有验证者私钥，经本地 Unix socket 为节点签名（自带双签保护）
+    RemoteSigner {
+        /// 加密的验证者私钥文件
+        #[arg(long)]
+        keystore: String,
+        /// 监听的 socket 路径（权限 0600）
+        #[arg(long, default_value = "data/signer.sock")]
+        socket: String,
+        /// 最后一次签名位置的持久化文件
+        #[arg(long, default_value = "data/signer_state.json")]
+        sign_state: String,
+        /// keystore 口令文件；未设置时读取 ARK_VALIDATOR_PASSWORD
+        #[arg(long)]
+        password_file: Option<String>,
+    },
+    /// 在 --home 下生成 node.toml、创世模板与 P2P 节点密钥
+    Init {
+        #[arg(long, default_value = ".")]
+        home: PathBuf,
+        /// 写入创世模板的链 ID
+        #[arg(long)]
+        chain_id: Option<String>,
+        /// 覆盖已有配置文件（节点密钥始终保留）
+        #[arg(long, action = ArgAction::SetTrue)]
+        force: bool,
+    },
+    /// 导出区块到可移植文件（节点须已停止）
+    ExportBlocks {
+        #[arg(long)]
+        out: PathBuf,
+        #[arg(long, default_value_t = 1)]
+        from: u64,
+        /// 缺省为当前最新高度
+        #[arg(long)]
+        to: Option<u64>,
+    },
+    /// 从区块文件逐块重放导入（节点须已停止）
+    ImportBlocks { file: PathBuf },
+    /// 状态快照导出 / 导入（节点须已停止）
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotCommand,
+    },
+    /// 删除链数据库，保留密钥、双签保护状态与快照（节点须已停止）
+    UnsafeReset {
+        #[arg(long, action = ArgAction::SetTrue)]
+        yes: bool,
+    },
+    /// 本地开发网：由固定助记词派生验证者与测试账户，在单进程内运行全部验证者（数据只在内存中）
+    Devnet {
+        #[arg(long, default_value_t = 4)]
+        validators: usize,
+        /// 预置余额的测试账户数
+        #[arg(long, default_value_t = 10)]
+        accounts: usize,
+        #[arg(long, default_value_t = 1000)]
+        block_time_ms: u64,
+        #[arg(long, default_value = "ark-devnet")]
+        chain_id: String,
+        /// 派生密钥所用的助记词，缺省为公开的测试助记词
+        #[arg(long)]
+        mnemonic: Option<String>,
+    },
+    /// 配置工具
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
 }
 
-#[derive(Debug, serde::Deserialize)]
-struct P2p {
-    listen_addr: String,
-    bootnodes: Vec<String>,
-}
-#[derive(Debug, serde::Deserialize)]
-struct Rpc {
-    http: String,
-    ws: String,
-    grpc: String,
-    metrics: String,
-    health: String,
-}
-#[derive(Debug, serde::Deserialize)]
-struct Db {
-    path: String,
+#[derive(Subcommand, Debug)]
+enum SnapshotCommand {
+    /// 截取当前最新高度的快照
+    Export {
+        /// 输出目录，缺省为 db.snapshot_dir
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// 用快照初始化空数据库
+    Import { file: PathBuf },
 }
-#[derive(Debug, serde::Deserialize)]
-struct Genesis {
-    file: String,
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// 打印合并默认值、配置文件、环境变量与 --set 之后的生效配置（隐去口令）
+    Print,
 }
 
+/// 交易池超龄淘汰的间隔
+const MEMPOOL_MAINTENANCE: Duration = Duration::from_secs(10);
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::new(cli.log.clone()))
-        .with_target(false)
-        .compact()
-        .init();
+    // 日志设置取自分层配置；配置本身有误时先按默认设置输出，错误在随后加载时报告
+    let mut log_cfg = NodeConfig::load(cli.config.as_deref(), std::env::vars(), &cli.overrides)
+        .map(|c| c.log)
+        .unwrap_or_default();
+    if let Some(filter) = &cli.log {
+        log_cfg.level = filter.clone();
+        log_cfg.modules.clear();
+    }
+    if let Some(format) = cli.log_format {
+        log_cfg.format = format;
+    }
+    let log_handle = logging::init(&log_cfg).context("invalid log settings")?;
+
+    if let Some(Command::Devnet {
+        validators,
+        accounts,
+        block_time_ms,
+        chain_id,
+        mnemonic,
+    }) = cli.command
+    {
+        let opts = devnet::DevnetOptions {
+            validators,
+            accounts,
+            block_time_ms,
+            chain_id,
+            mnemonic: mnemonic.unwrap_or_else(|| devnet::DEVNET_MNEMONIC.to_string()),
+        };
+        let cfg = NodeConfig::load(cli.config.as_deref(), std::env::vars(), &cli.overrides)?;
+        return run_devnet(cfg, opts, log_handle).await;
+    }
+    if let Some(command) = cli.command {
+        let (config, overrides) = (cli.config, cli.overrides);
+        return tokio::task::spawn_blocking(move || {
+            run_command(command, config.as_deref(), &overrides)
+        })
+        .await?;
+    }
 
     tracing::info!("ArkProtocol-Astra node starting...");
-    tracing::info!(config = %cli.config, observer = cli.observer, "loading config");
+    tracing::info!(
+        config = cli.config.as_deref().unwrap_or(config::DEFAULT_PATH),
+        overrides = cli.overrides.len(),
+        observer = cli.observer,
+        "loading config"
+    );
 
-    let cfg = load_config(&cli.config)?;
+    let cfg = NodeConfig::load(cli.config.as_deref(), std::env::vars(), &cli.overrides)?;
+    let role = Role::resolve(cli.observer, cfg.validator.is_some())?;
+    tracing::info!(
+        role = role.as_str(),
+        keys = cfg.validator.as_ref().map(|v| v.source()),
+        "node role"
+    );
+    // 观察者不加载任何密钥；验证者在打开数据库前先确认签名器可用
+    let signer = match &cfg.validator {
+        Some(v) if role == Role::Validator => Some(v.load_signer()?),
+        _ => None,
+    };
     tracing::info!(
         "config loaded: db.path={}, health={}",
         cfg.db.path,
         cfg.rpc.health
     );
-    // 使用 p2p/rpc/genesis 字段，避免未使用告警
     tracing::info!(
         p2p_listen = %cfg.p2p.listen_addr,
         bootnodes = cfg.p2p.bootnodes.len(),
@@ -77,110 +218,348 @@ async fn main() -> anyhow::Result<()> {
         "runtime config"
     );
 
-    // 确保数据库目录存在
-    if !Path::new(&cfg.db.path).exists() {
-        fs::create_dir_all(&cfg.db.path)?;
-        tracing::info!(db=%cfg.db.path, "created db directory");
-    }
-
-    // 读取创世文件（占位使用）
-    match fs::read_to_string(&cfg.genesis.file) {
-        Ok(_) => tracing::info!(genesis=%cfg.genesis.file, "genesis file found"),
-        Err(e) => {
-            tracing::warn!(genesis=%cfg.genesis.file, error=%e, "genesis file missing or unreadable")
+    // 打开数据库并初始化 / 校验创世区块
+    let (store, genesis) = init_chain(&cfg)?;
+    tracing::info!(
+        chain_id = %genesis.chain_id,
+        head = store.head().unwrap_or(0),
+        "chain database ready"
+    );
+    if let Some(signer) = &signer {
+        let pubkey = signer.public_key();
+        if !genesis.validators.iter().any(|v| v.pubkey == pubkey) {
+            tracing::warn!(
+                pubkey = %hex::encode(pubkey),
+                "validator key is not in the genesis validator set"
+            );
         }
+        tracing::info!(pubkey = %hex::encode(pubkey), "validator signer ready");
     }
-
-    // 启动健康检查 HTTP（极简实现）
-    let health_addr = cfg.rpc.health.clone();
-    let health_task = tokio::spawn(async move {
-        if let Err(e) = serve_health(&health_addr).await {
-            tracing::error!(%health_addr, error=%e, "health server failed");
+    match commands::read_node_key(std::path::Path::new(&cfg.p2p.node_key))? {
+        Some(key) => {
+            tracing::info!(node_id = %hex::encode(ark_crypto::Signer::public_key(&key)), "p2p node key loaded")
         }
+        None => tracing::warn!(path = %cfg.p2p.node_key, "no p2p node key; run `ark-node init`"),
+    }
+    let peers = PeerBook::with_bootnodes(cfg.p2p.bootnodes.iter().map(String::as_str))
+        .context("invalid p2p.bootnodes")?;
+    let node = Arc::new(Node::new(role, genesis, store, peers)?);
+    let producer = signer.map(|signer| {
+        Arc::new(std::sync::Mutex::new(producer::Producer::new(
+            node.clone(),
+            signer,
+        )))
     });
+    let n = node.clone();
+    run_services(cfg, node, log_handle, move |sup, restart| {
+        let Some(producer) = producer else {
+            return;
+        };
+        let halt = sup.token();
+        sup.add("consensus", restart, move |token| {
+            let (producer, node, halt) = (producer.clone(), n.clone(), halt.clone());
+            let block_time = {
+                let state = node.state.read().unwrap();
+                Duration::from_millis(node.exec.at(&state).params.block_time_ms)
+            };
+            Ok(Box::pin(async move {
+                let mut tick = tokio::time::interval(block_time);
+                tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => return Ok(()),
+                        _ = tick.tick() => {
+                            // 远程签名器走阻塞 socket
+                            let producer = producer.clone();
+                            let produced = tokio::task::spawn_blocking(move || {
+                                producer.lock().unwrap().step(node::now_ms())
+                            })
+                            .await?;
+                            match produced {
+                                Err(e) if node.halt_on_upgrade(&e) => {
+                                    halt.cancel();
+                                    return Ok(());
+                                }
+                                other => other?,
+                            };
+                        }
+                    }
+                }
+            }))
+        });
+    })
+    .await
+}
 
-    // 启动 /metrics（Prometheus 文本协议）
-    let metrics_addr = cfg.rpc.metrics.clone();
-    let start = Instant::now();
-    let version = env!("CARGO_PKG_VERSION");
-    let profile = if cfg!(debug_assertions) {
-        "dev"
-    } else {
-        "release"
+/// 开发网：派生密钥与创世，构建全部验证者，第一个验证者对外提供 RPC / 健康检查 / 指标。
+async fn run_devnet(
+    cfg: NodeConfig,
+    opts: devnet::DevnetOptions,
+    log_handle: admin::LogHandle,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(opts.validators > 0, "--validators must be at least 1");
+    anyhow::ensure!(opts.block_time_ms > 0, "--block-time-ms must be positive");
+    let keys = devnet::DevnetKeys::derive(&opts.mnemonic, opts.validators, opts.accounts)?;
+    let genesis = keys.genesis(&opts);
+    let devnet = devnet::Devnet::new(&genesis, &keys).context("build devnet genesis")?;
+    devnet::print_summary(&opts, &keys, &genesis);
+    let node = devnet.node(0);
+    let devnet = Arc::new(std::sync::Mutex::new(devnet));
+    let block_time = Duration::from_millis(opts.block_time_ms);
+    let n = node.clone();
+    run_services(cfg, node, log_handle, move |sup, restart| {
+        let halt = sup.token();
+        sup.add("devnet-producer", restart, move |token| {
+            let (devnet, node, halt) = (devnet.clone(), n.clone(), halt.clone());
+            Ok(Box::pin(async move {
+                let mut tick = tokio::time::interval(block_time);
+                tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => return Ok(()),
+                        _ = tick.tick() => {
+                            let produced = devnet.lock().unwrap().produce(node::now_ms());
+                            match produced {
+                                Err(e) if node.halt_on_upgrade(&e) => {
+                                    halt.cancel();
+                                    return Ok(());
+                                }
+                                other => other?,
+                            };
+                        }
+                    }
+                }
+            }))
+        });
+    })
+    .await
+}
+
+/// 注册并运行节点服务，直到收到停止信号。
+///
+/// consensus 在 mempool 之后、RPC 之前注册出块 / 投票服务（观察者不注册）。
+async fn run_services(
+    cfg: NodeConfig,
+    node: Arc<Node>,
+    log_handle: admin::LogHandle,
+    consensus: impl FnOnce(&mut Supervisor, Policy),
+) -> anyhow::Result<()> {
+    tracing::info!(gates = ?node.feature_gates(), "feature gates in effect");
+
+    // JSON-RPC（HTTP / WebSocket）与 gRPC
+    let mut methods = Methods::new();
+    ark_rpc::register_chain_api(&mut methods, node.clone());
+    ark_rpc::register_proof_api(&mut methods, node.clone());
+    ark_rpc::register_eth_api(&mut methods, node.clone());
+    let admin = admin::NodeAdmin {
+        node: node.clone(),
+        log: log_handle,
+        snapshot_dir: cfg.db.snapshot_dir.clone().into(),
     };
-    let metrics_task = tokio::spawn(async move {
-        if let Err(e) = serve_metrics(&metrics_addr, start, version, profile).await {
-            tracing::error!(%metrics_addr, error=%e, "metrics server failed");
-        }
+    ark_rpc::register_admin_api(&mut methods, Arc::new(admin));
+    let access = Arc::new(Access::new(&cfg.rpc.access).context("invalid rpc access config")?);
+    let mut http = HttpConfig::new(cfg.rpc.http);
+    http.access = access.clone();
+    let mut ws = WsConfig::new(cfg.rpc.ws);
+    ws.access = access.clone();
+    let mut grpc = GrpcConfig::new(cfg.rpc.grpc);
+    grpc.access = access;
+    grpc.methods = cfg.rpc.access.methods.grpc.clone();
+    let http_methods = Arc::new(cfg.rpc.access.methods.http.apply(&methods));
+    let ws_methods = Arc::new(cfg.rpc.access.methods.ws.apply(&methods));
+    let health_addr = cfg.rpc.health;
+    let metrics_addr = cfg.rpc.metrics;
+    register_metrics(Instant::now());
+
+    // 按依赖顺序注册服务，停止时逆序：先排空 RPC，最后刷盘存储
+    let mut sup = Supervisor::new(Duration::from_secs(cfg.lifecycle.stop_timeout_secs));
+    let restart = Policy::Restart {
+        max: cfg.lifecycle.max_restarts,
+        backoff: Duration::from_secs(1),
+    };
+
+    let n = node.clone();
+    sup.add("storage", Policy::FailFast, move |token| {
+        let node = n.clone();
+        Ok(Box::pin(async move {
+            token.cancelled().await;
+            node.store.write().unwrap().flush()?;
+            tracing::info!("storage flushed");
+            Ok(())
+        }))
     });
 
-    tracing::info!("node initialized (stub). Press Ctrl+C to stop.");
-    tokio::signal::ctrl_c().await?;
-    tracing::info!("shutdown signal received, cleaning up...");
+    let n = node.clone();
+    sup.add("mempool", restart, move |token| {
+        let node = n.clone();
+        Ok(Box::pin(async move {
+            let mut tick = tokio::time::interval(MEMPOOL_MAINTENANCE);
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => return Ok(()),
+                    _ = tick.tick() => {
+                        let evicted = node.mempool.lock().unwrap().evict_expired(node::now_ms());
+                        if !evicted.is_empty() {
+                            tracing::debug!(evicted = evicted.len(), "expired transactions evicted");
+                        }
+                    }
+                }
+            }
+        }))
+    });
 
-    // 停止后台任务
-    health_task.abort();
-    metrics_task.abort();
-    let _ = health_task.await;
-    let _ = metrics_task.await;
+    // p2p 尚为占位实现，接入后与共识一起在此处（mempool 之后、RPC 之前）注册
+    consensus(&mut sup, restart);
 
+    sup.add("rpc-http", Policy::FailFast, move |token| {
+        let (addr, server) =
+            ark_rpc::http::bind(http.clone(), http_methods.clone(), token.cancelled_owned())?;
+        tracing::info!(%addr, "json-rpc http server listening");
+        Ok(Box::pin(server))
+    });
+
+    let events = node.events.clone();
+    sup.add("rpc-ws", Policy::FailFast, move |token| {
+        let (addr, server) = ark_rpc::ws::bind_ws(
+            ws.clone(),
+            ws_methods.clone(),
+            events.clone(),
+            token.cancelled_owned(),
+        )?;
+        tracing::info!(%addr, "json-rpc websocket server listening");
+        Ok(Box::pin(server))
+    });
+
+    let (backend, events) = (node.clone(), node.events.clone());
+    sup.add("rpc-grpc", Policy::FailFast, move |token| {
+        let (addr, server) = ark_rpc::grpc::bind_grpc(
+            grpc.clone(),
+            backend.clone(),
+            events.clone(),
+            token.cancelled_owned(),
+        )?;
+        tracing::info!(%addr, "grpc server listening");
+        Ok(Box::pin(server))
+    });
+
+    let (n, health_cfg) = (node.clone(), cfg.health.clone());
+    sup.add("health", restart, move |token| {
+        let (addr, server) = health::bind(
+            health_addr,
+            n.clone(),
+            health_cfg.clone(),
+            token.cancelled_owned(),
+        )?;
+        tracing::info!(%addr, "health server listening");
+        Ok(Box::pin(server))
+    });
+
+    sup.add("metrics", restart, move |token| {
+        let (addr, server) = ark_metrics::bind(metrics_addr, token.cancelled_owned())?;
+        tracing::info!(%addr, "metrics server listening");
+        Ok(Box::pin(server))
+    });
+
+    let token = sup.token();
+    tokio::spawn(async move {
+        supervisor::shutdown_signal().await;
+        tracing::info!("shutdown signal received");
+        token.cancel();
+    });
+    tracing::info!("starting services; send SIGINT or SIGTERM to stop");
+    sup.run().await?;
+    if let Some(reason) = node.halted() {
+        anyhow::bail!("node halted: {reason}");
+    }
+    tracing::info!("node stopped");
     Ok(())
 }
 
-fn load_config(path: &str) -> anyhow::Result<NodeConfig> {
-    let raw = fs::read_to_string(path)?;
-    let cfg: NodeConfig = toml::from_str(&raw)?;
-    Ok(cfg)
+/// 运行一次性子命令（均为阻塞操作）。
+fn run_command(command: Command, config: Option<&str>, overrides: &[String]) -> anyhow::Result<()> {
+    let load = || NodeConfig::load(config, std::env::vars(), overrides);
+    match command {
+        Command::RemoteSigner {
+            keystore,
+            socket,
+            sign_state,
+            password_file,
+        } => commands::remote_signer(&keystore, &socket, &sign_state, password_file.as_deref()),
+        Command::Init {
+            home,
+            chain_id,
+            force,
+        } => commands::init(&home, chain_id.as_deref(), force),
+        Command::ExportBlocks { out, from, to } => {
+            commands::export_blocks(&load()?, &out, from, to)
+        }
+        Command::ImportBlocks { file } => commands::import_blocks(&load()?, &file),
+        Command::Snapshot {
+            action: SnapshotCommand::Export { out },
+        } => commands::snapshot_export(&load()?, out.as_deref()),
+        Command::Snapshot {
+            action: SnapshotCommand::Import { file },
+        } => commands::snapshot_import(&load()?, &file),
+        Command::UnsafeReset { yes } => commands::unsafe_reset(&load()?, yes),
+        Command::Devnet { .. } => unreachable!("devnet runs on the async runtime"),
+        Command::Config {
+            action: ConfigCommand::Print,
+        } => {
+            print!("{}", load()?.to_toml_redacted());
+            Ok(())
+        }
+    }
 }
 
-async fn serve_health(addr: &str) -> anyhow::Result<()> {
-    use tokio::io::AsyncWriteExt;
-    use tokio::net::TcpListener;
-
-    let listener = TcpListener::bind(addr).await?;
-    tracing::info!(%addr, "health server listening");
-    loop {
-        let (mut sock, _) = listener.accept().await?;
-        tokio::spawn(async move {
-            let resp =
-                b"HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: 2\r\n\r\nOK";
-            let _ = sock.write_all(resp).await;
-            let _ = sock.shutdown().await;
-        });
+/// 打开链数据库：空库写入创世区块与状态；已有数据则校验创世哈希一致。
+fn init_chain(cfg: &NodeConfig) -> anyhow::Result<(ChainStore, ark_types::Genesis)> {
+    let (genesis, state, block) = load_genesis(cfg)?;
+
+    let mut store = ChainStore::open(&cfg.db.path)?;
+    match store.genesis_hash() {
+        None => {
+            store.init_genesis(&block, state.entries())?;
+            tracing::info!(
+                hash = %block.hash(),
+                state_root = %block.header.state_root,
+                "genesis block written"
+            );
+        }
+        Some(stored) if stored == block.hash() => {
+            tracing::info!(hash = %stored, "genesis block verified");
+        }
+        Some(stored) => anyhow::bail!(
+            "genesis mismatch: database has {stored}, {} produces {}",
+            cfg.genesis.file,
+            block.hash()
+        ),
     }
+    Ok((store, genesis))
 }
 
-async fn serve_metrics(
-    addr: &str,
-    start: Instant,
-    version: &'static str,
-    profile: &'static str,
-) -> anyhow::Result<()> {
-    use tokio::io::AsyncWriteExt;
-    use tokio::net::TcpListener;
-
-    let listener = TcpListener::bind(addr).await?;
-    tracing::info!(%addr, "metrics server listening");
-    loop {
-        let (mut sock, _) = listener.accept().await?;
-        let uptime = start.elapsed().as_secs_f64();
-        let body = format!(
-            "# HELP ark_node_uptime_seconds Node uptime in seconds\n\
-             # TYPE ark_node_uptime_seconds gauge\n\
-             ark_node_uptime_seconds {}\n\
-             # HELP ark_node_build_info Build info\n\
-             # TYPE ark_node_build_info gauge\n\
-             ark_node_build_info{{version=\"{}\",profile=\"{}\"}} 1\n",
-            uptime, version, profile
-        );
-        let resp = format!(
-            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
-            body.len(),
-            body
-        );
-        tokio::spawn(async move {
-            let _ = sock.write_all(resp.as_bytes()).await;
-            let _ = sock.shutdown().await;
-        });
-    }
+/// 读取创世文件并构造创世状态与 0 号区块。
+fn load_genesis(cfg: &NodeConfig) -> anyhow::Result<(ark_types::Genesis, State, Block)> {
+    let raw = fs::read_to_string(&cfg.genesis.file)
+        .with_context(|| format!("read genesis file {}", cfg.genesis.file))?;
+    let genesis = ark_types::Genesis::from_json(&raw).context("parse genesis file")?;
+    let (state, block) = build_genesis(&genesis)?;
+    Ok((genesis, state, block))
+}
+
+fn register_metrics(start: Instant) {
+    ark_storage::metrics::register();
+    ark_exec::metrics::register();
+    ark_p2p::metrics::register();
+    ark_consensus::metrics::register();
+
+    let profile = if cfg!(debug_assertions) {
+        "dev"
+    } else {
+        "release"
+    };
+    ark_metrics::int_gauge_vec("node_build_info", "Build info", &["version", "profile"])
+        .with_label_values(&[env!("CARGO_PKG_VERSION"), profile])
+        .set(1);
+    let uptime = ark_metrics::gauge("node_uptime_seconds", "Node uptime in seconds");
+    ark_metrics::on_collect(move || uptime.set(start.elapsed().as_secs_f64()));
 }
@@ -0,0 +1,406 @@
+//! 节点运行时共享状态：链数据库、最新状态、交易池与节点表，供 RPC 等组件读取。
+use anyhow::Context;
+use ark_exec::{
+    fill_block, replay_block, BlockEnv, Executor, Mempool, MempoolConfig, SimulateError, State,
+    UpgradeError,
+};
+use ark_p2p::PeerBook;
+use ark_rpc::{CallError, ChainBackend, ChainEvent, EventBus, HeaderView, LogView, SyncStatus};
+use ark_storage::{ChainStore, TxLocation};
+use ark_types::proof::{StateProof, StateTree};
+use ark_types::{
+    Address, Amount, Block, BlockHeader, CallRequest, CallResult, FeatureGates, Gas, LogFilter,
+    Receipt, SignedTransaction, H256,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 组装区块时从交易池取出的候选交易上限
+const MAX_BLOCK_CANDIDATES: usize = 10_000;
+/// 保留最近多少个区块之后的状态，供指定高度的模拟执行 / gas 估算使用
+const RECENT_STATES: usize = 128;
+
+/// 节点角色。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// 持有验证者密钥，参与出块与投票
+    Validator,
+    /// 只同步与提供 RPC，从不加载密钥、不出块、不投票
+    Observer,
+}
+
+impl Role {
+    /// `--observer` 与 `[validator]` 配置互斥：公开 RPC 节点不应有机会接触验证者密钥。
+    pub fn resolve(observer: bool, has_validator_config: bool) -> anyhow::Result<Self> {
+        match (observer, has_validator_config) {
+            (true, true) => anyhow::bail!(
+                "observer mode refuses to start with a [validator] section in the config; \
+                 remove it or drop --observer"
+            ),
+            (false, true) => Ok(Role::Validator),
+            (_, false) => Ok(Role::Observer),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Validator => "validator",
+            Role::Observer => "observer",
+        }
+    }
+}
+
+pub struct Node {
+    pub role: Role,
+    pub genesis: ark_types::Genesis,
+    pub store: RwLock<ChainStore>,
+    pub state: RwLock<State>,
+    /// 最近 RECENT_STATES 个区块之后的状态（含最新），与最新状态结构共享
+    recent: Mutex<VecDeque<(u64, State)>>,
+    pub mempool: Mutex<Mempool>,
+    pub exec: Executor,
+    pub peers: Mutex<PeerBook>,
+    /// 链事件（WebSocket 订阅数据源）
+    pub events: EventBus,
+    /// 最新状态的 Merkle 树，首次出证明时构建，导入新区块时作废
+    proof_tree: Mutex<Option<Arc<StateTree>>>,
+    /// 因缺少已激活升级的实现而停止出块 / 导入时的原因
+    halted: Mutex<Option<String>>,
+}
+
+impl Node {
+    /// 由已初始化的链数据库恢复最新状态。
+    pub fn new(
+        role: Role,
+        genesis: ark_types::Genesis,
+        store: ChainStore,
+        peers: PeerBook,
+    ) -> anyhow::Result<Self> {
+        let state = State::from_entries(store.state_entries()).map_err(anyhow::Error::msg)?;
+        let recent = VecDeque::from([(store.head().unwrap_or(0), state.clone())]);
+        let exec = Executor::for_genesis(&genesis);
+        exec.check_activated(&state)?;
+        let mempool = Mempool::new(
+            MempoolConfig::from_params(&genesis.params),
+            genesis.chain_id.clone(),
+            exec.clone(),
+        );
+        Ok(Self {
+            role,
+            genesis,
+            store: RwLock::new(store),
+            state: RwLock::new(state),
+            recent: Mutex::new(recent),
+            mempool: Mutex::new(mempool),
+            exec,
+            peers: Mutex::new(peers),
+            events: EventBus::default(),
+            proof_tree: Mutex::new(None),
+            halted: Mutex::new(None),
+        })
+    }
+}
+
+impl Node {
+    /// 本地高度与在线节点上报的最高高度。
+    pub fn sync_status(&self) -> SyncStatus {
+        let current = self.head();
+        let peers = self.peers.lock().unwrap();
+        let highest = peers.best_height().unwrap_or(0).max(current);
+        SyncStatus {
+            syncing: highest > current,
+            current_height: current,
+            highest_height: highest,
+            peers: peers.active(now_ms()),
+        }
+    }
+
+    /// 在 height 区块之后状态的写时复制副本上运行 f，执行器与执行环境为下一个区块的。
+    /// 只保留最近 RECENT_STATES 个区块的状态，更早的高度返回 StateUnavailable。
+    fn with_scratch_state<T>(
+        &self,
+        height: u64,
+        f: impl FnOnce(&Executor, &mut State, &BlockEnv) -> T,
+    ) -> Result<T, CallError> {
+        let mut scratch = self
+            .recent
+            .lock()
+            .unwrap()
+            .iter()
+            .rfind(|(h, _)| *h == height)
+            .map(|(_, state)| state.clone())
+            .ok_or(CallError::StateUnavailable(height))?;
+        let parent = self
+            .store
+            .read()
+            .unwrap()
+            .header(height)
+            .map_err(|e| CallError::Rejected(e.to_string()))?
+            .ok_or(CallError::StateUnavailable(height))?;
+        let exec = self.exec.at(&scratch);
+        let env = BlockEnv {
+            chain_id: self.genesis.chain_id.clone(),
+            height: height + 1,
+            parent_hash: parent.hash(),
+            timestamp_ms: now_ms().max(parent.timestamp_ms + 1),
+            proposer: Address::ZERO,
+            gas_limit: exec.params.gas_limit_block,
+            base_fee: exec.fee_market.next_base_fee(
+                parent.base_fee,
+                parent.gas_used,
+                parent.gas_limit,
+            ),
+        };
+        Ok(f(&exec, &mut scratch, &env))
+    }
+}
+
+impl Node {
+    fn head_header(&self) -> anyhow::Result<BlockHeader> {
+        let store = self.store.read().unwrap();
+        let head = store.head().unwrap_or(0);
+        store
+            .header(head)?
+            .with_context(|| format!("missing header for head {head}"))
+    }
+
+    /// 以 proposer 身份在最新区块之上组装下一个区块（不提交，需再经 import_block）。
+    pub fn propose(&self, proposer: Address, timestamp_ms: u64) -> anyhow::Result<Block> {
+        let parent = self.head_header()?;
+        let mut scratch = self.state.read().unwrap().clone();
+        // 区块 gas 上限与 base fee 规则按治理修改后的参数
+        let exec = self.exec.at(&scratch);
+        let env = BlockEnv {
+            chain_id: self.genesis.chain_id.clone(),
+            height: parent.height + 1,
+            parent_hash: parent.hash(),
+            timestamp_ms: timestamp_ms.max(parent.timestamp_ms + 1),
+            proposer,
+            gas_limit: exec.params.gas_limit_block,
+            base_fee: exec.fee_market.next_base_fee(
+                parent.base_fee,
+                parent.gas_used,
+                parent.gas_limit,
+            ),
+        };
+        self.exec.check_upgrades(&scratch, env.height)?;
+        let candidates =
+            self.mempool
+                .lock()
+                .unwrap()
+                .pending(&scratch, env.base_fee, MAX_BLOCK_CANDIDATES);
+        let (built, skipped) = fill_block(&self.exec, &mut scratch, env, candidates)?;
+        for (stx, e) in &skipped {
+            tracing::debug!(hash = %stx.hash(), error = %e, "transaction skipped");
+        }
+        Ok(built.into_block(parent.hash()))
+    }
+
+    /// 在状态副本上重放并核对区块，通过后提交：写库、替换最新状态、清理交易池并发布链事件。
+    pub fn import_block(&self, block: &Block) -> anyhow::Result<()> {
+        let parent = self.head_header()?;
+        let mut scratch = self.state.read().unwrap().clone();
+        self.exec.check_upgrades(&scratch, block.height())?;
+        let built = replay_block(
+            &self.exec,
+            &mut scratch,
+            &self.genesis.chain_id,
+            &parent,
+            block,
+        )?;
+        self.store
+            .write()
+            .unwrap()
+            .commit_block(block, &built.receipts, scratch.entries())?;
+        {
+            let mut state = self.state.write().unwrap();
+            *state = scratch;
+            *self.proof_tree.lock().unwrap() = None;
+            let mut recent = self.recent.lock().unwrap();
+            recent.push_back((block.height(), state.clone()));
+            if recent.len() > RECENT_STATES {
+                recent.pop_front();
+            }
+            self.mempool.lock().unwrap().on_block(block, &state);
+        }
+
+        let height = block.height();
+        let mut logs = Vec::new();
+        for (tx_index, receipt) in built.receipts.iter().enumerate() {
+            for log in &receipt.logs {
+                logs.push(LogView {
+                    log: log.clone(),
+                    block_height: height,
+                    tx_hash: receipt.tx_hash,
+                    tx_index: tx_index as u32,
+                    log_index: logs.len() as u32,
+                });
+            }
+        }
+        for name in &built.upgrades {
+            tracing::info!(upgrade = %name, height, "protocol upgrade activated");
+        }
+        let hash = block.hash();
+        self.events
+            .publish(ChainEvent::NewHead(Box::new(HeaderView::new(
+                block.header.clone(),
+            ))));
+        if !logs.is_empty() {
+            self.events.publish(ChainEvent::Logs(logs));
+        }
+        self.events.publish(ChainEvent::Finalized { height, hash });
+        Ok(())
+    }
+}
+
+impl Node {
+    /// 当前生效的特性门。
+    pub fn feature_gates(&self) -> FeatureGates {
+        self.exec.feature_gates(&self.state.read().unwrap())
+    }
+
+    /// 若 err 源于本版本未实现的已激活升级，记录停止原因并返回 true；调用方随后应停止节点。
+    pub fn halt_on_upgrade(&self, err: &anyhow::Error) -> bool {
+        let Some(upgrade) = err.chain().find_map(|e| e.downcast_ref::<UpgradeError>()) else {
+            return false;
+        };
+        tracing::error!(reason = %upgrade, "halting: missing code for a scheduled protocol upgrade");
+        *self.halted.lock().unwrap() = Some(upgrade.to_string());
+        true
+    }
+
+    pub fn halted(&self) -> Option<String> {
+        self.halted.lock().unwrap().clone()
+    }
+
+    /// 最新状态的证明树；持有状态读锁期间构建并缓存，保证不会缓存到已被替换的状态。
+    fn proof_tree(&self) -> Arc<StateTree> {
+        let state = self.state.read().unwrap();
+        let mut cached = self.proof_tree.lock().unwrap();
+        cached
+            .get_or_insert_with(|| Arc::new(StateTree::new(state.entries())))
+            .clone()
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl ChainBackend for Node {
+    fn chain_id(&self) -> String {
+        self.genesis.chain_id.clone()
+    }
+
+    fn head(&self) -> u64 {
+        self.store.read().unwrap().head().unwrap_or(0)
+    }
+
+    fn block(&self, height: u64) -> anyhow::Result<Option<Block>> {
+        self.store.read().unwrap().block(height)
+    }
+
+    fn block_by_hash(&self, hash: &H256) -> anyhow::Result<Option<Block>> {
+        self.store.read().unwrap().block_by_hash(hash)
+    }
+
+    fn transaction(&self, hash: &H256) -> anyhow::Result<Option<(SignedTransaction, TxLocation)>> {
+        self.store.read().unwrap().transaction(hash)
+    }
+
+    fn pending_transaction(&self, hash: &H256) -> Option<SignedTransaction> {
+        let pool = self.mempool.lock().unwrap();
+        pool.get(hash).map(|p| p.stx.clone())
+    }
+
+    fn receipt(&self, hash: &H256) -> anyhow::Result<Option<(Receipt, TxLocation)>> {
+        self.store.read().unwrap().receipt(hash)
+    }
+
+    fn block_receipts(&self, height: u64) -> anyhow::Result<Option<Vec<Receipt>>> {
+        self.store.read().unwrap().receipts(height)
+    }
+
+    fn logs(
+        &self,
+        filter: &LogFilter,
+        from: u64,
+        to: u64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<LogView>> {
+        let logs = self.store.read().unwrap().logs(filter, from, to, limit)?;
+        Ok(logs
+            .into_iter()
+            .map(|l| LogView {
+                log: l.log,
+                block_height: l.height,
+                tx_hash: l.tx_hash,
+                tx_index: l.tx_index,
+                log_index: l.log_index,
+            })
+            .collect())
+    }
+
+    fn state_proof(&self, state_root: &H256, key: &[u8]) -> Option<StateProof> {
+        // 只保留最新状态：根不一致说明请求的是历史区块
+        let tree = self.proof_tree();
+        (tree.root() == *state_root).then(|| tree.prove(key))
+    }
+
+    fn balance(&self, address: &Address) -> Amount {
+        self.state.read().unwrap().balance(address)
+    }
+
+    fn code(&self, address: &Address) -> Option<Vec<u8>> {
+        let state = self.state.read().unwrap();
+        let code = state.code(address)?;
+        Some(
+            code.strip_prefix(ark_exec::evm::EVM_MAGIC)
+                .unwrap_or(code)
+                .to_vec(),
+        )
+    }
+
+    fn storage(&self, address: &Address, slot: &H256) -> H256 {
+        self.state.read().unwrap().storage(address, slot)
+    }
+
+    fn nonce(&self, address: &Address, pending: bool) -> u64 {
+        let state = self.state.read().unwrap();
+        if pending {
+            self.mempool.lock().unwrap().next_nonce(&state, address)
+        } else {
+            state.nonce(address)
+        }
+    }
+
+    fn submit_transaction(&self, stx: SignedTransaction) -> Result<H256, String> {
+        let state = self.state.read().unwrap();
+        let mut pool = self.mempool.lock().unwrap();
+        let added = pool.add(stx, &state, now_ms()).map_err(|e| e.to_string())?;
+        tracing::debug!(hash = %added.hash, pool = pool.len(), "transaction accepted");
+        self.events
+            .publish(ChainEvent::PendingTransaction(added.hash));
+        Ok(added.hash)
+    }
+
+    fn simulate(&self, call: &CallRequest, height: u64) -> Result<CallResult, CallError> {
+        self.with_scratch_state(height, |exec, state, env| exec.simulate(state, call, env))?
+            .map_err(|e| CallError::Rejected(e.to_string()))
+    }
+
+    fn estimate_gas(&self, call: &CallRequest, height: u64) -> Result<Gas, CallError> {
+        self.with_scratch_state(height, |exec, state, env| {
+            exec.estimate_gas(state, call, env)
+        })?
+        .map_err(|e| match e {
+            SimulateError::Reverted(reason) => CallError::Reverted(reason),
+            other => CallError::Rejected(other.to_string()),
+        })
+    }
+}
@@ -0,0 +1,143 @@
+//! 验证者出块服务：在最新区块之上组装下一个区块，依次签名提议 / prevote / precommit
+//! （签名器负责持久化的双签保护），随后导入。
+//!
+//! 验证者之间交换提议与投票需要 p2p，尚未接入，因此只有单独持有法定投票权的验证者
+//! （如单验证者网络）会出块，且每个高度都由它提议；投票权不足的验证者只同步、不签名。
+use crate::node::Node;
+use ark_consensus::{SignError, SignPosition, Step, ValidatorSigner};
+use ark_exec::staking;
+use ark_rpc::ChainBackend;
+use ark_types::Block;
+use std::sync::Arc;
+
+pub struct Producer {
+    node: Arc<Node>,
+    signer: Box<dyn ValidatorSigner>,
+    /// 当前高度与轮次；签名器拒绝本轮（如重启前已签过另一个区块）时换下一轮
+    height: u64,
+    round: u32,
+    /// 已提示过投票权不足，避免每个周期重复告警
+    warned: bool,
+}
+
+impl Producer {
+    pub fn new(node: Arc<Node>, signer: Box<dyn ValidatorSigner>) -> Self {
+        Self {
+            node,
+            signer,
+            height: 0,
+            round: 0,
+            warned: false,
+        }
+    }
+
+    /// 尝试出下一个区块；本验证者没有法定投票权或本轮签名被拒绝时返回 None。
+    pub fn step(&mut self, timestamp_ms: u64) -> anyhow::Result<Option<Block>> {
+        let set = staking::active_set(&self.node.state.read().unwrap());
+        let pubkey = self.signer.public_key();
+        let Some(me) = set
+            .by_pubkey(&pubkey)
+            .filter(|v| v.power >= set.quorum_power())
+        else {
+            if !std::mem::replace(&mut self.warned, true) {
+                tracing::warn!(
+                    pubkey = %hex::encode(pubkey),
+                    "validator lacks quorum power on its own; not producing blocks \
+                     until votes can be exchanged over p2p"
+                );
+            }
+            return Ok(None);
+        };
+        let operator = me.operator;
+        let height = self.node.head() + 1;
+        if height != self.height {
+            (self.height, self.round) = (height, 0);
+        }
+        let round = self.round;
+        let _span = tracing::info_span!("consensus", height, round).entered();
+        ark_consensus::metrics::enter(height, round);
+
+        let block = self.node.propose(operator, timestamp_ms)?;
+        let digest = block.hash().0;
+        for step in [Step::Proposal, Step::Prevote, Step::Precommit] {
+            let position = SignPosition {
+                height,
+                round,
+                step,
+            };
+            match self.signer.sign_vote(position, &digest) {
+                Ok(_) => {}
+                Err(e @ (SignError::Conflict(_) | SignError::Regression { .. })) => {
+                    tracing::warn!(error = %e, "signer refused this round; moving to the next");
+                    self.round += 1;
+                    return Ok(None);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.node.import_block(&block)?;
+        tracing::info!(hash = %block.hash(), txs = block.txs.len(), "block committed");
+        Ok(Some(block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devnet::{DevnetKeys, DevnetOptions, DEVNET_MNEMONIC};
+    use crate::node::Role;
+    use ark_consensus::{LocalSigner, SignGuard};
+    use ark_crypto::SecretKey;
+    use ark_p2p::PeerBook;
+    use ark_storage::ChainStore;
+
+    fn node(validators: usize) -> (Arc<Node>, DevnetKeys) {
+        let opts = DevnetOptions {
+            validators,
+            accounts: 1,
+            block_time_ms: 200,
+            chain_id: "ark-devnet".into(),
+            mnemonic: DEVNET_MNEMONIC.into(),
+        };
+        let keys = DevnetKeys::derive(&opts.mnemonic, validators, 1).unwrap();
+        let genesis = keys.genesis(&opts);
+        let (state, block) = ark_exec::build_genesis(&genesis).unwrap();
+        let mut store = ChainStore::in_memory();
+        store.init_genesis(&block, state.entries()).unwrap();
+        let node = Node::new(Role::Validator, genesis, store, PeerBook::new()).unwrap();
+        (Arc::new(node), keys)
+    }
+
+    fn signer(keys: &DevnetKeys, guard: SignGuard) -> Box<dyn ValidatorSigner> {
+        let key = SecretKey::from_bytes(&keys.validators[0].to_bytes()).unwrap();
+        Box::new(LocalSigner::new(key, guard))
+    }
+
+    #[test]
+    fn sole_validator_produces_and_skips_a_refused_round() {
+        let (node, keys) = node(1);
+        // 重启前已在高度 1 第 0 轮签过另一个区块
+        let mut guard = SignGuard::in_memory();
+        let earlier = SignPosition {
+            height: 1,
+            round: 0,
+            step: Step::Proposal,
+        };
+        guard.record(earlier, &[9; 32]).unwrap();
+        let mut producer = Producer::new(node.clone(), signer(&keys, guard));
+
+        assert!(producer.step(crate::node::now_ms()).unwrap().is_none());
+        let block = producer.step(crate::node::now_ms()).unwrap().unwrap();
+        assert_eq!((block.height(), node.head()), (1, 1));
+        producer.step(crate::node::now_ms() + 1).unwrap().unwrap();
+        assert_eq!(node.head(), 2);
+    }
+
+    #[test]
+    fn validator_without_quorum_does_not_sign() {
+        let (node, keys) = node(3);
+        let mut producer = Producer::new(node.clone(), signer(&keys, SignGuard::in_memory()));
+        assert!(producer.step(crate::node::now_ms()).unwrap().is_none());
+        assert_eq!(node.head(), 0);
+    }
+}
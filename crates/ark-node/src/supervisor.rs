@@ -0,0 +1,291 @@
+//! 服务编排：按依赖顺序启动、统一取消、逆序停止。
+//!
+//! - 服务按注册顺序启动；启动分两段：同步的准备阶段（打开资源、绑定端口）失败即整体失败，
+//!   成功后返回的 future 在独立任务中运行
+//! - 每个服务持有独立的取消令牌；根令牌被取消（如收到停止信号）后逆序逐个取消并等待，
+//!   每个服务有 stop_timeout 时间收尾（RPC 排空在途请求、存储刷盘），超时则中止
+//! - 服务在停止前自行退出：FailFast 立即停止全部服务并返回错误；Restart 在次数内退避后重启
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+pub type Running = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type StartFn = Box<dyn FnMut(CancellationToken) -> anyhow::Result<Running> + Send>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// 关键服务：意外退出即停止节点
+    FailFast,
+    /// 意外退出后等待 backoff 重启，最多 max 次，之后按 FailFast 处理
+    Restart { max: u32, backoff: Duration },
+}
+
+struct Service {
+    name: &'static str,
+    policy: Policy,
+    start: StartFn,
+}
+
+struct Slot {
+    name: &'static str,
+    policy: Policy,
+    start: StartFn,
+    token: CancellationToken,
+    handle: JoinHandle<()>,
+    restarts: u32,
+}
+
+pub struct Supervisor {
+    token: CancellationToken,
+    services: Vec<Service>,
+    stop_timeout: Duration,
+}
+
+impl Supervisor {
+    pub fn new(stop_timeout: Duration) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            services: Vec::new(),
+            stop_timeout,
+        }
+    }
+
+    /// 根取消令牌：取消即触发整体停止。
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// 注册服务；start 收到该服务的取消令牌，返回运行中的 future。
+    pub fn add<F>(&mut self, name: &'static str, policy: Policy, start: F)
+    where
+        F: FnMut(CancellationToken) -> anyhow::Result<Running> + Send + 'static,
+    {
+        self.services.push(Service {
+            name,
+            policy,
+            start: Box::new(start),
+        });
+    }
+
+    /// 启动全部服务并运行到根令牌取消或关键服务失败。
+    pub async fn run(self) -> anyhow::Result<()> {
+        let (exit_tx, mut exit_rx) = mpsc::unbounded_channel();
+        let mut slots: Vec<Slot> = Vec::with_capacity(self.services.len());
+        for mut service in self.services {
+            let token = CancellationToken::new();
+            match (service.start)(token.clone()) {
+                Ok(running) => {
+                    let handle = spawn(slots.len(), service.name, running, &exit_tx);
+                    tracing::info!(service = service.name, "service started");
+                    slots.push(Slot {
+                        name: service.name,
+                        policy: service.policy,
+                        start: service.start,
+                        token,
+                        handle,
+                        restarts: 0,
+                    });
+                }
+                Err(e) => {
+                    stop_all(slots, self.stop_timeout).await;
+                    return Err(e.context(format!("failed to start {}", service.name)));
+                }
+            }
+        }
+
+        let result = loop {
+            tokio::select! {
+                biased;
+                _ = self.token.cancelled() => break Ok(()),
+                Some((index, exit)) = exit_rx.recv() => {
+                    let slot = &mut slots[index];
+                    let reason = match &exit {
+                        Ok(()) => "exited".to_string(),
+                        Err(e) => format!("{e:#}"),
+                    };
+                    match slot.policy {
+                        Policy::Restart { max, backoff } if slot.restarts < max => {
+                            slot.restarts += 1;
+                            tracing::warn!(
+                                service = slot.name,
+                                %reason,
+                                attempt = slot.restarts,
+                                "service stopped unexpectedly, restarting"
+                            );
+                            tokio::time::sleep(backoff).await;
+                            match (slot.start)(slot.token.clone()) {
+                                Ok(running) => {
+                                    slot.handle = spawn(index, slot.name, running, &exit_tx);
+                                }
+                                Err(e) => {
+                                    break Err(e.context(format!("failed to restart {}", slot.name)))
+                                }
+                            }
+                        }
+                        _ => {
+                            tracing::error!(service = slot.name, %reason, "critical service stopped");
+                            break Err(anyhow::anyhow!("service {} stopped: {reason}", slot.name));
+                        }
+                    }
+                }
+            }
+        };
+        stop_all(slots, self.stop_timeout).await;
+        result
+    }
+}
+
+fn spawn(
+    index: usize,
+    name: &'static str,
+    running: Running,
+    exit_tx: &mpsc::UnboundedSender<(usize, anyhow::Result<()>)>,
+) -> JoinHandle<()> {
+    use tracing::Instrument;
+    let exit_tx = exit_tx.clone();
+    // 服务内的日志都带上 service 字段
+    let span = tracing::info_span!("service", service = name);
+    tokio::spawn(
+        async move {
+            let result = running.await;
+            if let Err(e) = &result {
+                tracing::error!(error = %format!("{e:#}"), "service failed");
+            }
+            let _ = exit_tx.send((index, result));
+        }
+        .instrument(span),
+    )
+}
+
+/// 逆序停止：先停 RPC 等上层服务，最后停存储。
+async fn stop_all(slots: Vec<Slot>, timeout: Duration) {
+    for mut slot in slots.into_iter().rev() {
+        slot.token.cancel();
+        match tokio::time::timeout(timeout, &mut slot.handle).await {
+            Ok(_) => tracing::info!(service = slot.name, "service stopped"),
+            Err(_) => {
+                tracing::warn!(
+                    service = slot.name,
+                    ?timeout,
+                    "service did not stop in time, aborting"
+                );
+                slot.handle.abort();
+            }
+        }
+    }
+}
+
+/// SIGINT（Ctrl+C）或 SIGTERM。
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+                return;
+            }
+            Err(e) => tracing::warn!(error = %e, "cannot listen for SIGTERM"),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    /// 运行到取消为止，并记录启动 / 停止事件。
+    fn recorder(
+        log: &Log,
+        name: &'static str,
+    ) -> impl FnMut(CancellationToken) -> anyhow::Result<Running> {
+        let log = log.clone();
+        move |token| {
+            log.lock().unwrap().push(format!("start {name}"));
+            let log = log.clone();
+            Ok(Box::pin(async move {
+                token.cancelled().await;
+                log.lock().unwrap().push(format!("stop {name}"));
+                Ok(())
+            }) as Running)
+        }
+    }
+
+    #[tokio::test]
+    async fn starts_in_order_stops_in_reverse_and_restarts() {
+        let log: Log = Arc::default();
+        let mut sup = Supervisor::new(Duration::from_secs(1));
+        sup.add("storage", Policy::FailFast, recorder(&log, "storage"));
+        let mut failures = 0;
+        let flaky_log = log.clone();
+        sup.add(
+            "flaky",
+            Policy::Restart {
+                max: 2,
+                backoff: Duration::from_millis(1),
+            },
+            move |token| {
+                flaky_log.lock().unwrap().push("start flaky".into());
+                failures += 1;
+                let fail = failures == 1;
+                Ok(Box::pin(async move {
+                    if fail {
+                        anyhow::bail!("boom");
+                    }
+                    token.cancelled().await;
+                    Ok(())
+                }) as Running)
+            },
+        );
+        sup.add("rpc", Policy::FailFast, recorder(&log, "rpc"));
+        let token = sup.token();
+        let run = tokio::spawn(sup.run());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        token.cancel();
+        run.await.unwrap().unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "start storage",
+                "start flaky",
+                "start rpc",
+                "start flaky",
+                "stop rpc",
+                "stop storage"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn critical_failure_stops_everything() {
+        let log: Log = Arc::default();
+        let mut sup = Supervisor::new(Duration::from_secs(1));
+        sup.add("storage", Policy::FailFast, recorder(&log, "storage"));
+        sup.add("consensus", Policy::FailFast, |_| {
+            Ok(Box::pin(async { anyhow::bail!("lost quorum") }) as Running)
+        });
+        let err = sup.run().await.unwrap_err();
+        assert!(err.to_string().contains("consensus"), "{err}");
+        assert_eq!(*log.lock().unwrap(), ["start storage", "stop storage"]);
+
+        // 准备阶段失败：已启动的服务被停止，后续服务不再启动
+        let log: Log = Arc::default();
+        let mut sup = Supervisor::new(Duration::from_secs(1));
+        sup.add("storage", Policy::FailFast, recorder(&log, "storage"));
+        sup.add("rpc", Policy::FailFast, |_| anyhow::bail!("address in use"));
+        sup.add("metrics", Policy::FailFast, recorder(&log, "metrics"));
+        let err = sup.run().await.unwrap_err();
+        assert!(format!("{err:#}").contains("failed to start rpc: address in use"));
+        assert_eq!(*log.lock().unwrap(), ["start storage", "stop storage"]);
+    }
+}
@@ -0,0 +1,84 @@
+//! 验证者签名器：本地 keystore 或远程签名器，两者都附加持久化的双签保护。
+use anyhow::Context;
+use ark_consensus::{LocalSigner, RemoteSigner, SignGuard, ValidatorSigner};
+use ark_crypto::{SecretKey, ValidatorKeystore};
+use std::fs;
+
+/// 未配置 password_file 时读取的环境变量
+pub const PASSWORD_ENV: &str = "ARK_VALIDATOR_PASSWORD";
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ValidatorConfig {
+    /// 加密的验证者私钥文件（钱包 keystore 亦可直接使用）
+    pub keystore: Option<String>,
+    /// keystore 口令文件；未设置时读取 ARK_VALIDATOR_PASSWORD
+    pub password_file: Option<String>,
+    /// 远程签名器的 Unix socket，与 keystore 二选一
+    pub remote_signer: Option<String>,
+    /// 最后一次签名位置的持久化文件
+    #[serde(default = "default_sign_state")]
+    pub sign_state: String,
+}
+
+fn default_sign_state() -> String {
+    "data/sign_state.json".into()
+}
+
+impl ValidatorConfig {
+    /// 日志中展示的密钥来源。
+    pub fn source(&self) -> &str {
+        self.remote_signer
+            .as_deref()
+            .or(self.keystore.as_deref())
+            .unwrap_or("-")
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match (&self.keystore, &self.remote_signer) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!("[validator] keystore and remote_signer are mutually exclusive")
+            }
+            (None, None) => anyhow::bail!("[validator] requires keystore or remote_signer"),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn load_signer(&self) -> anyhow::Result<Box<dyn ValidatorSigner>> {
+        self.validate()?;
+        let guard = SignGuard::open(&self.sign_state)
+            .with_context(|| format!("open sign state {}", self.sign_state))?;
+        if let Some(last) = guard.last() {
+            tracing::info!(%last, "resuming after last signed position");
+        }
+        if let Some(socket) = &self.remote_signer {
+            let signer = RemoteSigner::connect(socket, guard)
+                .with_context(|| format!("connect remote signer {socket}"))?;
+            return Ok(Box::new(signer));
+        }
+        let path = self.keystore.as_deref().expect("validated above");
+        let password = read_password(self.password_file.as_deref())?;
+        let key = load_keystore(path, &password)?;
+        Ok(Box::new(LocalSigner::new(key, guard)))
+    }
+}
+
+pub fn read_password(password_file: Option<&str>) -> anyhow::Result<String> {
+    match password_file {
+        Some(path) => {
+            let raw =
+                fs::read_to_string(path).with_context(|| format!("read password file {path}"))?;
+            Ok(raw.trim_end_matches(['\r', '\n']).to_string())
+        }
+        None => std::env::var(PASSWORD_ENV).with_context(|| {
+            format!("validator keystore password: set password_file or {PASSWORD_ENV}")
+        }),
+    }
+}
+
+pub fn load_keystore(path: &str, password: &str) -> anyhow::Result<SecretKey> {
+    let raw = fs::read_to_string(path).with_context(|| format!("read keystore {path}"))?;
+    let ks: ValidatorKeystore =
+        serde_json::from_str(&raw).with_context(|| format!("parse keystore {path}"))?;
+    ks.decrypt(password)
+        .with_context(|| format!("decrypt keystore {path}"))
+}
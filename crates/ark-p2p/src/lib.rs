@@ -1,2 +1,9 @@
 //! P2P 占位：后续集成 libp2p QUIC + gossipsub
+//!
+//! - peers：已知节点表（来源、评分、对端高度），供运维接口与同步进度使用
+pub mod metrics;
+pub mod peers;
+
+pub use peers::{validate_addr, PeerBook, PeerError, PeerInfo, PeerSource};
+
 pub struct P2p;
@@ -0,0 +1,18 @@
+//! 网络指标：节点表规模。
+use ark_metrics::IntGauge;
+use std::sync::LazyLock;
+
+pub(crate) static PEERS: LazyLock<IntGauge> =
+    LazyLock::new(|| ark_metrics::int_gauge("p2p_peers", "Known peers in the peer table"));
+pub(crate) static BANNED_PEERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    ark_metrics::int_gauge(
+        "p2p_banned_peers",
+        "Known peers whose score is at or below the ban threshold",
+    )
+});
+
+/// 在全局注册表中注册本 crate 的指标。
+pub fn register() {
+    LazyLock::force(&PEERS);
+    LazyLock::force(&BANNED_PEERS);
+}
@@ -0,0 +1,218 @@
+//! 已知节点表。
+//!
+//! - 节点以 multiaddr 字符串标识（如 `/ip4/1.2.3.4/udp/30333/quic-v1`），成对的 `/协议/值`
+//! - 评分限制在 [MIN_SCORE, MAX_SCORE]，不超过 BAN_SCORE 的节点视为被封禁
+//! - 对端高度由同步握手上报，用于计算同步进度
+use crate::metrics;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const MAX_SCORE: i32 = 100;
+pub const MIN_SCORE: i32 = -100;
+pub const BAN_SCORE: i32 = -50;
+/// 节点表容量上限
+pub const MAX_PEERS: usize = 256;
+/// 最近一次上报距今不超过该时长的节点视为在线
+pub const ACTIVE_WINDOW_MS: u64 = 60_000;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PeerError {
+    #[error("invalid multiaddr: {0}")]
+    InvalidAddr(String),
+    #[error("peer table full ({0} peers)")]
+    Full(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerSource {
+    /// 配置文件中的引导节点
+    Bootnode,
+    /// 运维通过 admin 接口添加
+    Admin,
+    /// 网络发现
+    Discovered,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeerInfo {
+    pub addr: String,
+    pub source: PeerSource,
+    pub score: i32,
+    pub banned: bool,
+    /// 对端上报的最新区块高度
+    pub best_height: Option<u64>,
+    pub last_seen_ms: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct PeerBook {
+    peers: BTreeMap<String, PeerInfo>,
+}
+
+impl PeerBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 以引导节点初始化；地址不合法时报错。
+    pub fn with_bootnodes<'a>(addrs: impl IntoIterator<Item = &'a str>) -> Result<Self, PeerError> {
+        let mut book = Self::new();
+        for addr in addrs {
+            book.add(addr, PeerSource::Bootnode)?;
+        }
+        Ok(book)
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// 添加节点；已存在时返回 false（保留原有评分）。
+    pub fn add(&mut self, addr: &str, source: PeerSource) -> Result<bool, PeerError> {
+        let addr = validate_addr(addr)?;
+        if self.peers.contains_key(addr) {
+            return Ok(false);
+        }
+        if self.peers.len() >= MAX_PEERS {
+            return Err(PeerError::Full(MAX_PEERS));
+        }
+        self.peers.insert(
+            addr.to_string(),
+            PeerInfo {
+                addr: addr.to_string(),
+                source,
+                score: 0,
+                banned: false,
+                best_height: None,
+                last_seen_ms: None,
+            },
+        );
+        self.report_metrics();
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, addr: &str) -> bool {
+        let removed = self.peers.remove(addr.trim()).is_some();
+        self.report_metrics();
+        removed
+    }
+
+    pub fn get(&self, addr: &str) -> Option<&PeerInfo> {
+        self.peers.get(addr)
+    }
+
+    /// 全部节点，按评分从高到低。
+    pub fn list(&self) -> Vec<PeerInfo> {
+        let mut peers: Vec<PeerInfo> = self.peers.values().cloned().collect();
+        peers.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.addr.cmp(&b.addr)));
+        peers
+    }
+
+    /// 调整评分，返回新评分；未知节点返回 None。
+    pub fn adjust_score(&mut self, addr: &str, delta: i32) -> Option<i32> {
+        let peer = self.peers.get_mut(addr)?;
+        peer.score = peer.score.saturating_add(delta).clamp(MIN_SCORE, MAX_SCORE);
+        peer.banned = peer.score <= BAN_SCORE;
+        let score = peer.score;
+        self.report_metrics();
+        Some(score)
+    }
+
+    /// 记录对端上报的高度。
+    pub fn report_height(&mut self, addr: &str, height: u64, now_ms: u64) -> bool {
+        let Some(peer) = self.peers.get_mut(addr) else {
+            return false;
+        };
+        peer.best_height = Some(height);
+        peer.last_seen_ms = Some(now_ms);
+        true
+    }
+
+    /// 在线（未封禁且近期有上报）的节点数。
+    pub fn active(&self, now_ms: u64) -> usize {
+        self.peers
+            .values()
+            .filter(|p| !p.banned)
+            .filter(|p| {
+                p.last_seen_ms
+                    .is_some_and(|t| now_ms.saturating_sub(t) <= ACTIVE_WINDOW_MS)
+            })
+            .count()
+    }
+
+    /// 未被封禁的节点中上报的最高高度。
+    pub fn best_height(&self) -> Option<u64> {
+        self.peers
+            .values()
+            .filter(|p| !p.banned)
+            .filter_map(|p| p.best_height)
+            .max()
+    }
+
+    fn report_metrics(&self) {
+        let banned = self.peers.values().filter(|p| p.banned).count();
+        metrics::PEERS.set(self.peers.len() as i64);
+        metrics::BANNED_PEERS.set(banned as i64);
+    }
+}
+
+/// 校验 multiaddr 的基本形态（传输层前缀 + 成对段），返回去除首尾空白后的地址。
+pub fn validate_addr(addr: &str) -> Result<&str, PeerError> {
+    let addr = addr.trim();
+    let invalid = || PeerError::InvalidAddr(addr.to_string());
+    let parts: Vec<&str> = addr
+        .strip_prefix('/')
+        .ok_or_else(invalid)?
+        .split('/')
+        .collect();
+    let transport = matches!(parts[0], "ip4" | "ip6" | "dns" | "dns4" | "dns6");
+    if !transport || parts.len() < 2 || parts.iter().any(|p| p.is_empty()) {
+        return Err(invalid());
+    }
+    Ok(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_remove_and_score_peers() {
+        let boot = "/ip4/10.0.0.1/udp/30333/quic-v1";
+        let mut book = PeerBook::with_bootnodes([boot]).unwrap();
+        assert!(book
+            .add("/dns4/peer.example/udp/30333/quic-v1", PeerSource::Admin)
+            .unwrap());
+        assert!(!book.add(boot, PeerSource::Admin).unwrap());
+        assert_eq!(book.get(boot).unwrap().source, PeerSource::Bootnode);
+        for bad in ["", "ip4/1.2.3.4", "/tcp/1", "/ip4//udp/1"] {
+            assert!(matches!(
+                book.add(bad, PeerSource::Admin),
+                Err(PeerError::InvalidAddr(_))
+            ));
+        }
+
+        assert_eq!(book.adjust_score(boot, 30), Some(30));
+        assert_eq!(book.list()[0].addr, boot);
+        book.report_height(boot, 12, 1);
+        book.report_height("/dns4/peer.example/udp/30333/quic-v1", 40, 1);
+        assert_eq!(book.best_height(), Some(40));
+        assert_eq!(book.active(1 + ACTIVE_WINDOW_MS), 2);
+        assert_eq!(book.active(2 + ACTIVE_WINDOW_MS), 0);
+        assert_eq!(
+            book.adjust_score("/dns4/peer.example/udp/30333/quic-v1", -500),
+            Some(MIN_SCORE)
+        );
+        // 被封禁的节点不计入同步目标
+        assert_eq!(book.best_height(), Some(12));
+
+        assert!(book.remove(boot));
+        assert!(!book.remove(boot));
+        assert_eq!(book.len(), 1);
+    }
+}
@@ -0,0 +1,500 @@
+//! RPC 访问控制，配置来自 node.toml 的 `[rpc.cors]`、`[rpc.rate_limit]`、`[rpc.auth]`、`[rpc.methods]`。
+//!
+//! - CORS：带 Origin 的请求须在 allowed_origins 中（`"*"` 放行全部），否则 403
+//! - 限流：令牌桶；携带有效 `X-API-Key` 的请求按 key 计数，其余（含凭据无效的请求）按来源 IP
+//!   计数，且在校验凭据之前扣除；超限返回 429
+//! - 鉴权：admin 命名空间（默认 `admin_*`）需 `Authorization: Bearer <token>`，
+//!   token 为静态口令或 HS256 JWT（iat 须在 jwt_max_age_secs 之内）；未配置凭据时 admin 方法不可用
+//! - 方法过滤：每个端点（http / ws）按 allow / deny 模式裁剪方法表，`*` 结尾为前缀匹配
+use crate::error::RpcError;
+use crate::methods::Methods;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use hyper::header::{HeaderValue, AUTHORIZATION, ORIGIN};
+use hyper::HeaderMap;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 调用 admin 方法但未通过鉴权
+pub const UNAUTHORIZED: i64 = -32006;
+
+/// 令牌桶数量上限：超过时先清理已回满的桶，仍超限则淘汰最久未使用的桶
+const MAX_BUCKETS: usize = 10_000;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessConfig {
+    pub cors: CorsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub auth: AuthConfig,
+    pub methods: MethodsConfig,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// 允许的 Origin；为空时拒绝所有浏览器跨域请求
+    pub allowed_origins: Vec<String>,
+}
+
+/// 每秒请求数为 0 表示不限流。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub per_ip_per_sec: u32,
+    pub per_ip_burst: u32,
+    pub per_key_per_sec: u32,
+    pub per_key_burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_ip_per_sec: 100,
+            per_ip_burst: 200,
+            per_key_per_sec: 1000,
+            per_key_burst: 2000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// 已签发的 API key（享受按 key 的限额）
+    pub api_keys: Vec<String>,
+    /// admin 静态 bearer token
+    pub admin_token: Option<String>,
+    /// HS256 密钥文件（十六进制）
+    pub jwt_secret_file: Option<PathBuf>,
+    pub jwt_max_age_secs: u64,
+    /// 需要 admin 鉴权的命名空间（方法名前缀，不含下划线）
+    pub admin_namespaces: Vec<String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            api_keys: Vec::new(),
+            admin_token: None,
+            jwt_secret_file: None,
+            jwt_max_age_secs: 60,
+            admin_namespaces: vec!["admin".into()],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MethodsConfig {
+    pub http: MethodFilter,
+    pub ws: MethodFilter,
+    /// gRPC 按 proto 中的方法名匹配，如 "SendTransaction"
+    pub grpc: MethodFilter,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MethodFilter {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl Default for MethodFilter {
+    fn default() -> Self {
+        Self {
+            allow: vec!["*".into()],
+            deny: Vec::new(),
+        }
+    }
+}
+
+impl MethodFilter {
+    pub fn permits(&self, method: &str) -> bool {
+        let hit = |patterns: &[String]| patterns.iter().any(|p| pattern_matches(p, method));
+        hit(&self.allow) && !hit(&self.deny)
+    }
+
+    /// 只保留本过滤器允许的方法。
+    pub fn apply(&self, methods: &Methods) -> Methods {
+        let mut out = methods.clone();
+        out.retain(|name| self.permits(name));
+        out
+    }
+}
+
+fn pattern_matches(pattern: &str, method: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => method.starts_with(prefix),
+        None => pattern == method,
+    }
+}
+
+/// 请求被拒绝的原因（由传输层映射为 HTTP 状态码）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Denied {
+    /// Origin 不在允许列表中（403）
+    Origin,
+    /// 凭据无效（401）
+    Unauthorized(&'static str),
+    /// 超出限额，附建议重试间隔（429）
+    RateLimited(Duration),
+}
+
+/// 已识别的调用方。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Caller {
+    pub admin: bool,
+    bucket: BucketKey,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum BucketKey {
+    Ip(IpAddr),
+    Key(String),
+}
+
+/// 运行期访问控制（由 AccessConfig 构建，HTTP 与 WebSocket 共享同一组限流桶）。
+pub struct Access {
+    origins: Vec<String>,
+    api_keys: HashSet<String>,
+    admin_token: Option<String>,
+    jwt_secret: Option<Vec<u8>>,
+    jwt_max_age: u64,
+    admin_namespaces: Vec<String>,
+    per_ip: RateLimiter<IpAddr>,
+    per_key: RateLimiter<String>,
+}
+
+impl Default for Access {
+    /// 不限流、无 admin 凭据、不允许跨域。
+    fn default() -> Self {
+        Self::build(
+            &AccessConfig {
+                rate_limit: RateLimitConfig {
+                    per_ip_per_sec: 0,
+                    per_ip_burst: 0,
+                    per_key_per_sec: 0,
+                    per_key_burst: 0,
+                },
+                ..AccessConfig::default()
+            },
+            None,
+        )
+    }
+}
+
+impl std::fmt::Debug for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Access")
+            .field("origins", &self.origins)
+            .field("api_keys", &self.api_keys.len())
+            .field("admin_token", &self.admin_token.is_some())
+            .field("jwt", &self.jwt_secret.is_some())
+            .field("admin_namespaces", &self.admin_namespaces)
+            .finish()
+    }
+}
+
+impl Access {
+    /// 由配置构建；读取 JWT 密钥文件。
+    pub fn new(config: &AccessConfig) -> anyhow::Result<Self> {
+        let secret = match &config.auth.jwt_secret_file {
+            Some(path) => {
+                let raw = std::fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("read jwt secret {}: {e}", path.display()))?;
+                let raw = raw.trim();
+                let secret = hex::decode(raw.strip_prefix("0x").unwrap_or(raw)).map_err(|e| {
+                    anyhow::anyhow!("jwt secret {} is not hex: {e}", path.display())
+                })?;
+                anyhow::ensure!(secret.len() >= 32, "jwt secret must be at least 32 bytes");
+                Some(secret)
+            }
+            None => None,
+        };
+        Ok(Self::build(config, secret))
+    }
+
+    fn build(config: &AccessConfig, jwt_secret: Option<Vec<u8>>) -> Self {
+        let rl = &config.rate_limit;
+        Self {
+            origins: config.cors.allowed_origins.clone(),
+            api_keys: config.auth.api_keys.iter().cloned().collect(),
+            admin_token: config.auth.admin_token.clone().filter(|t| !t.is_empty()),
+            jwt_secret,
+            jwt_max_age: config.auth.jwt_max_age_secs,
+            admin_namespaces: config.auth.admin_namespaces.clone(),
+            per_ip: RateLimiter::new(rl.per_ip_per_sec, rl.per_ip_burst),
+            per_key: RateLimiter::new(rl.per_key_per_sec, rl.per_key_burst),
+        }
+    }
+
+    /// 校验 Origin；允许时返回应回显的 Access-Control-Allow-Origin 值（无 Origin 时为 None）。
+    pub fn check_origin(&self, headers: &HeaderMap) -> Result<Option<HeaderValue>, Denied> {
+        let Some(origin) = headers.get(ORIGIN) else {
+            return Ok(None);
+        };
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|o| self.origins.iter().any(|a| a == "*" || a == o));
+        if allowed {
+            Ok(Some(origin.clone()))
+        } else {
+            Err(Denied::Origin)
+        }
+    }
+
+    /// 识别调用方（API key / admin 凭据）并扣除一次限额。
+    /// 先扣限额再校验凭据：无效 API key 按来源 IP 计数，猜测凭据同样受限流约束。
+    pub fn authenticate(&self, headers: &HeaderMap, ip: IpAddr) -> Result<Caller, Denied> {
+        let key = headers
+            .get("x-api-key")
+            .map(|k| k.to_str().unwrap_or_default());
+        let bucket = match key {
+            Some(key) if self.api_keys.contains(key) => BucketKey::Key(key.to_string()),
+            _ => BucketKey::Ip(ip),
+        };
+        self.charge(&bucket)?;
+        if matches!(bucket, BucketKey::Ip(_)) && key.is_some() {
+            return Err(Denied::Unauthorized("invalid api key"));
+        }
+        let admin = match headers.get(AUTHORIZATION) {
+            Some(value) => {
+                let token = value
+                    .to_str()
+                    .ok()
+                    .and_then(|v| v.strip_prefix("Bearer "))
+                    .ok_or(Denied::Unauthorized("expected bearer token"))?;
+                if !self.is_admin_token(token.trim()) {
+                    return Err(Denied::Unauthorized("invalid bearer token"));
+                }
+                true
+            }
+            None => false,
+        };
+        Ok(Caller { admin, bucket })
+    }
+
+    /// 扣除一次限额（WebSocket 连接上的每条消息调用一次）。
+    pub fn throttle(&self, caller: &Caller) -> Result<(), Denied> {
+        self.charge(&caller.bucket)
+    }
+
+    fn charge(&self, bucket: &BucketKey) -> Result<(), Denied> {
+        let now = Instant::now();
+        match bucket {
+            BucketKey::Ip(ip) => self.per_ip.check(*ip, now),
+            BucketKey::Key(key) => self.per_key.check(key.clone(), now),
+        }
+        .map_err(Denied::RateLimited)
+    }
+
+    fn is_admin_token(&self, token: &str) -> bool {
+        if let Some(expected) = &self.admin_token {
+            if ct_eq(expected.as_bytes(), token.as_bytes()) {
+                return true;
+            }
+        }
+        match &self.jwt_secret {
+            Some(secret) => verify_jwt(secret, token, unix_now(), self.jwt_max_age).is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn is_admin_method(&self, method: &str) -> bool {
+        self.admin_namespaces.iter().any(|ns| {
+            method
+                .strip_prefix(ns.as_str())
+                .is_some_and(|rest| rest.starts_with('_'))
+        })
+    }
+
+    /// 拆出未鉴权调用方使用的方法表：admin 命名空间的方法一律返回 UNAUTHORIZED。
+    pub fn public_view(&self, methods: &Methods) -> Methods {
+        let mut out = methods.clone();
+        let admin: Vec<String> = methods
+            .names()
+            .filter(|m| self.is_admin_method(m))
+            .map(str::to_string)
+            .collect();
+        for name in admin {
+            out.register(name, |_| {
+                Err(RpcError::new(UNAUTHORIZED, "admin authentication required"))
+            });
+        }
+        out
+    }
+}
+
+/// 令牌桶限流器。
+struct RateLimiter<K> {
+    per_sec: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<K, (f64, Instant)>>,
+}
+
+impl<K: Eq + Hash + Clone> RateLimiter<K> {
+    fn new(per_sec: u32, burst: u32) -> Self {
+        Self {
+            per_sec: per_sec as f64,
+            burst: burst.max(per_sec).max(1) as f64,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn check(&self, key: K, now: Instant) -> Result<(), Duration> {
+        if self.per_sec == 0.0 {
+            return Ok(());
+        }
+        let refill = |tokens: f64, last: Instant| {
+            (tokens + now.saturating_duration_since(last).as_secs_f64() * self.per_sec)
+                .min(self.burst)
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_BUCKETS && !buckets.contains_key(&key) {
+            buckets.retain(|_, (tokens, last)| refill(*tokens, *last) < self.burst);
+            while buckets.len() >= MAX_BUCKETS {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, (_, last))| *last)
+                    .map(|(k, _)| k.clone())
+                    .expect("bucket map is non-empty");
+                buckets.remove(&oldest);
+            }
+        }
+        let (tokens, last) = buckets.entry(key).or_insert((self.burst, now));
+        *tokens = refill(*tokens, *last);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - *tokens) / self.per_sec))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum JwtError {
+    Malformed,
+    Algorithm,
+    Signature,
+    Expired,
+}
+
+/// 校验 HS256 JWT：签名、`iat`（必填，与当前时间相差不超过 max_age 秒）与可选的 `exp`。
+pub fn verify_jwt(secret: &[u8], token: &str, now: u64, max_age: u64) -> Result<(), JwtError> {
+    let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    let mut parts = token.split('.');
+    let (Some(h), Some(p), Some(s), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(JwtError::Malformed);
+    };
+    let decode_json = |part: &str| -> Result<serde_json::Value, JwtError> {
+        let raw = b64.decode(part).map_err(|_| JwtError::Malformed)?;
+        serde_json::from_slice(&raw).map_err(|_| JwtError::Malformed)
+    };
+    if decode_json(h)?["alg"] != "HS256" {
+        return Err(JwtError::Algorithm);
+    }
+    let sig = b64.decode(s).map_err(|_| JwtError::Malformed)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("hmac accepts any key length");
+    mac.update(h.as_bytes());
+    mac.update(b".");
+    mac.update(p.as_bytes());
+    mac.verify_slice(&sig).map_err(|_| JwtError::Signature)?;
+
+    let claims = decode_json(p)?;
+    let iat = claims["iat"].as_u64().ok_or(JwtError::Malformed)?;
+    if iat.abs_diff(now) > max_age {
+        return Err(JwtError::Expired);
+    }
+    if let Some(exp) = claims.get("exp") {
+        if exp.as_u64().ok_or(JwtError::Malformed)? <= now {
+            return Err(JwtError::Expired);
+        }
+    }
+    Ok(())
+}
+
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn jwt(secret: &[u8], iat: u64) -> String {
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let h = b64.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+        let p = b64.encode(format!(r#"{{"iat":{iat}}}"#));
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(format!("{h}.{p}").as_bytes());
+        let s = b64.encode(mac.finalize().into_bytes());
+        format!("{h}.{p}.{s}")
+    }
+
+    #[test]
+    fn jwt_and_rate_limits() {
+        let secret = [7u8; 32];
+        let now = 1_700_000_000;
+        assert_eq!(
+            verify_jwt(&secret, &jwt(&secret, now - 10), now, 60),
+            Ok(())
+        );
+        assert_eq!(
+            verify_jwt(&secret, &jwt(&secret, now - 61), now, 60),
+            Err(JwtError::Expired)
+        );
+        assert_eq!(
+            verify_jwt(&[8u8; 32], &jwt(&secret, now), now, 60),
+            Err(JwtError::Signature)
+        );
+        assert_eq!(
+            verify_jwt(&secret, "a.b", now, 60),
+            Err(JwtError::Malformed)
+        );
+
+        let limiter = RateLimiter::new(2, 2);
+        let t0 = Instant::now();
+        assert!(limiter.check(1u8, t0).is_ok());
+        assert!(limiter.check(1u8, t0).is_ok());
+        let wait = limiter.check(1u8, t0).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+        assert!(limiter.check(2u8, t0).is_ok());
+        assert!(limiter.check(1u8, t0 + wait).is_ok());
+
+        // 桶数量有上限：新来源挤出最久未使用的桶
+        let limiter = RateLimiter::new(1, 1);
+        for k in 0..MAX_BUCKETS as u32 + 10 {
+            assert!(limiter
+                .check(k, t0 + Duration::from_millis(k as u64 / 1000))
+                .is_ok());
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_BUCKETS);
+
+        let filter = MethodFilter {
+            allow: vec!["ark_*".into(), "admin_peers".into()],
+            deny: vec!["ark_send*".into()],
+        };
+        assert!(filter.permits("ark_chainId"));
+        assert!(!filter.permits("ark_sendRawTransaction"));
+        assert!(filter.permits("admin_peers"));
+        assert!(!filter.permits("admin_addPeer"));
+    }
+}
@@ -0,0 +1,234 @@
+//! `admin_*` 运维方法：节点表、同步进度、运行时日志过滤、触发快照、导出交易池。
+//!
+//! 只有携带 admin 凭据的调用方可用（见 `access`），公开端点应通过方法过滤整体屏蔽。
+use crate::error::RpcError;
+use crate::methods::{Methods, Params};
+use ark_p2p::PeerInfo;
+use ark_types::{Address, SignedTransaction, H256};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+/// 节点向 admin 方法暴露的运维接口。
+pub trait AdminBackend: Send + Sync + 'static {
+    /// 已知节点，按评分从高到低
+    fn peers(&self) -> Vec<PeerInfo>;
+    /// 添加节点；已存在返回 false，地址不合法返回错误原因。
+    fn add_peer(&self, addr: &str) -> Result<bool, String>;
+    fn remove_peer(&self, addr: &str) -> bool;
+    fn sync_status(&self) -> SyncStatus;
+    /// 当前日志过滤指令（EnvFilter 语法）
+    fn log_filter(&self) -> String;
+    /// 替换日志过滤指令；语法错误时返回原因且不生效。
+    fn set_log_filter(&self, directives: &str) -> Result<(), String>;
+    /// 以最新区块生成状态快照并写入快照目录。
+    fn snapshot(&self) -> anyhow::Result<SnapshotInfo>;
+    /// 交易池全部交易（按发送方、nonce 排序）
+    fn mempool(&self) -> Vec<PoolEntry>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SyncStatus {
+    pub syncing: bool,
+    pub current_height: u64,
+    /// 本地与节点上报高度中的最大值
+    pub highest_height: u64,
+    /// 在线节点数
+    pub peers: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SnapshotInfo {
+    pub height: u64,
+    pub block_hash: H256,
+    pub state_root: H256,
+    pub entries: u64,
+    pub path: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PoolEntry {
+    pub hash: H256,
+    pub sender: Address,
+    pub nonce: u64,
+    pub size: usize,
+    pub received_ms: u64,
+    pub transaction: SignedTransaction,
+}
+
+/// 注册 admin 方法。
+pub fn register_admin_api(methods: &mut Methods, backend: Arc<dyn AdminBackend>) {
+    let b = backend.clone();
+    methods.register("admin_peers", move |_| Ok(json!(b.peers())));
+
+    let b = backend.clone();
+    methods.register("admin_addPeer", move |p: Params| {
+        let addr: String = p.required(0, "addr")?;
+        b.add_peer(&addr)
+            .map(|added| json!(added))
+            .map_err(RpcError::invalid_params)
+    });
+
+    let b = backend.clone();
+    methods.register("admin_removePeer", move |p: Params| {
+        let addr: String = p.required(0, "addr")?;
+        Ok(json!(b.remove_peer(&addr)))
+    });
+
+    let b = backend.clone();
+    methods.register("admin_syncStatus", move |_| Ok(json!(b.sync_status())));
+
+    let b = backend.clone();
+    methods.register("admin_logFilter", move |_| Ok(json!(b.log_filter())));
+
+    let b = backend.clone();
+    methods.register("admin_setLogFilter", move |p: Params| {
+        let directives: String = p.required(0, "filter")?;
+        let previous = b.log_filter();
+        b.set_log_filter(&directives)
+            .map_err(RpcError::invalid_params)?;
+        tracing::info!(%previous, current = %directives, "log filter changed via admin rpc");
+        Ok(json!({ "previous": previous, "current": directives }))
+    });
+
+    let b = backend.clone();
+    methods.register("admin_snapshot", move |_| {
+        let info = b.snapshot().map_err(RpcError::internal)?;
+        tracing::info!(height = info.height, path = %info.path, "snapshot written via admin rpc");
+        Ok(json!(info))
+    });
+
+    let b = backend;
+    methods.register("admin_mempool", move |p: Params| {
+        let limit: Option<usize> = p.optional(0, "limit")?;
+        let txs = b.mempool();
+        let count = txs.len();
+        let bytes: usize = txs.iter().map(|t| t.size).sum();
+        let txs: Vec<PoolEntry> = txs.into_iter().take(limit.unwrap_or(usize::MAX)).collect();
+        Ok(json!({ "count": count, "bytes": bytes, "transactions": txs }))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc::handle_request;
+    use ark_p2p::{PeerBook, PeerSource};
+    use serde_json::Value;
+    use std::sync::Mutex;
+
+    struct MockAdmin {
+        peers: Mutex<PeerBook>,
+        filter: Mutex<String>,
+    }
+
+    impl AdminBackend for MockAdmin {
+        fn peers(&self) -> Vec<PeerInfo> {
+            self.peers.lock().unwrap().list()
+        }
+        fn add_peer(&self, addr: &str) -> Result<bool, String> {
+            let mut peers = self.peers.lock().unwrap();
+            peers
+                .add(addr, PeerSource::Admin)
+                .map_err(|e| e.to_string())
+        }
+        fn remove_peer(&self, addr: &str) -> bool {
+            self.peers.lock().unwrap().remove(addr)
+        }
+        fn sync_status(&self) -> SyncStatus {
+            let peers = self.peers.lock().unwrap();
+            SyncStatus {
+                syncing: false,
+                current_height: 3,
+                highest_height: peers.best_height().unwrap_or(3).max(3),
+                peers: peers.len(),
+            }
+        }
+        fn log_filter(&self) -> String {
+            self.filter.lock().unwrap().clone()
+        }
+        fn set_log_filter(&self, directives: &str) -> Result<(), String> {
+            if directives.contains(' ') {
+                return Err("invalid filter".into());
+            }
+            *self.filter.lock().unwrap() = directives.into();
+            Ok(())
+        }
+        fn snapshot(&self) -> anyhow::Result<SnapshotInfo> {
+            anyhow::bail!("disk full")
+        }
+        fn mempool(&self) -> Vec<PoolEntry> {
+            (0..3)
+                .map(|nonce| PoolEntry {
+                    hash: H256([nonce as u8; 32]),
+                    sender: Address([1; 20]),
+                    nonce,
+                    size: 100,
+                    received_ms: 0,
+                    transaction: SignedTransaction {
+                        tx: ark_types::Transaction {
+                            chain_id: "ark-test".into(),
+                            nonce,
+                            action: ark_types::Action::Transfer { to: Address::ZERO },
+                            value: 1,
+                            gas_limit: 21_000,
+                            max_fee_per_gas: 10,
+                            max_priority_fee_per_gas: 1,
+                        },
+                        pubkey: vec![2; 33],
+                        signature: vec![1; 64],
+                    },
+                })
+                .collect()
+        }
+    }
+
+    fn call(m: &Methods, method: &str, params: Value) -> Value {
+        let req = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        handle_request(m, req).unwrap()
+    }
+
+    #[test]
+    fn admin_methods_manage_peers_log_filter_and_mempool() {
+        let mut m = Methods::new();
+        register_admin_api(
+            &mut m,
+            Arc::new(MockAdmin {
+                peers: Mutex::new(PeerBook::new()),
+                filter: Mutex::new("info".into()),
+            }),
+        );
+        let peer = "/ip4/10.0.0.2/udp/30333/quic-v1";
+        assert_eq!(call(&m, "admin_addPeer", json!([peer]))["result"], true);
+        assert_eq!(call(&m, "admin_addPeer", json!([peer]))["result"], false);
+        assert_eq!(
+            call(&m, "admin_addPeer", json!(["nonsense"]))["error"]["code"],
+            crate::error::INVALID_PARAMS
+        );
+        let peers = call(&m, "admin_peers", json!([]));
+        assert_eq!(peers["result"][0]["addr"], peer);
+        assert_eq!(peers["result"][0]["score"], 0);
+        assert_eq!(
+            call(&m, "admin_syncStatus", json!([]))["result"]["peers"],
+            1
+        );
+        assert_eq!(call(&m, "admin_removePeer", json!([peer]))["result"], true);
+
+        let changed = call(&m, "admin_setLogFilter", json!(["debug,ark_rpc=trace"]));
+        assert_eq!(changed["result"]["previous"], "info");
+        assert_eq!(
+            call(&m, "admin_logFilter", json!([]))["result"],
+            "debug,ark_rpc=trace"
+        );
+        assert!(call(&m, "admin_setLogFilter", json!(["a b"]))["error"].is_object());
+
+        let pool = call(&m, "admin_mempool", json!([2]));
+        assert_eq!(pool["result"]["count"], 3);
+        assert_eq!(pool["result"]["bytes"], 300);
+        assert_eq!(pool["result"]["transactions"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            call(&m, "admin_snapshot", json!([]))["error"]["code"],
+            crate::error::INTERNAL_ERROR
+        );
+    }
+}
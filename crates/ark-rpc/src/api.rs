@@ -0,0 +1,607 @@
+//! `ark_*` 链查询、交易提交与模拟执行方法。
+//!
+//! 金额一律为十进制字符串，哈希为 `0x` 十六进制，地址为 Base58Check。
+//! 查询不到的区块 / 交易 / 回执返回 null。
+//! `ark_getLogs` 一次最多跨 MAX_LOG_BLOCK_RANGE 个区块、返回 MAX_LOG_RESULTS 条，超出返回 LIMIT_EXCEEDED。
+use crate::error::{RpcError, EXECUTION_REVERTED, LIMIT_EXCEEDED, STATE_UNAVAILABLE, TX_REJECTED};
+use crate::methods::{Methods, Params};
+use ark_storage::TxLocation;
+use ark_types::proof::StateProof;
+use ark_types::{
+    Address, Amount, Block, BlockHeader, CallRequest, CallResult, Gas, Log, LogFilter, Receipt,
+    SignedTransaction, H256,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// 单次日志查询最多跨越的区块数
+pub const MAX_LOG_BLOCK_RANGE: u64 = 10_000;
+/// 单次日志查询最多返回的日志条数
+pub const MAX_LOG_RESULTS: usize = 10_000;
+
+/// 节点向 RPC 暴露的链数据接口。
+pub trait ChainBackend: Send + Sync + 'static {
+    fn chain_id(&self) -> String;
+    /// 最新区块高度
+    fn head(&self) -> u64;
+    fn block(&self, height: u64) -> anyhow::Result<Option<Block>>;
+    fn block_by_hash(&self, hash: &H256) -> anyhow::Result<Option<Block>>;
+    fn transaction(&self, hash: &H256) -> anyhow::Result<Option<(SignedTransaction, TxLocation)>>;
+    /// 交易池中尚未上链的交易
+    fn pending_transaction(&self, hash: &H256) -> Option<SignedTransaction>;
+    fn receipt(&self, hash: &H256) -> anyhow::Result<Option<(Receipt, TxLocation)>>;
+    /// 区块内全部回执（按交易顺序）
+    fn block_receipts(&self, height: u64) -> anyhow::Result<Option<Vec<Receipt>>>;
+    /// 在给定状态根上为状态键生成证明。只支持最新状态：其他（历史）状态根返回 None。
+    fn state_proof(&self, state_root: &H256, key: &[u8]) -> Option<StateProof>;
+    fn balance(&self, address: &Address) -> Amount;
+    /// pending 为 true 时计入交易池中排队的交易
+    fn nonce(&self, address: &Address, pending: bool) -> u64;
+    /// 最新状态下的合约代码；EVM 合约为去掉存储标记后的运行时代码。
+    fn code(&self, address: &Address) -> Option<Vec<u8>>;
+    /// 最新状态下的存储槽
+    fn storage(&self, address: &Address, slot: &H256) -> H256;
+    /// [from, to] 区块内匹配 filter 的日志，最多 limit 条。
+    fn logs(
+        &self,
+        filter: &LogFilter,
+        from: u64,
+        to: u64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<LogView>>;
+    /// 提交交易到交易池；拒绝原因以字符串返回。
+    fn submit_transaction(&self, stx: SignedTransaction) -> Result<H256, String>;
+    /// 在 height 区块之后的状态副本上模拟执行，不影响链状态；节点未保留该高度的状态时返回 StateUnavailable。
+    fn simulate(&self, call: &CallRequest, height: u64) -> Result<CallResult, CallError>;
+    /// 使交易成功所需的最小 gas_limit。
+    fn estimate_gas(&self, call: &CallRequest, height: u64) -> Result<Gas, CallError>;
+}
+
+/// 模拟执行 / gas 估算失败。
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CallError {
+    #[error("state at block {0} is not available")]
+    StateUnavailable(u64),
+    /// 交易无法上链（nonce、余额、费用等校验失败）
+    #[error("{0}")]
+    Rejected(String),
+    #[error("execution reverted: {0}")]
+    Reverted(String),
+}
+
+impl From<CallError> for RpcError {
+    fn from(e: CallError) -> Self {
+        match e {
+            CallError::StateUnavailable(_) => RpcError::new(STATE_UNAVAILABLE, e.to_string()),
+            CallError::Rejected(reason) => RpcError::new(TX_REJECTED, reason),
+            CallError::Reverted(ref reason) => RpcError::new(EXECUTION_REVERTED, e.to_string())
+                .with_data(json!({ "reason": reason })),
+        }
+    }
+}
+
+/// 区块标识：`"latest"`、`"earliest"`、十进制数字或 `0x` 十六进制高度。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockTag {
+    Latest,
+    Earliest,
+    Number(u64),
+}
+
+impl BlockTag {
+    pub fn resolve(self, head: u64) -> u64 {
+        match self {
+            BlockTag::Latest => head,
+            BlockTag::Earliest => 0,
+            BlockTag::Number(n) => n,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockTag {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Num(u64),
+            Str(String),
+        }
+        match Raw::deserialize(d)? {
+            Raw::Num(n) => Ok(BlockTag::Number(n)),
+            Raw::Str(s) => match s.as_str() {
+                "latest" => Ok(BlockTag::Latest),
+                "earliest" => Ok(BlockTag::Earliest),
+                _ => match s.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => s.parse(),
+                }
+                .map(BlockTag::Number)
+                .map_err(|_| serde::de::Error::custom(format!("invalid block tag `{s}`"))),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockView {
+    pub hash: H256,
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    /// 交易哈希，或 full=true 时为完整交易
+    pub transactions: Vec<Value>,
+}
+
+impl BlockView {
+    pub fn new(block: Block, full: bool) -> Self {
+        let hash = block.hash();
+        let height = block.height();
+        let transactions = block
+            .txs
+            .into_iter()
+            .enumerate()
+            .map(|(i, stx)| {
+                if full {
+                    let loc = TxLocation {
+                        height,
+                        index: i as u32,
+                    };
+                    json!(TxView::new(stx, Some(loc)))
+                } else {
+                    json!(stx.hash())
+                }
+            })
+            .collect();
+        Self {
+            hash,
+            header: block.header,
+            transactions,
+        }
+    }
+}
+
+/// 区块头（附哈希），用于 newHeads 订阅。
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct HeaderView {
+    pub hash: H256,
+    #[serde(flatten)]
+    pub header: BlockHeader,
+}
+
+impl HeaderView {
+    pub fn new(header: BlockHeader) -> Self {
+        Self {
+            hash: header.hash(),
+            header,
+        }
+    }
+}
+
+/// 带位置信息的日志。
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LogView {
+    #[serde(flatten)]
+    pub log: Log,
+    pub block_height: u64,
+    pub tx_hash: H256,
+    pub tx_index: u32,
+    /// 区块内序号
+    pub log_index: u32,
+}
+
+/// `ark_getLogs` 参数：区块范围缺省为最新区块。
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct LogQuery {
+    #[serde(default)]
+    pub from_block: Option<BlockTag>,
+    #[serde(default)]
+    pub to_block: Option<BlockTag>,
+    #[serde(flatten)]
+    pub filter: LogFilter,
+}
+
+/// 解析区块范围并按范围与条数上限查询日志。
+pub(crate) fn query_logs(
+    b: &dyn ChainBackend,
+    filter: &LogFilter,
+    from: Option<BlockTag>,
+    to: Option<BlockTag>,
+) -> Result<Vec<LogView>, RpcError> {
+    let head = b.head();
+    let from = from.unwrap_or(BlockTag::Latest).resolve(head);
+    let to = to.unwrap_or(BlockTag::Latest).resolve(head);
+    if from > to {
+        return Ok(Vec::new());
+    }
+    if to - from >= MAX_LOG_BLOCK_RANGE {
+        return Err(RpcError::new(
+            LIMIT_EXCEEDED,
+            format!("block range {from}..={to} exceeds limit of {MAX_LOG_BLOCK_RANGE} blocks"),
+        ));
+    }
+    let logs = b
+        .logs(filter, from, to, MAX_LOG_RESULTS + 1)
+        .map_err(RpcError::internal)?;
+    if logs.len() > MAX_LOG_RESULTS {
+        return Err(RpcError::new(
+            LIMIT_EXCEEDED,
+            format!("query returned more than {MAX_LOG_RESULTS} logs; narrow the block range"),
+        ));
+    }
+    Ok(logs)
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TxView {
+    pub hash: H256,
+    pub from: Address,
+    /// 尚在交易池中时为 null
+    pub block_height: Option<u64>,
+    pub index: Option<u32>,
+    #[serde(flatten)]
+    pub stx: SignedTransaction,
+}
+
+impl TxView {
+    pub fn new(stx: SignedTransaction, loc: Option<TxLocation>) -> Self {
+        Self {
+            hash: stx.hash(),
+            from: stx.sender(),
+            block_height: loc.map(|l| l.height),
+            index: loc.map(|l| l.index),
+            stx,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReceiptView {
+    #[serde(flatten)]
+    pub receipt: Receipt,
+    pub block_height: u64,
+    pub index: u32,
+}
+
+/// 注册链查询与交易提交方法。
+pub fn register_chain_api(methods: &mut Methods, backend: Arc<dyn ChainBackend>) {
+    let b = backend.clone();
+    methods.register("ark_chainId", move |_| Ok(json!(b.chain_id())));
+
+    let b = backend.clone();
+    methods.register("ark_blockNumber", move |_| Ok(json!(b.head())));
+
+    let b = backend.clone();
+    methods.register("ark_getBlockByNumber", move |p: Params| {
+        let tag: BlockTag = p.required(0, "block")?;
+        let full = p.optional(1, "full")?.unwrap_or(false);
+        let block = b.block(tag.resolve(b.head())).map_err(RpcError::internal)?;
+        Ok(json!(block.map(|blk| BlockView::new(blk, full))))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getBlockByHash", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        let full = p.optional(1, "full")?.unwrap_or(false);
+        let block = b.block_by_hash(&hash).map_err(RpcError::internal)?;
+        Ok(json!(block.map(|blk| BlockView::new(blk, full))))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getTransactionByHash", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        if let Some((stx, loc)) = b.transaction(&hash).map_err(RpcError::internal)? {
+            return Ok(json!(TxView::new(stx, Some(loc))));
+        }
+        Ok(json!(b
+            .pending_transaction(&hash)
+            .map(|stx| TxView::new(stx, None))))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getTransactionReceipt", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        let receipt = b.receipt(&hash).map_err(RpcError::internal)?;
+        Ok(json!(receipt.map(|(receipt, loc)| ReceiptView {
+            receipt,
+            block_height: loc.height,
+            index: loc.index,
+        })))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getBalance", move |p: Params| {
+        let address: Address = p.required(0, "address")?;
+        Ok(json!(b.balance(&address).to_string()))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getTransactionCount", move |p: Params| {
+        let address: Address = p.required(0, "address")?;
+        let pending = match p.optional::<String>(1, "tag")?.as_deref() {
+            None | Some("latest") => false,
+            Some("pending") => true,
+            Some(other) => {
+                return Err(RpcError::invalid_params(format!(
+                    "invalid tag `{other}`: expected latest or pending"
+                )))
+            }
+        };
+        Ok(json!(b.nonce(&address, pending)))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_simulate", move |p: Params| {
+        let call: CallRequest = p.required(0, "call")?;
+        let tag = p.optional(1, "block")?.unwrap_or(BlockTag::Latest);
+        Ok(json!(b.simulate(&call, tag.resolve(b.head()))?))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_estimateGas", move |p: Params| {
+        let call: CallRequest = p.required(0, "call")?;
+        let tag = p.optional(1, "block")?.unwrap_or(BlockTag::Latest);
+        Ok(json!(b.estimate_gas(&call, tag.resolve(b.head()))?))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getLogs", move |p: Params| {
+        let query: LogQuery = p.required(0, "filter")?;
+        Ok(json!(query_logs(
+            &*b,
+            &query.filter,
+            query.from_block,
+            query.to_block
+        )?))
+    });
+
+    let b = backend;
+    methods.register("ark_sendRawTransaction", move |p: Params| {
+        let raw: String = p.required(0, "raw")?;
+        let bytes = hex::decode(raw.strip_prefix("0x").unwrap_or(&raw))
+            .map_err(|e| RpcError::invalid_params(format!("invalid hex: {e}")))?;
+        let stx: SignedTransaction = serde_json::from_slice(&bytes)
+            .map_err(|e| RpcError::invalid_params(format!("invalid transaction: {e}")))?;
+        b.submit_transaction(stx)
+            .map(|hash| json!(hash))
+            .map_err(|reason| RpcError::new(TX_REJECTED, reason))
+    });
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::jsonrpc::handle_request;
+    use std::sync::Mutex;
+
+    /// 测试用后端：一条只含创世块的链，提交的交易记录在内存中。
+    #[derive(Default)]
+    pub(crate) struct MockBackend {
+        pub submitted: Mutex<Vec<SignedTransaction>>,
+    }
+
+    impl ChainBackend for MockBackend {
+        fn chain_id(&self) -> String {
+            "ark-test".into()
+        }
+        fn head(&self) -> u64 {
+            0
+        }
+        fn block(&self, height: u64) -> anyhow::Result<Option<Block>> {
+            Ok((height == 0).then(Block::default))
+        }
+        fn block_by_hash(&self, hash: &H256) -> anyhow::Result<Option<Block>> {
+            Ok((*hash == Block::default().hash()).then(Block::default))
+        }
+        fn transaction(&self, _: &H256) -> anyhow::Result<Option<(SignedTransaction, TxLocation)>> {
+            Ok(None)
+        }
+        fn pending_transaction(&self, hash: &H256) -> Option<SignedTransaction> {
+            let txs = self.submitted.lock().unwrap();
+            txs.iter().find(|t| t.hash() == *hash).cloned()
+        }
+        fn receipt(&self, _: &H256) -> anyhow::Result<Option<(Receipt, TxLocation)>> {
+            Ok(None)
+        }
+        fn block_receipts(&self, height: u64) -> anyhow::Result<Option<Vec<Receipt>>> {
+            Ok((height == 0).then(Vec::new))
+        }
+        fn state_proof(&self, state_root: &H256, key: &[u8]) -> Option<StateProof> {
+            // 空状态
+            state_root
+                .is_zero()
+                .then(|| ark_types::proof::prove_state(&[], key))
+        }
+        fn balance(&self, _: &Address) -> Amount {
+            42
+        }
+        fn nonce(&self, _: &Address, pending: bool) -> u64 {
+            if pending {
+                self.submitted.lock().unwrap().len() as u64
+            } else {
+                0
+            }
+        }
+        fn code(&self, _: &Address) -> Option<Vec<u8>> {
+            None
+        }
+        fn storage(&self, _: &Address, _: &H256) -> H256 {
+            H256::ZERO
+        }
+        fn logs(
+            &self,
+            filter: &LogFilter,
+            from: u64,
+            to: u64,
+            limit: usize,
+        ) -> anyhow::Result<Vec<LogView>> {
+            // 每个区块一条来自零地址的日志
+            Ok((from..=to)
+                .map(|height| LogView {
+                    log: Log {
+                        address: Address::ZERO,
+                        topics: vec![H256::from_u128(height as u128)],
+                        data: Vec::new(),
+                    },
+                    block_height: height,
+                    tx_hash: H256::ZERO,
+                    tx_index: 0,
+                    log_index: 0,
+                })
+                .filter(|v| filter.matches(&v.log))
+                .take(limit)
+                .collect())
+        }
+        fn submit_transaction(&self, stx: SignedTransaction) -> Result<H256, String> {
+            if stx.signature.is_empty() {
+                return Err("invalid signature".into());
+            }
+            let hash = stx.hash();
+            self.submitted.lock().unwrap().push(stx);
+            Ok(hash)
+        }
+        fn simulate(&self, call: &CallRequest, height: u64) -> Result<CallResult, CallError> {
+            if height != 0 {
+                return Err(CallError::StateUnavailable(height));
+            }
+            if call.value > 42 {
+                return Err(CallError::Rejected("insufficient funds".into()));
+            }
+            Ok(CallResult {
+                status: ark_types::ExecStatus::Success,
+                gas_used: 21_000,
+                gas_limit: call.gas_limit.unwrap_or(21_000),
+                output: Vec::new(),
+                logs: Vec::new(),
+                contract_address: None,
+                revert_reason: None,
+            })
+        }
+        fn estimate_gas(&self, call: &CallRequest, height: u64) -> Result<Gas, CallError> {
+            if matches!(call.action, ark_types::Action::Call { .. }) {
+                return Err(CallError::Reverted("revert code 1".into()));
+            }
+            self.simulate(call, height).map(|r| r.gas_used)
+        }
+    }
+
+    pub(crate) fn methods() -> Methods {
+        let mut m = Methods::new();
+        register_chain_api(&mut m, Arc::new(MockBackend::default()));
+        m
+    }
+
+    fn call(m: &Methods, method: &str, params: Value) -> Value {
+        let req = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        handle_request(m, req).unwrap()
+    }
+
+    fn raw_tx(signature: Vec<u8>) -> String {
+        let stx = SignedTransaction {
+            tx: ark_types::Transaction {
+                chain_id: "ark-test".into(),
+                nonce: 0,
+                action: ark_types::Action::Transfer { to: Address::ZERO },
+                value: 1,
+                gas_limit: 21_000,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 1,
+            },
+            pubkey: vec![2; 33],
+            signature,
+        };
+        format!("0x{}", hex::encode(serde_json::to_vec(&stx).unwrap()))
+    }
+
+    #[test]
+    fn chain_queries() {
+        let m = methods();
+        assert_eq!(call(&m, "ark_chainId", json!([]))["result"], "ark-test");
+        let genesis = call(&m, "ark_getBlockByNumber", json!(["earliest"]));
+        assert_eq!(genesis["result"]["height"], 0);
+        assert_eq!(
+            genesis["result"]["hash"],
+            json!(Block::default().hash().to_string())
+        );
+        assert_eq!(
+            call(&m, "ark_getBlockByNumber", json!(["0x5"]))["result"],
+            Value::Null
+        );
+        assert_eq!(
+            call(&m, "ark_getBlockByNumber", json!(["soon"]))["error"]["code"],
+            -32602
+        );
+        let addr = Address::ZERO.to_string();
+        assert_eq!(call(&m, "ark_getBalance", json!([addr]))["result"], "42");
+    }
+
+    #[test]
+    fn send_raw_transaction_reports_rejection() {
+        let m = methods();
+        let ok = call(&m, "ark_sendRawTransaction", json!([raw_tx(vec![1; 64])]));
+        let hash = ok["result"].as_str().unwrap().to_string();
+        let tx = call(&m, "ark_getTransactionByHash", json!([hash]));
+        assert_eq!(tx["result"]["block_height"], Value::Null);
+        let addr = Address::ZERO.to_string();
+        assert_eq!(
+            call(&m, "ark_getTransactionCount", json!([addr, "pending"]))["result"],
+            1
+        );
+
+        let bad = call(&m, "ark_sendRawTransaction", json!([raw_tx(Vec::new())]));
+        assert_eq!(bad["error"]["code"], TX_REJECTED);
+        assert_eq!(bad["error"]["message"], "invalid signature");
+        let bad = call(&m, "ark_sendRawTransaction", json!(["0xzz"]));
+        assert_eq!(bad["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn simulate_and_estimate_gas() {
+        let m = methods();
+        let req = |value: &str, action: Value| json!({"from": Address::ZERO, "action": action, "value": value});
+        let transfer = json!({"type": "transfer", "to": Address::ZERO});
+        let ok = call(&m, "ark_simulate", json!([req("1", transfer.clone())]));
+        assert_eq!(ok["result"]["status"], "success");
+        assert_eq!(ok["result"]["gas_used"], 21_000);
+        let est = call(
+            &m,
+            "ark_estimateGas",
+            json!([req("1", transfer.clone()), "latest"]),
+        );
+        assert_eq!(est["result"], 21_000);
+
+        let poor = call(&m, "ark_simulate", json!([req("100", transfer.clone())]));
+        assert_eq!(poor["error"]["code"], TX_REJECTED);
+        let old = call(&m, "ark_simulate", json!([req("1", transfer), "0x1"]));
+        assert_eq!(old["error"]["code"], STATE_UNAVAILABLE);
+        let contract = json!({"type": "call", "to": Address::ZERO, "input": "0x"});
+        let reverted = call(&m, "ark_estimateGas", json!([req("0", contract)]));
+        assert_eq!(reverted["error"]["code"], EXECUTION_REVERTED);
+        assert_eq!(reverted["error"]["data"]["reason"], "revert code 1");
+    }
+
+    #[test]
+    fn get_logs_filters_and_enforces_range_limit() {
+        let m = methods();
+        let topic = H256::from_u128(3).to_string();
+        let logs = call(
+            &m,
+            "ark_getLogs",
+            json!([{"from_block": 0, "to_block": "0x9", "topics": [[topic]]}]),
+        );
+        let logs = logs["result"].as_array().unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0]["block_height"], 3);
+        assert_eq!(logs[0]["topics"][0], topic);
+
+        let latest = call(&m, "ark_getLogs", json!([{}]));
+        assert_eq!(latest["result"].as_array().unwrap().len(), 1);
+        let empty = call(&m, "ark_getLogs", json!([{"from_block": 5, "to_block": 1}]));
+        assert_eq!(empty["result"], json!([]));
+        let wide = call(
+            &m,
+            "ark_getLogs",
+            json!([{"from_block": 0, "to_block": MAX_LOG_BLOCK_RANGE}]),
+        );
+        assert_eq!(wide["error"]["code"], LIMIT_EXCEEDED);
+    }
+}
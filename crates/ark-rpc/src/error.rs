@@ -0,0 +1,64 @@
+//! JSON-RPC 错误对象与错误码。
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+// -32000 ~ -32099 为服务端自定义
+/// 交易被交易池拒绝
+pub const TX_REJECTED: i64 = -32000;
+/// 请求的历史状态未保留（只能对最新状态生成证明）
+pub const STATE_UNAVAILABLE: i64 = -32001;
+/// 估算 gas 时交易在上限内仍执行失败
+pub const EXECUTION_REVERTED: i64 = -32003;
+/// 超出批量条数 / 范围等限制
+pub const LIMIT_EXCEEDED: i64 = -32005;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("rpc error {code}: {message}")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn parse_error() -> Self {
+        Self::new(PARSE_ERROR, "parse error")
+    }
+
+    pub fn invalid_request(reason: impl Into<String>) -> Self {
+        Self::new(INVALID_REQUEST, reason)
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("method not found: {method}"))
+    }
+
+    pub fn invalid_params(reason: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, reason)
+    }
+
+    /// 内部错误不向客户端透出细节，只记录日志。
+    pub fn internal(err: impl std::fmt::Display) -> Self {
+        tracing::error!(error = %err, "rpc internal error");
+        Self::new(INTERNAL_ERROR, "internal error")
+    }
+}
@@ -0,0 +1,589 @@
+//! `eth_*` 兼容方法，供以太坊钱包与工具访问 EVM 合约。
+//!
+//! - 数值为 `0x` 十六进制 quantity，地址为 `0x` 十六进制（与原生地址同一组 20 字节）
+//! - 链 ID 由链 ID 字符串派生，见 `ark_types::eth::eth_chain_id`
+//! - 账户查询只支持最新区块，其余高度返回 STATE_UNAVAILABLE；`pending` 视同 `latest`
+//! - `eth_call` / `eth_estimateGas` 可指定节点保留的最近区块
+//! - `eth_getLogs` 与 `ark_getLogs` 共用区块范围与条数上限
+//! - `eth_sendRawTransaction` 接受签名的 EIP-1559 交易，发送方由签名恢复并按以太坊规则派生地址；
+//!   交易哈希为其编码的 keccak256，可用 `eth_getTransactionByHash` / `eth_getTransactionReceipt` 查询
+//!   （原生交易也可查询，均以类型 0x2 呈现）
+use crate::api::{query_logs, BlockTag, ChainBackend, LogView};
+use crate::error::{RpcError, EXECUTION_REVERTED, STATE_UNAVAILABLE, TX_REJECTED};
+use crate::methods::{Methods, Params};
+use ark_types::eth::{
+    address_from_hex, address_to_hex, eth_chain_id, keccak256, Eip1559Transaction,
+};
+use ark_types::log::OneOrMany;
+use ark_types::{
+    Action, Address, Block, CallRequest, ExecStatus, LogFilter, Receipt, SignedTransaction, H256,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// `eth_call` / `eth_estimateGas` 的调用对象；缺少 `to` 时按 EVM 合约创建处理。
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EthCall {
+    from: Option<String>,
+    to: Option<String>,
+    gas: Option<String>,
+    gas_price: Option<String>,
+    value: Option<String>,
+    #[serde(alias = "input")]
+    data: Option<String>,
+}
+
+impl EthCall {
+    fn into_request(self) -> Result<CallRequest, RpcError> {
+        let address = |s: &str| address_from_hex(s).map_err(RpcError::invalid_params);
+        let data = match self.data.as_deref() {
+            Some(s) => hex::decode(s.strip_prefix("0x").unwrap_or(s))
+                .map_err(|e| RpcError::invalid_params(format!("invalid data: {e}")))?,
+            None => Vec::new(),
+        };
+        let action = match self.to.as_deref() {
+            Some(to) => Action::Call {
+                to: address(to)?,
+                input: data,
+            },
+            None => Action::EvmCreate { code: data },
+        };
+        Ok(CallRequest {
+            from: self
+                .from
+                .as_deref()
+                .map(address)
+                .transpose()?
+                .unwrap_or(Address::ZERO),
+            action,
+            value: self
+                .value
+                .as_deref()
+                .map(quantity)
+                .transpose()?
+                .unwrap_or(0),
+            gas_limit: self
+                .gas
+                .as_deref()
+                .map(quantity)
+                .transpose()?
+                .map(|g| g.min(u64::MAX as u128) as u64),
+            max_fee_per_gas: self.gas_price.as_deref().map(quantity).transpose()?,
+            max_priority_fee_per_gas: None,
+        })
+    }
+}
+
+/// `eth_getLogs` 的过滤对象。
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EthLogFilter {
+    from_block: Option<Value>,
+    to_block: Option<Value>,
+    address: Option<OneOrMany<String>>,
+    #[serde(default)]
+    topics: Vec<Option<OneOrMany<H256>>>,
+}
+
+impl EthLogFilter {
+    fn to_filter(&self) -> Result<LogFilter, RpcError> {
+        let parse = |s: &String| address_from_hex(s).map_err(RpcError::invalid_params);
+        let address = match &self.address {
+            None => None,
+            Some(OneOrMany::One(a)) => Some(OneOrMany::One(parse(a)?)),
+            Some(OneOrMany::Many(list)) => Some(OneOrMany::Many(
+                list.iter().map(parse).collect::<Result<_, _>>()?,
+            )),
+        };
+        Ok(LogFilter {
+            address,
+            topics: self.topics.clone(),
+        })
+    }
+}
+
+fn quantity(s: &str) -> Result<u128, RpcError> {
+    s.strip_prefix("0x")
+        .and_then(|hex| u128::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| RpcError::invalid_params(format!("invalid quantity `{s}`")))
+}
+
+fn hex_quantity(n: impl std::fmt::LowerHex) -> Value {
+    json!(format!("{n:#x}"))
+}
+
+fn hex_data(bytes: &[u8]) -> Value {
+    json!(format!("0x{}", hex::encode(bytes)))
+}
+
+fn address_param(p: &Params, index: usize) -> Result<Address, RpcError> {
+    let s: String = p.required(index, "address")?;
+    address_from_hex(&s).map_err(RpcError::invalid_params)
+}
+
+/// 区块标识；以太坊工具常传的 `pending` / `safe` / `finalized` 均按最新区块处理。
+fn block_tag(v: Option<Value>) -> Result<BlockTag, RpcError> {
+    match v {
+        None | Some(Value::Null) => Ok(BlockTag::Latest),
+        Some(Value::String(s)) if matches!(s.as_str(), "pending" | "safe" | "finalized") => {
+            Ok(BlockTag::Latest)
+        }
+        Some(v) => serde_json::from_value(v)
+            .map_err(|e| RpcError::invalid_params(format!("invalid `block`: {e}"))),
+    }
+}
+
+fn block_param(b: &dyn ChainBackend, p: &Params, index: usize) -> Result<u64, RpcError> {
+    Ok(block_tag(p.optional(index, "block")?)?.resolve(b.head()))
+}
+
+/// 账户状态只有最新一份。
+fn latest_state(b: &dyn ChainBackend, p: &Params, index: usize) -> Result<(), RpcError> {
+    let height = block_param(b, p, index)?;
+    if height != b.head() {
+        return Err(RpcError::new(
+            STATE_UNAVAILABLE,
+            format!("state at block {height} is not available"),
+        ));
+    }
+    Ok(())
+}
+
+fn block_view(block: Block) -> Value {
+    let h = &block.header;
+    json!({
+        "number": hex_quantity(h.height),
+        "hash": block.hash(),
+        "parentHash": h.parent_hash,
+        "timestamp": hex_quantity(h.timestamp_ms / 1000),
+        "miner": address_to_hex(&h.proposer),
+        "stateRoot": h.state_root,
+        "transactionsRoot": h.tx_root,
+        "receiptsRoot": h.receipts_root,
+        "logsBloom": h.logs_bloom,
+        "gasLimit": hex_quantity(h.gas_limit),
+        "gasUsed": hex_quantity(h.gas_used),
+        "baseFeePerGas": hex_quantity(h.base_fee),
+        // 没有工作量证明与叔块，按以太坊 PoS 区块的取值填充
+        "difficulty": "0x0",
+        "nonce": "0x0000000000000000",
+        "extraData": "0x",
+        "sha3Uncles": keccak256(&[0xc0]),
+        "uncles": [],
+        "transactions": block.txs.iter().map(|stx| stx.hash()).collect::<Vec<H256>>(),
+    })
+}
+
+/// 32 字节大端整数（签名分量 r / s）的 quantity 形式。
+fn hex_word(bytes: &[u8]) -> Value {
+    let digits = hex::encode(bytes);
+    let trimmed = digits.trim_start_matches('0');
+    json!(format!(
+        "0x{}",
+        if trimmed.is_empty() { "0" } else { trimmed }
+    ))
+}
+
+fn tx_to(action: &Action) -> Value {
+    match action {
+        Action::Transfer { to } | Action::Call { to, .. } => json!(address_to_hex(to)),
+        _ => Value::Null,
+    }
+}
+
+/// 交易视图；block 为所在区块与区块内序号，仍在交易池中时为 None。
+fn tx_view(stx: &SignedTransaction, block: Option<(&Block, u32)>) -> Value {
+    let tx = &stx.tx;
+    let gas_price = block
+        .and_then(|(blk, _)| tx.effective_gas_price(blk.header.base_fee))
+        .unwrap_or(tx.max_fee_per_gas);
+    let mut view = json!({
+        "hash": stx.hash(),
+        "type": "0x2",
+        "chainId": hex_quantity(eth_chain_id(&tx.chain_id)),
+        "nonce": hex_quantity(tx.nonce),
+        "from": address_to_hex(&stx.sender()),
+        "to": tx_to(&tx.action),
+        "value": hex_quantity(tx.value),
+        "gas": hex_quantity(tx.gas_limit),
+        "gasPrice": hex_quantity(gas_price),
+        "maxFeePerGas": hex_quantity(tx.max_fee_per_gas),
+        "maxPriorityFeePerGas": hex_quantity(tx.max_priority_fee_per_gas),
+        "input": hex_data(tx.action.payload()),
+        "accessList": [],
+        "blockHash": block.map(|(blk, _)| blk.hash()),
+        "blockNumber": block.map(|(blk, _)| hex_quantity(blk.header.height)),
+        "transactionIndex": block.map(|(_, index)| hex_quantity(index)),
+    });
+    if let Some(eth) = stx.eth_transaction() {
+        let sig = &eth.signature;
+        view["v"] = hex_quantity(sig[64]);
+        view["yParity"] = hex_quantity(sig[64]);
+        view["r"] = hex_word(&sig[..32]);
+        view["s"] = hex_word(&sig[32..64]);
+    }
+    view
+}
+
+/// 回执视图；first_log_index 为本交易第一条日志在区块内的序号。
+fn receipt_view(
+    receipt: Receipt,
+    stx: &SignedTransaction,
+    block: &Block,
+    index: u32,
+    first_log_index: u32,
+) -> Value {
+    let block_hash = block.hash();
+    let bloom = ark_types::Bloom::from_logs(&receipt.logs);
+    let logs: Vec<Value> = receipt
+        .logs
+        .iter()
+        .zip(first_log_index..)
+        .map(|(log, log_index)| {
+            let v = LogView {
+                log: log.clone(),
+                block_height: block.header.height,
+                tx_hash: receipt.tx_hash,
+                tx_index: index,
+                log_index,
+            };
+            log_view(v, block_hash)
+        })
+        .collect();
+    json!({
+        "transactionHash": receipt.tx_hash,
+        "transactionIndex": hex_quantity(index),
+        "blockHash": block_hash,
+        "blockNumber": hex_quantity(block.header.height),
+        "type": "0x2",
+        "from": address_to_hex(&stx.sender()),
+        "to": tx_to(&stx.tx.action),
+        "status": if receipt.is_success() { "0x1" } else { "0x0" },
+        "gasUsed": hex_quantity(receipt.gas_used),
+        "cumulativeGasUsed": hex_quantity(receipt.cumulative_gas_used),
+        "effectiveGasPrice": hex_quantity(receipt.effective_gas_price),
+        "contractAddress": receipt.contract_address.as_ref().map(address_to_hex),
+        "logs": logs,
+        "logsBloom": bloom,
+    })
+}
+
+fn log_view(v: LogView, block_hash: H256) -> Value {
+    json!({
+        "address": address_to_hex(&v.log.address),
+        "topics": v.log.topics,
+        "data": hex_data(&v.log.data),
+        "blockNumber": hex_quantity(v.block_height),
+        "blockHash": block_hash,
+        "transactionHash": v.tx_hash,
+        "transactionIndex": hex_quantity(v.tx_index),
+        "logIndex": hex_quantity(v.log_index),
+        "removed": false,
+    })
+}
+
+/// 注册 `eth_*` / `net_version` 方法。
+pub fn register_eth_api(methods: &mut Methods, backend: Arc<dyn ChainBackend>) {
+    let b = backend.clone();
+    methods.register("eth_chainId", move |_| {
+        Ok(hex_quantity(eth_chain_id(&b.chain_id())))
+    });
+
+    let b = backend.clone();
+    methods.register("net_version", move |_| {
+        Ok(json!(eth_chain_id(&b.chain_id()).to_string()))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_blockNumber", move |_| Ok(hex_quantity(b.head())));
+
+    let b = backend.clone();
+    methods.register("eth_gasPrice", move |_| {
+        let head = b.block(b.head()).map_err(RpcError::internal)?;
+        Ok(hex_quantity(head.map_or(0, |blk| blk.header.base_fee)))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getBlockByNumber", move |p: Params| {
+        let height = block_param(&*b, &p, 0)?;
+        let block = b.block(height).map_err(RpcError::internal)?;
+        Ok(block.map_or(Value::Null, block_view))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getBalance", move |p: Params| {
+        let address = address_param(&p, 0)?;
+        latest_state(&*b, &p, 1)?;
+        Ok(hex_quantity(b.balance(&address)))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getTransactionCount", move |p: Params| {
+        let address = address_param(&p, 0)?;
+        let pending = p.optional::<String>(1, "block")?.as_deref() == Some("pending");
+        if !pending {
+            latest_state(&*b, &p, 1)?;
+        }
+        Ok(hex_quantity(b.nonce(&address, pending)))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getCode", move |p: Params| {
+        let address = address_param(&p, 0)?;
+        latest_state(&*b, &p, 1)?;
+        Ok(hex_data(&b.code(&address).unwrap_or_default()))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getStorageAt", move |p: Params| {
+        let address = address_param(&p, 0)?;
+        let slot: String = p.required(1, "slot")?;
+        let raw = slot.strip_prefix("0x").unwrap_or(&slot);
+        if raw.is_empty() || raw.len() > 64 {
+            return Err(RpcError::invalid_params(format!("invalid slot `{slot}`")));
+        }
+        let mut key = [0u8; 32];
+        hex::decode_to_slice(format!("{raw:0>64}"), &mut key)
+            .map_err(|e| RpcError::invalid_params(format!("invalid slot `{slot}`: {e}")))?;
+        latest_state(&*b, &p, 2)?;
+        Ok(json!(b.storage(&address, &H256(key))))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_call", move |p: Params| {
+        let call = p.required::<EthCall>(0, "call")?.into_request()?;
+        let height = block_param(&*b, &p, 1)?;
+        let result = b.simulate(&call, height)?;
+        match result.status {
+            ExecStatus::Success => Ok(hex_data(&result.output)),
+            _ => {
+                let reason = result
+                    .revert_reason
+                    .unwrap_or_else(|| format!("{:?}", result.status).to_lowercase());
+                Err(
+                    RpcError::new(EXECUTION_REVERTED, format!("execution reverted: {reason}"))
+                        .with_data(hex_data(&result.output)),
+                )
+            }
+        }
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getLogs", move |p: Params| {
+        let query: EthLogFilter = p.required(0, "filter")?;
+        let from = block_tag(query.from_block.clone())?;
+        let to = block_tag(query.to_block.clone())?;
+        let logs = query_logs(&*b, &query.to_filter()?, Some(from), Some(to))?;
+        let mut hashes = BTreeMap::new();
+        let mut out = Vec::with_capacity(logs.len());
+        for v in logs {
+            let hash = match hashes.get(&v.block_height) {
+                Some(hash) => *hash,
+                None => {
+                    let block = b.block(v.block_height).map_err(RpcError::internal)?;
+                    let hash = block.map(|blk| blk.hash()).unwrap_or_default();
+                    hashes.insert(v.block_height, hash);
+                    hash
+                }
+            };
+            out.push(log_view(v, hash));
+        }
+        Ok(json!(out))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_sendRawTransaction", move |p: Params| {
+        let raw: String = p.required(0, "raw")?;
+        let bytes = hex::decode(raw.strip_prefix("0x").unwrap_or(&raw))
+            .map_err(|e| RpcError::invalid_params(format!("invalid hex: {e}")))?;
+        let eth = Eip1559Transaction::decode(&bytes)
+            .map_err(|e| RpcError::invalid_params(format!("invalid transaction: {e}")))?;
+        let chain_id = b.chain_id();
+        if eth.chain_id != eth_chain_id(&chain_id) {
+            return Err(RpcError::new(
+                TX_REJECTED,
+                format!(
+                    "chain id mismatch: expected {:#x}, got {:#x}",
+                    eth_chain_id(&chain_id),
+                    eth.chain_id
+                ),
+            ));
+        }
+        let pubkey = ark_crypto::recover(&eth.signing_hash().0, &eth.signature)
+            .ok_or_else(|| RpcError::new(TX_REJECTED, "invalid signature"))?;
+        let stx = SignedTransaction {
+            tx: eth.to_native(&chain_id),
+            pubkey: pubkey.to_vec(),
+            signature: eth.signature.to_vec(),
+        };
+        b.submit_transaction(stx)
+            .map(|hash| json!(hash))
+            .map_err(|reason| RpcError::new(TX_REJECTED, reason))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getTransactionByHash", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        if let Some((stx, loc)) = b.transaction(&hash).map_err(RpcError::internal)? {
+            let block = b.block(loc.height).map_err(RpcError::internal)?;
+            return Ok(tx_view(&stx, block.as_ref().map(|blk| (blk, loc.index))));
+        }
+        Ok(b.pending_transaction(&hash)
+            .map_or(Value::Null, |stx| tx_view(&stx, None)))
+    });
+
+    let b = backend.clone();
+    methods.register("eth_getTransactionReceipt", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        let Some((receipt, loc)) = b.receipt(&hash).map_err(RpcError::internal)? else {
+            return Ok(Value::Null);
+        };
+        let tx = b.transaction(&hash).map_err(RpcError::internal)?;
+        let block = b.block(loc.height).map_err(RpcError::internal)?;
+        let receipts = b.block_receipts(loc.height).map_err(RpcError::internal)?;
+        let (Some((stx, _)), Some(block)) = (tx, block) else {
+            return Ok(Value::Null);
+        };
+        let first_log_index = receipts
+            .unwrap_or_default()
+            .iter()
+            .take(loc.index as usize)
+            .map(|r| r.logs.len() as u32)
+            .sum();
+        Ok(receipt_view(
+            receipt,
+            &stx,
+            &block,
+            loc.index,
+            first_log_index,
+        ))
+    });
+
+    let b = backend;
+    methods.register("eth_estimateGas", move |p: Params| {
+        let call = p.required::<EthCall>(0, "call")?.into_request()?;
+        let height = block_param(&*b, &p, 1)?;
+        Ok(hex_quantity(b.estimate_gas(&call, height)?))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::tests::MockBackend;
+    use crate::error::INVALID_PARAMS;
+    use crate::jsonrpc::handle_request;
+
+    fn call(m: &Methods, method: &str, params: Value) -> Value {
+        let req = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        handle_request(m, req).unwrap()
+    }
+
+    #[test]
+    fn eth_queries_use_hex_encoding() {
+        let mut m = Methods::new();
+        register_eth_api(&mut m, Arc::new(MockBackend::default()));
+        let chain_id = call(&m, "eth_chainId", json!([]))["result"].clone();
+        assert_eq!(chain_id, hex_quantity(eth_chain_id("ark-test")));
+        assert_eq!(call(&m, "eth_blockNumber", json!([]))["result"], "0x0");
+
+        let addr = address_to_hex(&Address::ZERO);
+        assert_eq!(
+            call(&m, "eth_getBalance", json!([addr, "latest"]))["result"],
+            "0x2a"
+        );
+        assert_eq!(
+            call(&m, "eth_getBalance", json!([addr, "0x1"]))["error"]["code"],
+            STATE_UNAVAILABLE
+        );
+        assert_eq!(
+            call(&m, "eth_getBalance", json!([Address::ZERO]))["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            call(&m, "eth_getCode", json!([addr, "pending"]))["result"],
+            "0x"
+        );
+        let slot = call(&m, "eth_getStorageAt", json!([addr, "0x1", "latest"]));
+        assert_eq!(slot["result"], json!(H256::ZERO));
+
+        let block = call(&m, "eth_getBlockByNumber", json!(["earliest", false]));
+        assert_eq!(block["result"]["number"], "0x0");
+        assert_eq!(block["result"]["hash"], json!(Block::default().hash()));
+
+        let ok = call(&m, "eth_call", json!([{"to": addr, "value": "0x1"}]));
+        assert_eq!(ok["result"], "0x");
+        let poor = call(&m, "eth_call", json!([{"to": addr, "value": "0x64"}]));
+        assert_eq!(poor["error"]["code"], crate::error::TX_REJECTED);
+        let create = call(&m, "eth_estimateGas", json!([{"data": "0x00"}]));
+        assert_eq!(create["result"], "0x5208");
+        let reverted = call(&m, "eth_estimateGas", json!([{"to": addr}]));
+        assert_eq!(reverted["error"]["code"], EXECUTION_REVERTED);
+
+        let logs = call(
+            &m,
+            "eth_getLogs",
+            json!([{"fromBlock": "earliest", "toBlock": "0x2", "address": [addr]}]),
+        );
+        let logs = logs["result"].as_array().unwrap();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[2]["blockNumber"], "0x2");
+        assert_eq!(logs[0]["address"], json!(addr));
+        assert_eq!(logs[0]["blockHash"], json!(Block::default().hash()));
+        let other = address_to_hex(&Address([1; 20]));
+        let none = call(&m, "eth_getLogs", json!([{"address": other}]));
+        assert_eq!(none["result"], json!([]));
+    }
+
+    #[test]
+    fn raw_eip1559_transactions_are_recovered_and_queryable() {
+        let mut m = Methods::new();
+        let backend = Arc::new(MockBackend::default());
+        register_eth_api(&mut m, backend.clone());
+        let sk = ark_crypto::SecretKey::from_bytes(&[1u8; 32]).unwrap();
+        let mut tx = Eip1559Transaction {
+            chain_id: eth_chain_id("ark-test"),
+            nonce: 0,
+            max_priority_fee_per_gas: 1,
+            max_fee_per_gas: 10,
+            gas_limit: 50_000,
+            to: Some(Address([0xaa; 20])),
+            value: 5,
+            data: vec![0x01, 0x02],
+            signature: [0; 65],
+        };
+        tx.signature = sk.sign_recoverable(&tx.signing_hash().0).unwrap();
+        let raw = format!("0x{}", hex::encode(tx.encode()));
+
+        let sent = call(&m, "eth_sendRawTransaction", json!([raw]));
+        assert_eq!(sent["result"], json!(tx.hash()));
+        let stx = backend.submitted.lock().unwrap()[0].clone();
+        let from = ark_types::eth::address_from_pubkey(&sk.uncompressed_public_key());
+        assert_eq!(stx.sender(), from);
+
+        let view = call(&m, "eth_getTransactionByHash", json!([tx.hash()]))["result"].clone();
+        assert_eq!(view["from"], json!(address_to_hex(&from)));
+        assert_eq!(view["to"], json!(address_to_hex(&Address([0xaa; 20]))));
+        assert_eq!(view["input"], "0x0102");
+        assert_eq!(view["blockNumber"], Value::Null);
+        assert_eq!(view["yParity"], hex_quantity(tx.signature[64]));
+        let receipt = call(&m, "eth_getTransactionReceipt", json!([tx.hash()]));
+        assert_eq!(receipt["result"], Value::Null);
+
+        let other_chain = Eip1559Transaction {
+            chain_id: 1,
+            ..tx.clone()
+        };
+        let raw = format!("0x{}", hex::encode(other_chain.encode()));
+        let rejected = call(&m, "eth_sendRawTransaction", json!([raw]));
+        assert_eq!(rejected["error"]["code"], TX_REJECTED);
+        let garbage = call(&m, "eth_sendRawTransaction", json!(["0x01"]));
+        assert_eq!(garbage["error"]["code"], INVALID_PARAMS);
+
+        let block = call(&m, "eth_getBlockByNumber", json!(["latest", false]));
+        assert_eq!(block["result"]["uncles"], json!([]));
+    }
+}
@@ -0,0 +1,205 @@
+//! 链上类型与 `ark.node.v1` 生成消息之间的转换。
+use super::pb;
+use ark_storage::TxLocation;
+use ark_types::{Address, Block, BlockHeader, ExecStatus, Log, Receipt, SignedTransaction, H256};
+use tonic::Status;
+
+/// 请求中的 32 字节哈希字段。
+pub fn hash(field: &str, raw: &[u8]) -> Result<H256, Status> {
+    if raw.is_empty() {
+        return Err(Status::invalid_argument(format!("{field} is required")));
+    }
+    let bytes: [u8; 32] = raw
+        .try_into()
+        .map_err(|_| Status::invalid_argument(format!("{field}: expected 32-byte hash")))?;
+    Ok(H256(bytes))
+}
+
+pub fn address(field: &str, raw: &str) -> Result<Address, Status> {
+    if raw.is_empty() {
+        return Err(Status::invalid_argument(format!("{field} is required")));
+    }
+    raw.parse()
+        .map_err(|_| Status::invalid_argument(format!("{field}: invalid address")))
+}
+
+/// SendTransactionRequest.raw：签名交易的 JSON 编码。
+pub fn signed_transaction(raw: &[u8]) -> Result<SignedTransaction, Status> {
+    serde_json::from_slice(raw).map_err(|_| Status::invalid_argument("raw: invalid transaction"))
+}
+
+pub fn header(header: &BlockHeader) -> pb::BlockHeader {
+    pb::BlockHeader {
+        height: header.height,
+        hash: header.hash().as_bytes().to_vec(),
+        parent_hash: header.parent_hash.as_bytes().to_vec(),
+        timestamp_ms: header.timestamp_ms,
+        proposer: header.proposer.to_string(),
+        state_root: header.state_root.as_bytes().to_vec(),
+        tx_root: header.tx_root.as_bytes().to_vec(),
+        receipts_root: header.receipts_root.as_bytes().to_vec(),
+        gas_limit: header.gas_limit,
+        gas_used: header.gas_used,
+        base_fee: header.base_fee.to_string(),
+        logs_bloom: if header.logs_bloom.is_empty() {
+            Vec::new()
+        } else {
+            header.logs_bloom.0.to_vec()
+        },
+    }
+}
+
+pub fn block(block: &Block, full: bool) -> pb::Block {
+    let transactions = if full {
+        block
+            .txs
+            .iter()
+            .enumerate()
+            .map(|(i, stx)| {
+                let loc = TxLocation {
+                    height: block.height(),
+                    index: i as u32,
+                };
+                transaction(stx, Some(loc))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    pb::Block {
+        header: Some(header(&block.header)),
+        tx_hashes: block
+            .txs
+            .iter()
+            .map(|stx| stx.hash().as_bytes().to_vec())
+            .collect(),
+        transactions,
+    }
+}
+
+pub fn transaction(stx: &SignedTransaction, loc: Option<TxLocation>) -> pb::Transaction {
+    let tx = &stx.tx;
+    pb::Transaction {
+        hash: stx.hash().as_bytes().to_vec(),
+        from: stx.sender().to_string(),
+        chain_id: tx.chain_id.clone(),
+        nonce: tx.nonce,
+        value: tx.value.to_string(),
+        gas_limit: tx.gas_limit,
+        max_fee_per_gas: tx.max_fee_per_gas.to_string(),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas.to_string(),
+        encoded: serde_json::to_vec(stx).expect("transaction serializes"),
+        pending: loc.is_none(),
+        block_height: loc.map_or(0, |l| l.height),
+        index: loc.map_or(0, |l| l.index),
+    }
+}
+
+fn log(log: &Log) -> pb::Log {
+    pb::Log {
+        address: log.address.to_string(),
+        topics: log.topics.iter().map(|t| t.as_bytes().to_vec()).collect(),
+        data: log.data.clone(),
+    }
+}
+
+pub fn receipt(receipt: &Receipt, loc: TxLocation) -> pb::Receipt {
+    let status = match receipt.status {
+        ExecStatus::Success => pb::ExecStatus::Success,
+        ExecStatus::Reverted => pb::ExecStatus::Reverted,
+        ExecStatus::OutOfGas => pb::ExecStatus::OutOfGas,
+    };
+    pb::Receipt {
+        tx_hash: receipt.tx_hash.as_bytes().to_vec(),
+        status: status.into(),
+        gas_used: receipt.gas_used,
+        cumulative_gas_used: receipt.cumulative_gas_used,
+        effective_gas_price: receipt.effective_gas_price.to_string(),
+        contract_address: receipt
+            .contract_address
+            .as_ref()
+            .map(Address::to_string)
+            .unwrap_or_default(),
+        revert_reason: receipt.revert_reason.clone().unwrap_or_default(),
+        logs: receipt.logs.iter().map(log).collect(),
+        block_height: loc.height,
+        index: loc.index,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_types::{Action, Bloom, Transaction};
+
+    #[test]
+    fn converts_blocks_receipts_and_request_fields() {
+        let stx = SignedTransaction {
+            tx: Transaction {
+                chain_id: "ark-test".into(),
+                nonce: 3,
+                action: Action::Transfer { to: Address::ZERO },
+                value: u128::MAX,
+                gas_limit: 21_000,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 1,
+            },
+            pubkey: vec![2; 33],
+            signature: vec![1; 64],
+        };
+        let mut blk = Block {
+            header: BlockHeader {
+                height: 5,
+                ..Default::default()
+            },
+            txs: vec![stx.clone()],
+        };
+        let light = block(&blk, false);
+        assert!(light.transactions.is_empty());
+        assert_eq!(light.tx_hashes, vec![stx.hash().as_bytes().to_vec()]);
+        // 无日志的区块省略 bloom
+        assert!(light.header.unwrap().logs_bloom.is_empty());
+
+        let event = Log {
+            address: Address::ZERO,
+            topics: vec![H256([7; 32])],
+            data: vec![1, 2],
+        };
+        blk.header.logs_bloom = Bloom::from_logs(std::slice::from_ref(&event));
+        let full = block(&blk, true);
+        assert_eq!(full.header.unwrap().logs_bloom.len(), 256);
+        let tx = &full.transactions[0];
+        assert_eq!((tx.pending, tx.block_height, tx.index), (false, 5, 0));
+        assert_eq!(tx.value, u128::MAX.to_string());
+        assert_eq!(signed_transaction(&tx.encoded).unwrap(), stx);
+
+        let r = Receipt {
+            tx_hash: stx.hash(),
+            status: ExecStatus::Reverted,
+            gas_used: 21_000,
+            cumulative_gas_used: 21_000,
+            effective_gas_price: 10,
+            contract_address: None,
+            revert_reason: Some("nope".into()),
+            logs: vec![event],
+        };
+        let loc = TxLocation {
+            height: 5,
+            index: 0,
+        };
+        let out = receipt(&r, loc);
+        assert_eq!(out.status(), pb::ExecStatus::Reverted);
+        assert_eq!(out.logs[0].topics, vec![vec![7; 32]]);
+        assert!(out.contract_address.is_empty());
+        assert_eq!(out.revert_reason, "nope");
+
+        assert_eq!(hash("hash", &[1; 32]).unwrap(), H256([1; 32]));
+        assert_eq!(
+            hash("hash", &[]).unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+        assert!(hash("hash", &[1; 31]).is_err());
+        assert!(address("address", "nope").is_err());
+        assert!(signed_transaction(b"{").is_err());
+    }
+}
@@ -0,0 +1,557 @@
+//! gRPC 传输：`ark.node.v1.Node` 服务（定义见 `proto/ark/node/v1/node.proto`）。
+//!
+//! 消息与服务骨架由 tonic-build 按 node.proto 在构建时生成（见 `build.rs`），传输为 tonic 的
+//! HTTP/2（h2c，无 TLS），不支持消息压缩。
+//!
+//! 访问控制与 HTTP / WebSocket 共用同一个 `Access`：每次调用（含打开区块流）按来源 IP
+//! 或 `x-api-key` 元数据扣一次限额，超限返回 RESOURCE_EXHAUSTED；并发区块流数量有上限。
+//! 被 `[rpc.methods.grpc]` 屏蔽的方法在进入处理函数前返回 PERMISSION_DENIED。
+
+// tonic 处理函数的错误类型就是 `Status`，体积大也只能沿用
+#![allow(clippy::result_large_err)]
+
+mod messages;
+
+/// 由 node.proto 生成的消息与服务代码。
+pub mod pb {
+    tonic::include_proto!("ark.node.v1");
+}
+
+use crate::access::{Access, Denied, MethodFilter};
+use crate::api::ChainBackend;
+use crate::pubsub::{ChainEvent, EventBus};
+use futures_util::Stream;
+use pb::node_server::{Node, NodeServer};
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{broadcast, watch};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+
+impl From<Denied> for Status {
+    fn from(denied: Denied) -> Self {
+        match denied {
+            Denied::Origin => Status::unauthenticated("origin not allowed"),
+            Denied::Unauthorized(reason) => Status::unauthenticated(reason),
+            Denied::RateLimited(_) => Status::resource_exhausted("rate limit exceeded"),
+        }
+    }
+}
+
+/// 内部错误只记录日志，不向客户端暴露细节。
+fn internal(e: impl std::fmt::Display) -> Status {
+    tracing::error!(error = %e, "grpc handler failed");
+    Status::internal("internal error")
+}
+
+#[derive(Clone, Debug)]
+pub struct GrpcConfig {
+    pub addr: SocketAddr,
+    /// 单条请求消息的最大字节数
+    pub max_message_bytes: usize,
+    /// 同时进行的区块流上限
+    pub max_streams: usize,
+    pub access: Arc<Access>,
+    /// 按 proto 方法名（如 "SendTransaction"）过滤
+    pub methods: MethodFilter,
+}
+
+impl GrpcConfig {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            max_message_bytes: 4 << 20,
+            max_streams: 100,
+            access: Arc::new(Access::default()),
+            methods: MethodFilter::default(),
+        }
+    }
+}
+
+struct Shared {
+    backend: Arc<dyn ChainBackend>,
+    bus: EventBus,
+    config: GrpcConfig,
+    /// 关闭时通知正在推送的区块流结束，否则优雅关闭会一直等待
+    closing: watch::Receiver<bool>,
+    streams: AtomicUsize,
+}
+
+/// 活跃区块流计数，流结束时自动减一。
+struct StreamGuard(Arc<Shared>);
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.0.streams.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub async fn serve_grpc(
+    config: GrpcConfig,
+    backend: Arc<dyn ChainBackend>,
+    bus: EventBus,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let (addr, server) = bind_grpc(config, backend, bus, shutdown)?;
+    tracing::info!(%addr, "grpc server listening");
+    server.await
+}
+
+/// 绑定端口，返回实际监听地址与服务 future（端口为 0 时由系统分配）。须在 tokio 运行时内调用。
+pub fn bind_grpc(
+    config: GrpcConfig,
+    backend: Arc<dyn ChainBackend>,
+    bus: EventBus,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<(SocketAddr, impl Future<Output = anyhow::Result<()>>)> {
+    let listener = std::net::TcpListener::bind(config.addr)?;
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::TcpListener::from_std(listener)?;
+    let addr = listener.local_addr()?;
+    let (close_tx, closing) = watch::channel(false);
+    let max_message_bytes = config.max_message_bytes;
+    let service = NodeService(Arc::new(Shared {
+        backend,
+        bus,
+        config,
+        closing,
+        streams: AtomicUsize::new(0),
+    }));
+    let server = tonic::transport::Server::builder()
+        .add_service(NodeServer::new(service).max_decoding_message_size(max_message_bytes))
+        .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async move {
+            shutdown.await;
+            let _ = close_tx.send(true);
+        });
+    Ok((addr, async move { server.await.map_err(Into::into) }))
+}
+
+struct NodeService(Arc<Shared>);
+
+impl NodeService {
+    /// 每次调用先检查方法过滤，再按来源 IP / API key 鉴权并扣限额。
+    fn admit<T>(&self, method: &str, req: &Request<T>) -> Result<(), Status> {
+        if !self.0.config.methods.permits(method) {
+            return Err(Status::permission_denied(format!(
+                "method {method} is disabled"
+            )));
+        }
+        let ip = req
+            .remote_addr()
+            .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |a| a.ip());
+        let headers = req.metadata().clone().into_headers();
+        self.0.config.access.authenticate(&headers, ip)?;
+        Ok(())
+    }
+
+    /// 在阻塞线程池上访问后端（存储读取可能较慢）。
+    async fn blocking<T, F>(&self, f: F) -> Result<Response<T>, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn ChainBackend) -> Result<T, Status> + Send + 'static,
+    {
+        let backend = self.0.backend.clone();
+        tokio::task::spawn_blocking(move || f(&*backend))
+            .await
+            .unwrap_or_else(|e| Err(internal(e)))
+            .map(Response::new)
+    }
+}
+
+type BlockStream = Pin<Box<dyn Stream<Item = Result<pb::Block, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Node for NodeService {
+    async fn get_chain_id(
+        &self,
+        req: Request<pb::GetChainIdRequest>,
+    ) -> Result<Response<pb::GetChainIdResponse>, Status> {
+        self.admit("GetChainId", &req)?;
+        self.blocking(|b| {
+            Ok(pb::GetChainIdResponse {
+                chain_id: b.chain_id(),
+            })
+        })
+        .await
+    }
+
+    async fn get_block_number(
+        &self,
+        req: Request<pb::GetBlockNumberRequest>,
+    ) -> Result<Response<pb::GetBlockNumberResponse>, Status> {
+        self.admit("GetBlockNumber", &req)?;
+        self.blocking(|b| Ok(pb::GetBlockNumberResponse { height: b.head() }))
+            .await
+    }
+
+    async fn get_block_by_number(
+        &self,
+        req: Request<pb::GetBlockByNumberRequest>,
+    ) -> Result<Response<pb::BlockResponse>, Status> {
+        self.admit("GetBlockByNumber", &req)?;
+        let req = req.into_inner();
+        self.blocking(move |b| {
+            let height = if req.latest { b.head() } else { req.height };
+            let block = b.block(height).map_err(internal)?;
+            Ok(pb::BlockResponse {
+                block: block.map(|blk| messages::block(&blk, req.full_transactions)),
+            })
+        })
+        .await
+    }
+
+    async fn get_block_by_hash(
+        &self,
+        req: Request<pb::GetBlockByHashRequest>,
+    ) -> Result<Response<pb::BlockResponse>, Status> {
+        self.admit("GetBlockByHash", &req)?;
+        let req = req.into_inner();
+        let hash = messages::hash("hash", &req.hash)?;
+        self.blocking(move |b| {
+            let block = b.block_by_hash(&hash).map_err(internal)?;
+            Ok(pb::BlockResponse {
+                block: block.map(|blk| messages::block(&blk, req.full_transactions)),
+            })
+        })
+        .await
+    }
+
+    async fn get_transaction(
+        &self,
+        req: Request<pb::GetTransactionRequest>,
+    ) -> Result<Response<pb::TransactionResponse>, Status> {
+        self.admit("GetTransaction", &req)?;
+        let hash = messages::hash("hash", &req.get_ref().hash)?;
+        self.blocking(move |b| {
+            let found = match b.transaction(&hash).map_err(internal)? {
+                Some((stx, loc)) => Some((stx, Some(loc))),
+                None => b.pending_transaction(&hash).map(|stx| (stx, None)),
+            };
+            Ok(pb::TransactionResponse {
+                transaction: found.map(|(stx, loc)| messages::transaction(&stx, loc)),
+            })
+        })
+        .await
+    }
+
+    async fn get_receipt(
+        &self,
+        req: Request<pb::GetReceiptRequest>,
+    ) -> Result<Response<pb::ReceiptResponse>, Status> {
+        self.admit("GetReceipt", &req)?;
+        let hash = messages::hash("hash", &req.get_ref().hash)?;
+        self.blocking(move |b| {
+            let found = b.receipt(&hash).map_err(internal)?;
+            Ok(pb::ReceiptResponse {
+                receipt: found.map(|(receipt, loc)| messages::receipt(&receipt, loc)),
+            })
+        })
+        .await
+    }
+
+    async fn get_account(
+        &self,
+        req: Request<pb::GetAccountRequest>,
+    ) -> Result<Response<pb::Account>, Status> {
+        self.admit("GetAccount", &req)?;
+        let req = req.into_inner();
+        let address = messages::address("address", &req.address)?;
+        self.blocking(move |b| {
+            Ok(pb::Account {
+                address: address.to_string(),
+                balance: b.balance(&address).to_string(),
+                nonce: b.nonce(&address, req.pending),
+            })
+        })
+        .await
+    }
+
+    async fn send_transaction(
+        &self,
+        req: Request<pb::SendTransactionRequest>,
+    ) -> Result<Response<pb::SendTransactionResponse>, Status> {
+        self.admit("SendTransaction", &req)?;
+        let stx = messages::signed_transaction(&req.get_ref().raw)?;
+        self.blocking(move |b| {
+            let hash = b
+                .submit_transaction(stx)
+                .map_err(Status::failed_precondition)?;
+            Ok(pb::SendTransactionResponse {
+                hash: hash.as_bytes().to_vec(),
+            })
+        })
+        .await
+    }
+
+    type SubscribeBlocksStream = BlockStream;
+
+    /// 服务端流：每个 NewHead 事件推送一个完整 Block 消息。
+    async fn subscribe_blocks(
+        &self,
+        req: Request<pb::SubscribeBlocksRequest>,
+    ) -> Result<Response<BlockStream>, Status> {
+        self.admit("SubscribeBlocks", &req)?;
+        let shared = &self.0;
+        if shared.streams.fetch_add(1, Ordering::SeqCst) >= shared.config.max_streams {
+            shared.streams.fetch_sub(1, Ordering::SeqCst);
+            return Err(Status::resource_exhausted("too many streams"));
+        }
+        let state = BlockFeed {
+            _guard: StreamGuard(shared.clone()),
+            shared: shared.clone(),
+            events: shared.bus.subscribe(),
+            closing: shared.closing.clone(),
+            full: req.get_ref().full_transactions,
+            done: false,
+        };
+        let stream = futures_util::stream::unfold(state, |mut feed| async move {
+            if feed.done {
+                return None;
+            }
+            match feed.next_block().await {
+                Ok(Some(block)) => Some((Ok(block), feed)),
+                Ok(None) => None,
+                Err(status) => {
+                    feed.done = true;
+                    Some((Err(status), feed))
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// 区块流状态；以错误状态结束时先推送该状态再结束。
+struct BlockFeed {
+    _guard: StreamGuard,
+    shared: Arc<Shared>,
+    events: broadcast::Receiver<Arc<ChainEvent>>,
+    closing: watch::Receiver<bool>,
+    full: bool,
+    done: bool,
+}
+
+impl BlockFeed {
+    /// 下一个新区块；事件总线关闭时返回 None（正常结束）。
+    async fn next_block(&mut self) -> Result<Option<pb::Block>, Status> {
+        loop {
+            let event = tokio::select! {
+                ev = self.events.recv() => ev,
+                _ = self.closing.changed() => return Err(Status::unavailable("server shutting down")),
+            };
+            let height = match event {
+                Ok(ev) => match &*ev {
+                    ChainEvent::NewHead(head) => head.header.height,
+                    _ => continue,
+                },
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    return Err(Status::resource_exhausted(format!(
+                        "subscriber lagged behind by {n} events"
+                    )))
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(None),
+            };
+            let backend = self.shared.backend.clone();
+            let block = tokio::task::spawn_blocking(move || backend.block(height))
+                .await
+                .map_err(internal)?
+                .map_err(internal)?;
+            if let Some(block) = block {
+                return Ok(Some(messages::block(&block, self.full)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::tests::MockBackend;
+    use crate::api::HeaderView;
+    use ark_types::Block;
+    use pb::node_client::NodeClient;
+    use tonic::transport::Channel;
+    use tonic::Code;
+
+    async fn client(addr: SocketAddr) -> NodeClient<Channel> {
+        NodeClient::connect(format!("http://{addr}")).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn unary_calls_and_block_stream() {
+        let bus = EventBus::default();
+        let (stop, rx) = tokio::sync::oneshot::channel::<()>();
+        let (addr, server) = bind_grpc(
+            GrpcConfig::new("127.0.0.1:0".parse().unwrap()),
+            Arc::new(MockBackend::default()),
+            bus.clone(),
+            async {
+                let _ = rx.await;
+            },
+        )
+        .unwrap();
+        let task = tokio::spawn(server);
+        let mut client = client(addr).await;
+
+        let reply = client.get_chain_id(pb::GetChainIdRequest {}).await.unwrap();
+        assert_eq!(reply.into_inner().chain_id, "ark-test");
+
+        let account = client
+            .get_account(pb::GetAccountRequest {
+                address: ark_types::Address::ZERO.to_string(),
+                pending: false,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(account.balance, "42");
+        let err = client
+            .get_account(pb::GetAccountRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let block = client
+            .get_block_by_number(pb::GetBlockByNumberRequest {
+                latest: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            block.block.unwrap().header.unwrap().hash,
+            Block::default().hash().as_bytes().to_vec()
+        );
+        let missing = client
+            .get_receipt(pb::GetReceiptRequest { hash: vec![1; 32] })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(missing.receipt.is_none());
+
+        // 空签名被交易池拒绝 → FAILED_PRECONDITION；非法编码 → INVALID_ARGUMENT
+        let stx = ark_types::SignedTransaction {
+            tx: ark_types::Transaction {
+                chain_id: "ark-test".into(),
+                nonce: 0,
+                action: ark_types::Action::Transfer {
+                    to: ark_types::Address::ZERO,
+                },
+                value: 1,
+                gas_limit: 21_000,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 1,
+            },
+            pubkey: vec![2; 33],
+            signature: Vec::new(),
+        };
+        let raw = serde_json::to_vec(&stx).unwrap();
+        let err = client
+            .send_transaction(pb::SendTransactionRequest { raw })
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::FailedPrecondition);
+        assert_eq!(err.message(), "invalid signature");
+        let err = client
+            .send_transaction(pb::SendTransactionRequest { raw: b"{".to_vec() })
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // 区块流：NewHead 事件推送对应区块，关闭服务时以 UNAVAILABLE 结束
+        let mut stream = client
+            .subscribe_blocks(pb::SubscribeBlocksRequest::default())
+            .await
+            .unwrap()
+            .into_inner();
+        while bus.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+        let genesis = Block::default();
+        bus.publish(ChainEvent::PendingTransaction(genesis.hash()));
+        bus.publish(ChainEvent::NewHead(Box::new(HeaderView::new(
+            genesis.header.clone(),
+        ))));
+        let first = stream.message().await.unwrap().unwrap();
+        assert_eq!(first, messages::block(&genesis, false));
+        stop.send(()).unwrap();
+        assert_eq!(
+            stream.message().await.unwrap_err().code(),
+            Code::Unavailable
+        );
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn enforces_rate_limits_and_stream_cap() {
+        let mut access = crate::access::AccessConfig::default();
+        access.rate_limit.per_ip_per_sec = 1;
+        access.rate_limit.per_ip_burst = 4;
+        let mut config = GrpcConfig::new("127.0.0.1:0".parse().unwrap());
+        config.max_streams = 1;
+        config.access = Arc::new(Access::new(&access).unwrap());
+        config.methods.deny = vec!["SendTransaction".into()];
+        let bus = EventBus::default();
+        let (stop, rx) = tokio::sync::oneshot::channel::<()>();
+        let (addr, server) = bind_grpc(
+            config,
+            Arc::new(MockBackend::default()),
+            bus.clone(),
+            async {
+                let _ = rx.await;
+            },
+        )
+        .unwrap();
+        let task = tokio::spawn(server);
+        let mut client = client(addr).await;
+
+        let mut bad_key = Request::new(pb::GetChainIdRequest {});
+        bad_key
+            .metadata_mut()
+            .insert("x-api-key", "guess".parse().unwrap());
+        let err = client.get_chain_id(bad_key).await.unwrap_err();
+        assert_eq!(err.code(), Code::Unauthenticated);
+        client.get_chain_id(pb::GetChainIdRequest {}).await.unwrap();
+
+        // 被屏蔽的方法不进入处理函数，也不消耗限额
+        let err = client
+            .send_transaction(pb::SendTransactionRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+        assert_eq!(err.message(), "method SendTransaction is disabled");
+
+        // 第一个流占满上限，第二个被拒绝；随后限额耗尽
+        let mut stream = client
+            .subscribe_blocks(pb::SubscribeBlocksRequest::default())
+            .await
+            .unwrap()
+            .into_inner();
+        while bus.receiver_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+        let err = client
+            .subscribe_blocks(pb::SubscribeBlocksRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::ResourceExhausted);
+        assert_eq!(err.message(), "too many streams");
+        let err = client
+            .get_chain_id(pb::GetChainIdRequest {})
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::ResourceExhausted);
+        assert_eq!(err.message(), "rate limit exceeded");
+
+        stop.send(()).unwrap();
+        assert_eq!(
+            stream.message().await.unwrap_err().code(),
+            Code::Unavailable
+        );
+        task.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,352 @@
+//! JSON-RPC HTTP 传输：仅接受 POST（及 CORS 预检 OPTIONS），请求体超限返回 413，
+//! 全部为通知时返回 204。访问控制见 `access`：Origin 不允许 403、凭据无效 401、限流 429。
+use crate::access::{Access, Denied};
+use crate::jsonrpc::handle_body;
+use crate::methods::Methods;
+use hyper::body::HttpBody;
+use hyper::header::{
+    HeaderValue, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, ALLOW, CONTENT_TYPE, RETRY_AFTER, VARY,
+    WWW_AUTHENTICATE,
+};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    pub addr: SocketAddr,
+    /// 请求体最大字节数
+    pub max_request_bytes: usize,
+    /// 单次批量请求的最大条数
+    pub max_batch: usize,
+    /// 访问控制（与 WebSocket 端点共享限流桶）
+    pub access: Arc<Access>,
+}
+
+impl HttpConfig {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            max_request_bytes: 5 << 20,
+            max_batch: 100,
+            access: Arc::new(Access::default()),
+        }
+    }
+}
+
+/// 按调用方身份区分的两份方法表。
+struct Views {
+    public: Methods,
+    admin: Methods,
+}
+
+/// 启动 HTTP 服务直到 shutdown 完成（优雅关闭：不再接受新连接，等待进行中的请求）。
+pub async fn serve(
+    config: HttpConfig,
+    methods: Arc<Methods>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let (addr, server) = bind(config, methods, shutdown)?;
+    tracing::info!(%addr, "json-rpc http server listening");
+    server.await
+}
+
+/// 绑定端口，返回实际监听地址与服务 future（端口为 0 时由系统分配）。
+pub fn bind(
+    config: HttpConfig,
+    methods: Arc<Methods>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<(SocketAddr, impl Future<Output = anyhow::Result<()>>)> {
+    let builder = hyper::Server::try_bind(&config.addr)?;
+    let views = Arc::new(Views {
+        public: config.access.public_view(&methods),
+        admin: (*methods).clone(),
+    });
+    let config = Arc::new(config);
+    let make = make_service_fn(move |conn: &AddrStream| {
+        let ip = conn.remote_addr().ip();
+        let views = views.clone();
+        let config = config.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(req, ip, views.clone(), config.clone())
+            }))
+        }
+    });
+    let server = builder.serve(make);
+    let addr = server.local_addr();
+    let server = server.with_graceful_shutdown(shutdown);
+    Ok((addr, async move { server.await.map_err(Into::into) }))
+}
+
+async fn handle(
+    req: Request<Body>,
+    ip: IpAddr,
+    views: Arc<Views>,
+    config: Arc<HttpConfig>,
+) -> Result<Response<Body>, Infallible> {
+    let access = &config.access;
+    let origin = match access.check_origin(req.headers()) {
+        Ok(origin) => origin,
+        Err(denied) => return Ok(denied_response(&denied)),
+    };
+    let mut resp = dispatch(req, ip, views, &config).await;
+    if let Some(origin) = origin {
+        let h = resp.headers_mut();
+        h.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        h.insert(VARY, HeaderValue::from_static("Origin"));
+    }
+    Ok(resp)
+}
+
+async fn dispatch(
+    req: Request<Body>,
+    ip: IpAddr,
+    views: Arc<Views>,
+    config: &HttpConfig,
+) -> Response<Body> {
+    if req.method() == Method::OPTIONS {
+        // CORS 预检（Origin 已校验）
+        let mut resp = plain(StatusCode::NO_CONTENT, "");
+        let h = resp.headers_mut();
+        h.insert(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("POST, OPTIONS"),
+        );
+        h.insert(
+            ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("content-type, authorization, x-api-key"),
+        );
+        h.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from_static("600"));
+        return resp;
+    }
+    if req.method() != Method::POST {
+        let mut resp = plain(StatusCode::METHOD_NOT_ALLOWED, "only POST is supported");
+        resp.headers_mut()
+            .insert(ALLOW, HeaderValue::from_static("POST, OPTIONS"));
+        return resp;
+    }
+    if let Some(ct) = req.headers().get(CONTENT_TYPE) {
+        let json = ct
+            .to_str()
+            .map(|v| v.starts_with("application/json"))
+            .unwrap_or(false);
+        if !json {
+            return plain(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "content-type must be application/json",
+            );
+        }
+    }
+    // 按请求计数：一次批量请求只扣一次，批量条数另有上限
+    let caller = match config.access.authenticate(req.headers(), ip) {
+        Ok(caller) => caller,
+        Err(denied) => return denied_response(&denied),
+    };
+    let body = match read_body(req.into_body(), config.max_request_bytes).await {
+        Ok(b) => b,
+        Err(status) => return plain(status, "request body too large or unreadable"),
+    };
+
+    let max_batch = config.max_batch;
+    let result = tokio::task::spawn_blocking(move || {
+        let methods = if caller.admin {
+            &views.admin
+        } else {
+            &views.public
+        };
+        handle_body(methods, &body, max_batch)
+    })
+    .await;
+    match result {
+        Ok(Some(v)) => {
+            let mut resp = Response::new(Body::from(v.to_string()));
+            resp.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            resp
+        }
+        Ok(None) => plain(StatusCode::NO_CONTENT, ""),
+        Err(e) => {
+            tracing::error!(error = %e, "rpc handler panicked");
+            plain(StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+        }
+    }
+}
+
+/// 访问控制拒绝时的 HTTP 响应（WebSocket 握手同样使用）。
+pub(crate) fn denied_response(denied: &Denied) -> Response<Body> {
+    match denied {
+        Denied::Origin => plain(StatusCode::FORBIDDEN, "origin not allowed"),
+        Denied::Unauthorized(reason) => {
+            let mut resp = plain(StatusCode::UNAUTHORIZED, reason);
+            resp.headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            resp
+        }
+        Denied::RateLimited(wait) => {
+            let mut resp = plain(StatusCode::TOO_MANY_REQUESTS, "rate limit exceeded");
+            let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            resp.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(secs.max(1)));
+            resp
+        }
+    }
+}
+
+/// 读取请求体，超过 limit 返回 413（先看 Content-Length，再在读取过程中计数）。
+pub(crate) async fn read_body(mut body: Body, limit: usize) -> Result<Vec<u8>, StatusCode> {
+    if body.size_hint().lower() as usize > limit {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    let mut out = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if out.len() + chunk.len() > limit {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        out.extend_from_slice(&chunk);
+    }
+    Ok(out)
+}
+
+fn plain(status: StatusCode, msg: &'static str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(msg));
+    *resp.status_mut() = status;
+    resp.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    async fn request(addr: SocketAddr, method: &str, body: &str) -> (u16, String) {
+        request_with(addr, method, "", body).await
+    }
+
+    /// headers 为额外的请求头行（每行以 \r\n 结尾）。
+    async fn request_with(
+        addr: SocketAddr,
+        method: &str,
+        headers: &str,
+        body: &str,
+    ) -> (u16, String) {
+        let mut sock = TcpStream::connect(addr).await.unwrap();
+        let req = format!(
+            "{method} / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+             {headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        sock.write_all(req.as_bytes()).await.unwrap();
+        let mut raw = String::new();
+        sock.read_to_string(&mut raw).await.unwrap();
+        let status = raw[9..12].parse().unwrap();
+        let body = raw.split("\r\n\r\n").nth(1).unwrap_or("").to_string();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn serves_json_rpc_over_http_with_limits() {
+        let mut config = HttpConfig::new("127.0.0.1:0".parse().unwrap());
+        config.max_request_bytes = 256;
+        let methods = Arc::new(crate::api::tests::methods());
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let (addr, server) = bind(config, methods, async {
+            let _ = rx.await;
+        })
+        .unwrap();
+        let task = tokio::spawn(server);
+
+        let (status, body) = request(
+            addr,
+            "POST",
+            r#"{"jsonrpc":"2.0","id":7,"method":"ark_chainId"}"#,
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert!(body.contains(r#""result":"ark-test""#), "{body}");
+
+        let (status, _) =
+            request(addr, "POST", r#"{"jsonrpc":"2.0","method":"ark_chainId"}"#).await;
+        assert_eq!(status, 204);
+        let (status, _) = request(addr, "GET", "").await;
+        assert_eq!(status, 405);
+        let (status, _) = request(addr, "POST", &"x".repeat(300)).await;
+        assert_eq!(status, 413);
+
+        tx.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn enforces_cors_auth_rate_limits_and_admin_gate() {
+        let mut access = crate::access::AccessConfig::default();
+        access.cors.allowed_origins = vec!["https://wallet.example".into()];
+        access.rate_limit.per_ip_per_sec = 1;
+        access.rate_limit.per_ip_burst = 5;
+        access.auth.api_keys = vec!["k1".into()];
+        access.auth.admin_token = Some("s3cret".into());
+        let mut config = HttpConfig::new("127.0.0.1:0".parse().unwrap());
+        config.access = Arc::new(Access::new(&access).unwrap());
+
+        let mut methods = crate::api::tests::methods();
+        methods.register("admin_ping", |_| Ok(serde_json::json!("pong")));
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let (addr, server) = bind(config, Arc::new(methods), async {
+            let _ = rx.await;
+        })
+        .unwrap();
+        let task = tokio::spawn(server);
+        let admin = r#"{"jsonrpc":"2.0","id":1,"method":"admin_ping"}"#;
+
+        let (status, _) =
+            request_with(addr, "POST", "Origin: https://evil.example\r\n", admin).await;
+        assert_eq!(status, 403);
+        let (status, _) =
+            request_with(addr, "OPTIONS", "Origin: https://wallet.example\r\n", "").await;
+        assert_eq!(status, 204);
+        let (status, _) = request_with(addr, "POST", "X-API-Key: nope\r\n", admin).await;
+        assert_eq!(status, 401);
+        let (status, _) =
+            request_with(addr, "POST", "Authorization: Bearer wrong\r\n", admin).await;
+        assert_eq!(status, 401);
+
+        // 凭据无效的请求同样扣来源 IP 的限额；未鉴权调用 admin 方法得到 UNAUTHORIZED，带 token 则成功
+        let (status, body) = request(addr, "POST", admin).await;
+        assert_eq!(status, 200);
+        assert!(
+            body.contains(&crate::access::UNAUTHORIZED.to_string()),
+            "{body}"
+        );
+        let (status, body) =
+            request_with(addr, "POST", "Authorization: Bearer s3cret\r\n", admin).await;
+        assert_eq!(status, 200);
+        assert!(body.contains(r#""result":"pong""#), "{body}");
+        let (status, _) = request(addr, "POST", admin).await;
+        assert_eq!(status, 200);
+        let (status, _) = request(addr, "POST", admin).await;
+        assert_eq!(status, 429);
+        // 限额耗尽后继续猜测凭据只会得到 429
+        let (status, _) =
+            request_with(addr, "POST", "Authorization: Bearer guess\r\n", admin).await;
+        assert_eq!(status, 429);
+        let (status, _) = request_with(addr, "POST", "X-API-Key: guess\r\n", admin).await;
+        assert_eq!(status, 429);
+        // API key 使用独立的限额
+        let (status, _) = request_with(addr, "POST", "X-API-Key: k1\r\n", admin).await;
+        assert_eq!(status, 200);
+
+        tx.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+}
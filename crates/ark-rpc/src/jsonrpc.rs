@@ -0,0 +1,144 @@
+//! JSON-RPC 2.0 请求处理：单请求 / 批量请求 / 通知（无 id，不返回响应）。
+use crate::error::{RpcError, LIMIT_EXCEEDED};
+use crate::methods::{Methods, Params};
+use serde_json::{json, Value};
+
+pub const VERSION: &str = "2.0";
+
+/// 处理一个请求体。返回 None 表示全部为通知，无需响应内容。
+pub fn handle_body(methods: &Methods, body: &[u8], max_batch: usize) -> Option<Value> {
+    let req: Value = match serde_json::from_slice(body) {
+        Ok(v) => v,
+        Err(_) => return Some(error_response(Value::Null, RpcError::parse_error())),
+    };
+    match req {
+        Value::Array(items) if items.is_empty() => Some(error_response(
+            Value::Null,
+            RpcError::invalid_request("empty batch"),
+        )),
+        Value::Array(items) if items.len() > max_batch => Some(error_response(
+            Value::Null,
+            RpcError::new(
+                LIMIT_EXCEEDED,
+                format!("batch of {} exceeds limit {max_batch}", items.len()),
+            ),
+        )),
+        Value::Array(items) => {
+            let responses: Vec<Value> = items
+                .into_iter()
+                .filter_map(|item| handle_request(methods, item))
+                .collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        single => handle_request(methods, single),
+    }
+}
+
+/// 处理单个请求对象；通知返回 None。
+pub fn handle_request(methods: &Methods, req: Value) -> Option<Value> {
+    let Value::Object(mut obj) = req else {
+        return Some(error_response(
+            Value::Null,
+            RpcError::invalid_request("request must be an object"),
+        ));
+    };
+    let id = obj.remove("id");
+    let is_notification = id.is_none();
+    let id = id.unwrap_or(Value::Null);
+    if !matches!(id, Value::Null | Value::Number(_) | Value::String(_)) {
+        return Some(error_response(
+            Value::Null,
+            RpcError::invalid_request("id must be a string, number or null"),
+        ));
+    }
+    if obj.get("jsonrpc").and_then(Value::as_str) != Some(VERSION) {
+        return Some(error_response(
+            id,
+            RpcError::invalid_request("jsonrpc must be \"2.0\""),
+        ));
+    }
+    let Some(Value::String(method)) = obj.remove("method") else {
+        return Some(error_response(
+            id,
+            RpcError::invalid_request("method must be a string"),
+        ));
+    };
+    let params = match obj.remove("params") {
+        None => Value::Null,
+        Some(p @ (Value::Array(_) | Value::Object(_))) => p,
+        Some(_) => {
+            return Some(error_response(
+                id,
+                RpcError::invalid_request("params must be an array or object"),
+            ))
+        }
+    };
+
+    let result = methods.call(&method, Params(params));
+    if is_notification {
+        return None;
+    }
+    Some(match result {
+        Ok(v) => json!({ "jsonrpc": VERSION, "id": id, "result": v }),
+        Err(e) => error_response(id, e),
+    })
+}
+
+pub fn error_response(id: Value, err: RpcError) -> Value {
+    json!({ "jsonrpc": VERSION, "id": id, "error": err })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn methods() -> Methods {
+        let mut m = Methods::new();
+        m.register("add", |p: Params| {
+            let a: u64 = p.required(0, "a")?;
+            let b: u64 = p.required(1, "b")?;
+            Ok(json!(a + b))
+        });
+        m
+    }
+
+    fn call(body: &str) -> Option<Value> {
+        handle_body(&methods(), body.as_bytes(), 3)
+    }
+
+    #[test]
+    fn single_batch_and_notification() {
+        let r = call(r#"{"jsonrpc":"2.0","id":1,"method":"add","params":[1,2]}"#).unwrap();
+        assert_eq!(r["result"], 3);
+        assert_eq!(r["id"], 1);
+
+        let r = call(
+            r#"[{"jsonrpc":"2.0","id":"a","method":"add","params":[1,1]},
+                {"jsonrpc":"2.0","method":"add","params":[1,1]},
+                {"jsonrpc":"2.0","id":2,"method":"nope"}]"#,
+        )
+        .unwrap();
+        let items = r.as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["result"], 2);
+        assert_eq!(items[1]["error"]["code"], -32601);
+
+        assert_eq!(
+            call(r#"{"jsonrpc":"2.0","method":"add","params":[1,1]}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn structured_errors() {
+        let code = |body: &str| call(body).unwrap()["error"]["code"].as_i64().unwrap();
+        assert_eq!(code("{not json"), -32700);
+        assert_eq!(code("[]"), -32600);
+        assert_eq!(code(r#"{"jsonrpc":"1.0","id":1,"method":"add"}"#), -32600);
+        assert_eq!(
+            code(r#"{"jsonrpc":"2.0","id":1,"method":"add","params":[1]}"#),
+            -32602
+        );
+        assert_eq!(code(r#"[1,2,3,4]"#), LIMIT_EXCEEDED);
+    }
+}
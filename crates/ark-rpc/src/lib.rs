@@ -1,2 +1,39 @@
-//! RPC 占位：后续提供 JSON-RPC / gRPC / WS
-pub fn serve() {}
+//! RPC 服务：JSON-RPC 2.0（HTTP / WebSocket）与 gRPC。
+//!
+//! - methods：方法注册表与参数解析
+//! - access：CORS、限流、admin 鉴权与按端点的方法过滤
+//! - admin：`admin_*` 运维方法（节点表、同步进度、日志过滤、快照、交易池），数据来自 AdminBackend
+//! - jsonrpc：请求校验、批量分发、标准错误码
+//! - api：`ark_*` 链查询 / 日志查询 / 交易提交方法，数据来自节点实现的 ChainBackend
+//! - eth：`eth_*` 兼容方法（十六进制编码），供以太坊钱包与工具访问 EVM 合约
+//! - proof：返回 Merkle 证明的账户 / 存储 / 交易 / 回执查询
+//! - http：HTTP 传输（仅 POST，限制请求体大小与批量条数）
+//! - pubsub / ws：链事件总线与 WebSocket 订阅（新区块头、日志、待打包交易、最终确认）
+//! - grpc：`ark.node.v1.Node` 服务，protobuf 定义位于 `proto/`
+pub mod access;
+pub mod admin;
+pub mod api;
+pub mod error;
+pub mod eth;
+pub mod grpc;
+pub mod http;
+pub mod jsonrpc;
+pub mod methods;
+pub mod proof;
+pub mod pubsub;
+pub mod ws;
+
+pub use access::{Access, AccessConfig};
+pub use admin::{register_admin_api, AdminBackend, PoolEntry, SnapshotInfo, SyncStatus};
+pub use api::{
+    register_chain_api, BlockTag, CallError, ChainBackend, HeaderView, LogQuery, LogView,
+    MAX_LOG_BLOCK_RANGE, MAX_LOG_RESULTS,
+};
+pub use error::RpcError;
+pub use eth::register_eth_api;
+pub use grpc::{serve_grpc, GrpcConfig};
+pub use http::{serve, HttpConfig};
+pub use methods::{Methods, Params};
+pub use proof::register_proof_api;
+pub use pubsub::{ChainEvent, EventBus};
+pub use ws::{serve_ws, WsConfig};
@@ -0,0 +1,93 @@
+//! 方法注册表：方法名 -> 同步处理函数。
+use crate::error::RpcError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub type Handler = Arc<dyn Fn(Params) -> Result<Value, RpcError> + Send + Sync>;
+
+/// 请求参数：按位置（数组）或按名称（对象）。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params(pub Value);
+
+impl Params {
+    fn positional(&self, index: usize) -> Option<&Value> {
+        match &self.0 {
+            Value::Array(items) => items.get(index).filter(|v| !v.is_null()),
+            _ => None,
+        }
+    }
+
+    /// 必填位置参数。
+    pub fn required<T: DeserializeOwned>(&self, index: usize, name: &str) -> Result<T, RpcError> {
+        let v = self
+            .positional(index)
+            .ok_or_else(|| RpcError::invalid_params(format!("missing parameter `{name}`")))?;
+        serde_json::from_value(v.clone())
+            .map_err(|e| RpcError::invalid_params(format!("invalid `{name}`: {e}")))
+    }
+
+    /// 可选位置参数（缺省或 null 返回 None）。
+    pub fn optional<T: DeserializeOwned>(
+        &self,
+        index: usize,
+        name: &str,
+    ) -> Result<Option<T>, RpcError> {
+        self.positional(index)
+            .map(|v| {
+                serde_json::from_value(v.clone())
+                    .map_err(|e| RpcError::invalid_params(format!("invalid `{name}`: {e}")))
+            })
+            .transpose()
+    }
+
+    /// 整体按结构解析（按名称传参时使用）。
+    pub fn parse<T: DeserializeOwned>(self) -> Result<T, RpcError> {
+        serde_json::from_value(self.0).map_err(|e| RpcError::invalid_params(e.to_string()))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Methods {
+    handlers: BTreeMap<String, Handler>,
+}
+
+impl Methods {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册方法；同名方法会被覆盖。
+    pub fn register<F>(&mut self, name: impl Into<String>, handler: F)
+    where
+        F: Fn(Params) -> Result<Value, RpcError> + Send + Sync + 'static,
+    {
+        self.handlers.insert(name.into(), Arc::new(handler));
+    }
+
+    /// 合并另一组方法。
+    pub fn merge(&mut self, other: Methods) {
+        self.handlers.extend(other.handlers);
+    }
+
+    /// 只保留 keep 返回 true 的方法。
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.handlers.retain(|name, _| keep(name));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+
+    pub fn call(&self, name: &str, params: Params) -> Result<Value, RpcError> {
+        match self.handlers.get(name) {
+            Some(h) => h(params),
+            None => Err(RpcError::method_not_found(name)),
+        }
+    }
+}
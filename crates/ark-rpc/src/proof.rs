@@ -0,0 +1,159 @@
+//! 返回 Merkle 证明的查询方法，供轻客户端 / 跨链桥离线校验（校验逻辑见 `ark_types::proof`）。
+//!
+//! - `ark_getAccountProof(address, block?)`、`ark_getStorageProof(address, slot, block?)`：
+//!   证明相对该区块头的 state_root。不支持历史状态：节点只保留最新状态，
+//!   block 不是最新区块时返回 STATE_UNAVAILABLE（"historical state proofs are not supported"）
+//! - `ark_getTransactionProof(hash)`、`ark_getReceiptProof(hash)`：相对区块头的 tx_root / receipts_root
+use crate::api::{BlockTag, ChainBackend};
+use crate::error::{RpcError, STATE_UNAVAILABLE};
+use crate::methods::{Methods, Params};
+use ark_types::account::{account_key, storage_key};
+use ark_types::block::receipt_leaf;
+use ark_types::proof::{ReceiptProof, StateProof, TxProof};
+use ark_types::{merkle, Address, BlockHeader, SignedTransaction, H256};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Serialize)]
+struct StateProofView<T> {
+    block_height: u64,
+    state_root: H256,
+    #[serde(flatten)]
+    value: T,
+    proof: StateProof,
+}
+
+fn state_proof(
+    b: &dyn ChainBackend,
+    tag: BlockTag,
+    key: &[u8],
+) -> Result<Option<(BlockHeader, StateProof)>, RpcError> {
+    let Some(block) = b.block(tag.resolve(b.head())).map_err(RpcError::internal)? else {
+        return Ok(None);
+    };
+    let header = block.header;
+    let proof = b.state_proof(&header.state_root, key).ok_or_else(|| {
+        RpcError::new(
+            STATE_UNAVAILABLE,
+            format!(
+                "historical state proofs are not supported: block {} is not the latest block",
+                header.height
+            ),
+        )
+    })?;
+    Ok(Some((header, proof)))
+}
+
+/// 注册证明查询方法。
+pub fn register_proof_api(methods: &mut Methods, backend: Arc<dyn ChainBackend>) {
+    let b = backend.clone();
+    methods.register("ark_getAccountProof", move |p: Params| {
+        let address: Address = p.required(0, "address")?;
+        let tag = p.optional(1, "block")?.unwrap_or(BlockTag::Latest);
+        let Some((header, proof)) = state_proof(&*b, tag, &account_key(&address))? else {
+            return Ok(Value::Null);
+        };
+        let account = proof
+            .verify_account(&header.state_root, &address)
+            .map_err(RpcError::internal)?;
+        Ok(json!(StateProofView {
+            block_height: header.height,
+            state_root: header.state_root,
+            value: json!({ "address": address, "account": account }),
+            proof,
+        }))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getStorageProof", move |p: Params| {
+        let address: Address = p.required(0, "address")?;
+        let slot: H256 = p.required(1, "slot")?;
+        let tag = p.optional(2, "block")?.unwrap_or(BlockTag::Latest);
+        let Some((header, proof)) = state_proof(&*b, tag, &storage_key(&address, &slot))? else {
+            return Ok(Value::Null);
+        };
+        let value = proof
+            .verify_storage(&header.state_root, &address, &slot)
+            .map_err(RpcError::internal)?;
+        Ok(json!(StateProofView {
+            block_height: header.height,
+            state_root: header.state_root,
+            value: json!({ "address": address, "slot": slot, "value": value }),
+            proof,
+        }))
+    });
+
+    let b = backend.clone();
+    methods.register("ark_getTransactionProof", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        let Some((_, loc)) = b.transaction(&hash).map_err(RpcError::internal)? else {
+            return Ok(Value::Null);
+        };
+        let block = b
+            .block(loc.height)
+            .map_err(RpcError::internal)?
+            .ok_or_else(|| RpcError::internal("indexed transaction without block"))?;
+        let leaves: Vec<H256> = block.txs.iter().map(SignedTransaction::hash).collect();
+        let proof = merkle::prove(&leaves, loc.index as usize)
+            .ok_or_else(|| RpcError::internal("transaction index out of range"))?;
+        let transaction = block.txs[loc.index as usize].clone();
+        Ok(json!(TxProof {
+            header: block.header,
+            transaction,
+            proof,
+        }))
+    });
+
+    let b = backend;
+    methods.register("ark_getReceiptProof", move |p: Params| {
+        let hash: H256 = p.required(0, "hash")?;
+        let Some((receipt, loc)) = b.receipt(&hash).map_err(RpcError::internal)? else {
+            return Ok(Value::Null);
+        };
+        let header = b
+            .block(loc.height)
+            .map_err(RpcError::internal)?
+            .ok_or_else(|| RpcError::internal("indexed receipt without block"))?
+            .header;
+        let receipts = b
+            .block_receipts(loc.height)
+            .map_err(RpcError::internal)?
+            .unwrap_or_default();
+        let leaves: Vec<H256> = receipts.iter().map(receipt_leaf).collect();
+        let proof = merkle::prove(&leaves, loc.index as usize)
+            .ok_or_else(|| RpcError::internal("receipt index out of range"))?;
+        Ok(json!(ReceiptProof {
+            header,
+            receipt,
+            proof,
+        }))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::tests::MockBackend;
+    use crate::jsonrpc::handle_request;
+
+    #[test]
+    fn account_proof_verifies_offline() {
+        let mut m = Methods::new();
+        register_proof_api(&mut m, Arc::new(MockBackend::default()));
+        let addr = Address([9; 20]);
+        let req = json!({"jsonrpc": "2.0", "id": 1, "method": "ark_getAccountProof", "params": [addr, "latest"]});
+        let resp = handle_request(&m, req).unwrap();
+        let result = &resp["result"];
+        assert_eq!(result["account"]["balance"], "0");
+
+        // 客户端侧：反序列化证明并相对区块头的状态根校验
+        let proof: StateProof = serde_json::from_value(result["proof"].clone()).unwrap();
+        let root: H256 = serde_json::from_value(result["state_root"].clone()).unwrap();
+        assert_eq!(proof.verify_account(&root, &addr).unwrap().nonce, 0);
+        assert!(proof.verify_account(&root, &Address([8; 20])).is_err());
+
+        let req = json!({"jsonrpc": "2.0", "id": 2, "method": "ark_getTransactionProof", "params": [H256::ZERO]});
+        assert_eq!(handle_request(&m, req).unwrap()["result"], Value::Null);
+    }
+}
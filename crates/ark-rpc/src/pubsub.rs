@@ -0,0 +1,214 @@
+//! 订阅：链事件总线与单连接订阅表。
+//!
+//! 节点在出块 / 交易入池 / 最终确认时向 EventBus 发布事件；每个 WebSocket 连接按自身订阅
+//! 把事件转换为 `ark_subscription` 通知。
+use crate::api::{HeaderView, LogView};
+use crate::error::{RpcError, LIMIT_EXCEEDED};
+use crate::methods::Params;
+use ark_types::{LogFilter, H256};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+pub const NOTIFICATION_METHOD: &str = "ark_subscription";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainEvent {
+    NewHead(Box<HeaderView>),
+    /// 同一区块内的日志（按 log_index 升序）
+    Logs(Vec<LogView>),
+    PendingTransaction(H256),
+    Finalized {
+        height: u64,
+        hash: H256,
+    },
+}
+
+/// 链事件广播；接收端落后超过容量时会收到 Lagged。
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<Arc<ChainEvent>>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            tx: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// 发布事件；没有订阅者时直接丢弃。
+    pub fn publish(&self, event: ChainEvent) {
+        let _ = self.tx.send(Arc::new(event));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<ChainEvent>> {
+        self.tx.subscribe()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubscriptionKind {
+    NewHeads,
+    Logs(LogFilter),
+    PendingTransactions,
+    Finality,
+}
+
+impl SubscriptionKind {
+    /// `["newHeads"]`、`["logs", {filter}]`、`["pendingTransactions"]`、`["finality"]`
+    pub fn from_params(params: &Params) -> Result<Self, RpcError> {
+        let name: String = params.required(0, "kind")?;
+        Ok(match name.as_str() {
+            "newHeads" => SubscriptionKind::NewHeads,
+            "logs" => SubscriptionKind::Logs(params.optional(1, "filter")?.unwrap_or_default()),
+            "pendingTransactions" => SubscriptionKind::PendingTransactions,
+            "finality" => SubscriptionKind::Finality,
+            other => {
+                return Err(RpcError::invalid_params(format!(
+                    "unknown subscription `{other}`"
+                )))
+            }
+        })
+    }
+
+    fn results(&self, event: &ChainEvent) -> Vec<Value> {
+        match (self, event) {
+            (SubscriptionKind::NewHeads, ChainEvent::NewHead(h)) => vec![json!(h)],
+            (SubscriptionKind::Logs(filter), ChainEvent::Logs(logs)) => logs
+                .iter()
+                .filter(|l| filter.matches(&l.log))
+                .map(|l| json!(l))
+                .collect(),
+            (SubscriptionKind::PendingTransactions, ChainEvent::PendingTransaction(h)) => {
+                vec![json!(h)]
+            }
+            (SubscriptionKind::Finality, ChainEvent::Finalized { height, hash }) => {
+                vec![json!({ "height": height, "hash": hash })]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// 单个连接的订阅表。
+#[derive(Debug)]
+pub struct Subscriptions {
+    max: usize,
+    next_id: u64,
+    subs: BTreeMap<String, SubscriptionKind>,
+}
+
+impl Subscriptions {
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            next_id: 1,
+            subs: BTreeMap::new(),
+        }
+    }
+
+    pub fn subscribe(&mut self, kind: SubscriptionKind) -> Result<String, RpcError> {
+        if self.subs.len() >= self.max {
+            return Err(RpcError::new(
+                LIMIT_EXCEEDED,
+                format!("subscription limit {} reached", self.max),
+            ));
+        }
+        let id = format!("0x{:016x}", self.next_id);
+        self.next_id += 1;
+        self.subs.insert(id.clone(), kind);
+        Ok(id)
+    }
+
+    pub fn unsubscribe(&mut self, id: &str) -> bool {
+        self.subs.remove(id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subs.is_empty()
+    }
+
+    /// 事件对应的全部通知消息。
+    pub fn notifications(&self, event: &ChainEvent) -> Vec<Value> {
+        self.subs
+            .iter()
+            .flat_map(|(id, kind)| {
+                kind.results(event).into_iter().map(move |result| {
+                    json!({
+                        "jsonrpc": "2.0",
+                        "method": NOTIFICATION_METHOD,
+                        "params": { "subscription": id, "result": result },
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_types::{Address, Log};
+
+    fn log_view(address: Address, log_index: u32) -> LogView {
+        LogView {
+            log: Log {
+                address,
+                topics: Vec::new(),
+                data: Vec::new(),
+            },
+            block_height: 1,
+            tx_hash: H256::ZERO,
+            tx_index: 0,
+            log_index,
+        }
+    }
+
+    #[test]
+    fn routes_events_to_matching_subscriptions_with_cap() {
+        let mut subs = Subscriptions::new(2);
+        let heads = subs.subscribe(SubscriptionKind::NewHeads).unwrap();
+        let params = Params(json!(["logs", { "address": Address([1; 20]).to_string() }]));
+        let logs = subs
+            .subscribe(SubscriptionKind::from_params(&params).unwrap())
+            .unwrap();
+        assert_eq!(
+            subs.subscribe(SubscriptionKind::Finality).unwrap_err().code,
+            LIMIT_EXCEEDED
+        );
+
+        let event = ChainEvent::Logs(vec![
+            log_view(Address([1; 20]), 0),
+            log_view(Address([2; 20]), 1),
+        ]);
+        let out = subs.notifications(&event);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0]["params"]["subscription"], json!(logs));
+        assert_eq!(out[0]["params"]["result"]["log_index"], 0);
+
+        let head = ChainEvent::NewHead(Box::new(HeaderView::new(Default::default())));
+        assert_eq!(
+            subs.notifications(&head)[0]["params"]["subscription"],
+            json!(heads)
+        );
+        assert!(subs.unsubscribe(&heads));
+        assert!(subs.notifications(&head).is_empty());
+        assert!(!subs.unsubscribe(&heads));
+    }
+}
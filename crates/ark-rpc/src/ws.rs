@@ -0,0 +1,457 @@
+//! JSON-RPC over WebSocket（RFC 6455，服务端）：普通方法调用 + `ark_subscribe` / `ark_unsubscribe`。
+//!
+//! - 握手在 hyper 上完成（以便做访问控制），升级后的连接交给 tokio-tungstenite 处理帧协议
+//! - 每个连接一个读任务与一个写任务（有界发送队列）
+//! - 请求响应在队列满时等待；订阅通知用 try_send，队列满或广播落后即以 1013 关闭连接，
+//!   避免慢客户端拖住事件总线
+//! - 连接数、每连接订阅数与单条消息大小均有上限
+//! - 握手时做 Origin 校验与鉴权（见 `access`），之后每条消息扣一次限额
+use crate::access::{Access, Caller};
+use crate::error::{RpcError, LIMIT_EXCEEDED};
+use crate::http::denied_response;
+use crate::jsonrpc::{error_response, handle_body};
+use crate::methods::{Methods, Params};
+use crate::pubsub::{ChainEvent, EventBus, SubscriptionKind, Subscriptions};
+use futures_util::{SinkExt, StreamExt};
+use hyper::header::{HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::json;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::error::{CapacityError, Error as WsError, ProtocolError};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role, WebSocketConfig};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+#[derive(Clone, Debug)]
+pub struct WsConfig {
+    pub addr: SocketAddr,
+    pub max_connections: usize,
+    pub max_subscriptions: usize,
+    /// 单条（重组后）消息最大字节数
+    pub max_message_bytes: usize,
+    /// 每连接发送队列长度
+    pub queue_len: usize,
+    pub max_batch: usize,
+    pub access: Arc<Access>,
+}
+
+impl WsConfig {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            max_connections: 100,
+            max_subscriptions: 32,
+            max_message_bytes: 1 << 20,
+            queue_len: 256,
+            max_batch: 100,
+            access: Arc::new(Access::default()),
+        }
+    }
+}
+
+struct Shared {
+    config: WsConfig,
+    /// 未鉴权调用方的方法表（admin 方法返回 UNAUTHORIZED）
+    public: Methods,
+    admin: Arc<Methods>,
+    bus: EventBus,
+    active: AtomicUsize,
+}
+
+/// 活跃连接计数，连接结束时自动减一。
+struct ConnGuard(Arc<Shared>);
+
+impl Drop for ConnGuard {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub async fn serve_ws(
+    config: WsConfig,
+    methods: Arc<Methods>,
+    bus: EventBus,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+    let (addr, server) = bind_ws(config, methods, bus, shutdown)?;
+    tracing::info!(%addr, "json-rpc websocket server listening");
+    server.await
+}
+
+pub fn bind_ws(
+    config: WsConfig,
+    methods: Arc<Methods>,
+    bus: EventBus,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<(SocketAddr, impl Future<Output = anyhow::Result<()>>)> {
+    let builder = hyper::Server::try_bind(&config.addr)?;
+    let shared = Arc::new(Shared {
+        public: config.access.public_view(&methods),
+        admin: methods,
+        config,
+        bus,
+        active: AtomicUsize::new(0),
+    });
+    let make = make_service_fn(move |conn: &AddrStream| {
+        let ip = conn.remote_addr().ip();
+        let shared = shared.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| upgrade(req, ip, shared.clone()))) }
+    });
+    let server = builder.serve(make);
+    let addr = server.local_addr();
+    let server = server.with_graceful_shutdown(shutdown);
+    Ok((addr, async move { server.await.map_err(Into::into) }))
+}
+
+async fn upgrade(
+    req: Request<Body>,
+    ip: IpAddr,
+    shared: Arc<Shared>,
+) -> Result<Response<Body>, Infallible> {
+    let headers = req.headers();
+    let has_token = |name, token: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .any(|t| t.trim().eq_ignore_ascii_case(token))
+    };
+    let is_upgrade = req.method() == Method::GET
+        && has_token(CONNECTION, "upgrade")
+        && has_token(UPGRADE, "websocket");
+    let Some(key) = headers.get(SEC_WEBSOCKET_KEY).filter(|_| is_upgrade) else {
+        return Ok(status(
+            StatusCode::BAD_REQUEST,
+            "expected websocket upgrade",
+        ));
+    };
+    if headers
+        .get("sec-websocket-version")
+        .map(|v| v.as_bytes() != b"13")
+        .unwrap_or(true)
+    {
+        let mut resp = status(
+            StatusCode::UPGRADE_REQUIRED,
+            "websocket version 13 required",
+        );
+        resp.headers_mut()
+            .insert("sec-websocket-version", HeaderValue::from_static("13"));
+        return Ok(resp);
+    }
+    let access = &shared.config.access;
+    let caller = match access
+        .check_origin(headers)
+        .and_then(|_| access.authenticate(headers, ip))
+    {
+        Ok(caller) => caller,
+        Err(denied) => return Ok(denied_response(&denied)),
+    };
+    if shared.active.fetch_add(1, Ordering::SeqCst) >= shared.config.max_connections {
+        shared.active.fetch_sub(1, Ordering::SeqCst);
+        return Ok(status(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "too many connections",
+        ));
+    }
+    let guard = ConnGuard(shared.clone());
+    let accept = derive_accept_key(key.as_bytes());
+
+    tokio::spawn(async move {
+        match hyper::upgrade::on(req).await {
+            Ok(io) => run_connection(io, guard, caller).await,
+            Err(e) => tracing::debug!(error = %e, "websocket upgrade failed"),
+        }
+    });
+
+    let mut resp = Response::new(Body::empty());
+    *resp.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    let h = resp.headers_mut();
+    h.insert(UPGRADE, HeaderValue::from_static("websocket"));
+    h.insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+    h.insert(
+        SEC_WEBSOCKET_ACCEPT,
+        HeaderValue::from_str(&accept).expect("base64 is a valid header value"),
+    );
+    Ok(resp)
+}
+
+fn status(code: StatusCode, msg: &'static str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(msg));
+    *resp.status_mut() = code;
+    resp
+}
+
+enum Incoming {
+    Text(String),
+    Close,
+    Error(CloseCode, &'static str),
+}
+
+fn close(code: CloseCode, reason: &'static str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    }))
+}
+
+async fn run_connection<S>(io: S, guard: ConnGuard, caller: Caller)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let shared = guard.0.clone();
+    let config = &shared.config;
+    let ws_config = WebSocketConfig {
+        max_message_size: Some(config.max_message_bytes),
+        max_frame_size: Some(config.max_message_bytes),
+        ..WebSocketConfig::default()
+    };
+    let stream = WebSocketStream::from_raw_socket(io, Role::Server, Some(ws_config)).await;
+    let (sink, stream) = stream.split();
+    let (out_tx, out_rx) = mpsc::channel(config.queue_len);
+    let (in_tx, mut in_rx) = mpsc::channel(16);
+    let writer = tokio::spawn(write_loop(sink, out_rx));
+    let reader = tokio::spawn(read_loop(stream, in_tx));
+
+    // 每连接一份方法表，附加绑定本连接订阅表的 subscribe / unsubscribe
+    let subs = Arc::new(Mutex::new(Subscriptions::new(config.max_subscriptions)));
+    let mut methods = if caller.admin {
+        (*shared.admin).clone()
+    } else {
+        shared.public.clone()
+    };
+    let s = subs.clone();
+    methods.register("ark_subscribe", move |p: Params| {
+        let kind = SubscriptionKind::from_params(&p)?;
+        s.lock().unwrap().subscribe(kind).map(|id| json!(id))
+    });
+    let s = subs.clone();
+    methods.register("ark_unsubscribe", move |p: Params| {
+        let id: String = p.required(0, "id")?;
+        Ok(json!(s.lock().unwrap().unsubscribe(&id)))
+    });
+    let methods = Arc::new(methods);
+
+    // 有订阅时才接收广播，避免空闲连接积压
+    let mut events: Option<broadcast::Receiver<Arc<ChainEvent>>> = None;
+    let close_with = loop {
+        tokio::select! {
+            incoming = in_rx.recv() => match incoming {
+                None => break None,
+                Some(Incoming::Close) => break None,
+                Some(Incoming::Error(code, reason)) => break Some((code, reason)),
+                Some(Incoming::Text(text)) => {
+                    if config.access.throttle(&caller).is_err() {
+                        let err = RpcError::new(LIMIT_EXCEEDED, "rate limit exceeded");
+                        let resp = error_response(serde_json::Value::Null, err);
+                        if out_tx.send(Message::Text(resp.to_string())).await.is_err() {
+                            break None;
+                        }
+                        continue;
+                    }
+                    let m = methods.clone();
+                    let max_batch = config.max_batch;
+                    let resp = tokio::task::spawn_blocking(move || {
+                        handle_body(&m, text.as_bytes(), max_batch)
+                    })
+                    .await;
+                    let Ok(resp) = resp else {
+                        break Some((CloseCode::Again, "internal error"));
+                    };
+                    if let Some(v) = resp {
+                        if out_tx.send(Message::Text(v.to_string())).await.is_err() {
+                            break None;
+                        }
+                    }
+                    let active = !subs.lock().unwrap().is_empty();
+                    if active && events.is_none() {
+                        events = Some(shared.bus.subscribe());
+                    } else if !active {
+                        events = None;
+                    }
+                }
+            },
+            event = recv_event(&mut events) => match event {
+                Ok(event) => {
+                    let notes = subs.lock().unwrap().notifications(&event);
+                    let full = notes
+                        .into_iter()
+                        .any(|n| out_tx.try_send(Message::Text(n.to_string())).is_err());
+                    if full {
+                        break Some((CloseCode::Again, "subscriber too slow"));
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    break Some((CloseCode::Again, "subscriber too slow"));
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    break Some((CloseCode::Away, "server shutting down"));
+                }
+            },
+        }
+    };
+
+    reader.abort();
+    if let Some((code, reason)) = close_with {
+        // 队列已满时放弃关闭帧，直接断开
+        let _ = out_tx.try_send(close(code, reason));
+    }
+    drop(out_tx);
+    let _ = writer.await;
+}
+
+async fn recv_event(
+    events: &mut Option<broadcast::Receiver<Arc<ChainEvent>>>,
+) -> Result<Arc<ChainEvent>, broadcast::error::RecvError> {
+    match events {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn write_loop<W>(mut sink: W, mut rx: mpsc::Receiver<Message>)
+where
+    W: futures_util::Sink<Message> + Unpin,
+{
+    while let Some(msg) = rx.recv().await {
+        let closing = msg.is_close();
+        if sink.send(msg).await.is_err() || closing {
+            break;
+        }
+    }
+    let _ = sink.close().await;
+}
+
+/// 读取客户端消息；ping / pong 与对端发起的关闭握手由 tungstenite 自动应答。
+async fn read_loop<R>(mut stream: R, tx: mpsc::Sender<Incoming>)
+where
+    R: futures_util::Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    while let Some(msg) = stream.next().await {
+        let incoming = match msg {
+            Ok(Message::Text(text)) => Incoming::Text(text),
+            Ok(Message::Binary(_)) => {
+                Incoming::Error(CloseCode::Unsupported, "binary not supported")
+            }
+            Ok(Message::Close(_)) => Incoming::Close,
+            Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => continue,
+            Err(WsError::Capacity(CapacityError::MessageTooLong { .. })) => {
+                Incoming::Error(CloseCode::Size, "message too large")
+            }
+            Err(WsError::Utf8) => Incoming::Error(CloseCode::Invalid, "invalid utf-8"),
+            Err(WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => return,
+            Err(WsError::Protocol(_)) => Incoming::Error(CloseCode::Protocol, "protocol error"),
+            Err(_) => return,
+        };
+        let done = matches!(incoming, Incoming::Close | Incoming::Error(..));
+        if tx.send(incoming).await.is_err() || done {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::HeaderView;
+    use serde_json::Value;
+    use tokio::net::TcpStream;
+
+    type Client = WebSocketStream<TcpStream>;
+
+    async fn send_text(ws: &mut Client, text: &str) {
+        ws.send(Message::Text(text.into())).await.unwrap();
+    }
+
+    async fn recv_json(ws: &mut Client) -> Value {
+        match ws.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+
+    async fn connect(addr: SocketAddr) -> Client {
+        let sock = TcpStream::connect(addr).await.unwrap();
+        // 客户端握手会校验 Sec-WebSocket-Accept
+        let (ws, resp) = tokio_tungstenite::client_async(format!("ws://{addr}/"), sock)
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 101);
+        ws
+    }
+
+    #[tokio::test]
+    async fn subscribe_receive_and_unsubscribe() {
+        let bus = EventBus::new(16);
+        let methods = Arc::new(crate::api::tests::methods());
+        let config = WsConfig::new("127.0.0.1:0".parse().unwrap());
+        let (addr, server) = bind_ws(config, methods, bus.clone(), std::future::pending()).unwrap();
+        tokio::spawn(server);
+
+        let mut ws = connect(addr).await;
+        send_text(
+            &mut ws,
+            r#"{"jsonrpc":"2.0","id":1,"method":"ark_chainId"}"#,
+        )
+        .await;
+        assert_eq!(recv_json(&mut ws).await["result"], "ark-test");
+
+        send_text(
+            &mut ws,
+            r#"{"jsonrpc":"2.0","id":2,"method":"ark_subscribe","params":["newHeads"]}"#,
+        )
+        .await;
+        let id = recv_json(&mut ws).await["result"].clone();
+        assert!(id.is_string());
+
+        bus.publish(ChainEvent::PendingTransaction(ark_types::H256::ZERO));
+        let header = ark_types::BlockHeader {
+            height: 7,
+            ..Default::default()
+        };
+        bus.publish(ChainEvent::NewHead(Box::new(HeaderView::new(header))));
+        let note = recv_json(&mut ws).await;
+        assert_eq!(note["method"], "ark_subscription");
+        assert_eq!(note["params"]["subscription"], id);
+        assert_eq!(note["params"]["result"]["height"], 7);
+
+        let unsub =
+            format!(r#"{{"jsonrpc":"2.0","id":3,"method":"ark_unsubscribe","params":[{id}]}}"#);
+        send_text(&mut ws, &unsub).await;
+        assert_eq!(recv_json(&mut ws).await["result"], true);
+    }
+
+    #[tokio::test]
+    async fn oversized_and_binary_messages_close_the_connection() {
+        let mut config = WsConfig::new("127.0.0.1:0".parse().unwrap());
+        config.max_message_bytes = 64;
+        let methods = Arc::new(crate::api::tests::methods());
+        let (addr, server) =
+            bind_ws(config, methods, EventBus::new(4), std::future::pending()).unwrap();
+        tokio::spawn(server);
+
+        let mut ws = connect(addr).await;
+        ws.send(Message::Binary(vec![1, 2, 3])).await.unwrap();
+        match ws.next().await.unwrap().unwrap() {
+            Message::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Unsupported),
+            other => panic!("unexpected message {other:?}"),
+        }
+
+        let mut ws = connect(addr).await;
+        ws.send(Message::Text("x".repeat(100))).await.unwrap();
+        match ws.next().await.unwrap().unwrap() {
+            Message::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Size),
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! 可移植区块文件：在节点之间迁移区块或离线备份，导入时逐块重放校验。
+//!
+//! 文件格式：magic `ARKBLKS1` | genesis_hash(32B) | 区块 ...
+//! 区块格式：len(u32be) | 区块 JSON，按高度连续递增；不含回执（导入时重新执行得到）。
+use crate::chain::ChainStore;
+use ark_types::{Block, H256};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"ARKBLKS1";
+/// 单个区块编码长度上限，防止损坏文件导致超大分配
+const MAX_BLOCK_LEN: u32 = 64 << 20;
+
+/// 将 [from, to] 高度区间的区块写入 path（先写临时文件再原子替换），返回写入的区块数。
+pub fn export_blocks(store: &ChainStore, from: u64, to: u64, path: &Path) -> anyhow::Result<u64> {
+    let genesis_hash = store
+        .genesis_hash()
+        .ok_or_else(|| anyhow::anyhow!("database not initialized"))?;
+    anyhow::ensure!(from <= to, "empty range {from}..={to}");
+    let tmp = path.with_extension("tmp");
+    {
+        let mut w = BufWriter::new(File::create(&tmp)?);
+        w.write_all(MAGIC)?;
+        w.write_all(genesis_hash.as_bytes())?;
+        for height in from..=to {
+            let block = store
+                .block(height)?
+                .ok_or_else(|| anyhow::anyhow!("block {height} not found"))?;
+            let raw = serde_json::to_vec(&block)?;
+            w.write_all(&(raw.len() as u32).to_be_bytes())?;
+            w.write_all(&raw)?;
+        }
+        w.flush()?;
+        w.get_ref().sync_all()?;
+    }
+    fs::rename(&tmp, path)?;
+    Ok(to - from + 1)
+}
+
+/// 顺序读取区块文件。
+pub struct BlockReader {
+    genesis_hash: H256,
+    r: BufReader<File>,
+}
+
+impl BlockReader {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut r = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        anyhow::ensure!(&magic == MAGIC, "not a block file: {}", path.display());
+        let mut hash = [0u8; 32];
+        r.read_exact(&mut hash)?;
+        Ok(Self {
+            genesis_hash: H256(hash),
+            r,
+        })
+    }
+
+    pub fn genesis_hash(&self) -> H256 {
+        self.genesis_hash
+    }
+
+    /// 下一个区块；文件在区块边界处结束时返回 None。
+    pub fn next_block(&mut self) -> anyhow::Result<Option<Block>> {
+        let mut len = [0u8; 4];
+        match self.r.read(&mut len[..1])? {
+            0 => return Ok(None),
+            _ => self.r.read_exact(&mut len[1..])?,
+        }
+        let len = u32::from_be_bytes(len);
+        anyhow::ensure!(len <= MAX_BLOCK_LEN, "block entry too large ({len} bytes)");
+        let mut raw = vec![0u8; len as usize];
+        self.r.read_exact(&mut raw)?;
+        Ok(Some(serde_json::from_slice(&raw)?))
+    }
+}
+
+impl Iterator for BlockReader {
+    type Item = anyhow::Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_block().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_types::{Address, BlockHeader};
+
+    #[test]
+    fn export_then_read_back() {
+        let block = |height, parent_hash| Block {
+            header: BlockHeader {
+                height,
+                parent_hash,
+                timestamp_ms: height,
+                proposer: Address::ZERO,
+                state_root: H256::ZERO,
+                tx_root: H256::ZERO,
+                receipts_root: H256::ZERO,
+                gas_limit: 1,
+                gas_used: 0,
+                base_fee: 1,
+                logs_bloom: Default::default(),
+            },
+            txs: Vec::new(),
+        };
+        let mut store = ChainStore::in_memory();
+        let g = block(0, H256::ZERO);
+        store.init_genesis(&g, Vec::new()).unwrap();
+        let b1 = block(1, g.hash());
+        let b2 = block(2, b1.hash());
+        store.commit_block(&b1, &[], Vec::new()).unwrap();
+        store.commit_block(&b2, &[], Vec::new()).unwrap();
+
+        let path = std::env::temp_dir().join(format!("ark-blocks-{}.arkblk", std::process::id()));
+        assert_eq!(export_blocks(&store, 1, 2, &path).unwrap(), 2);
+        assert!(export_blocks(&store, 1, 3, &path).is_err());
+        let reader = BlockReader::open(&path).unwrap();
+        assert_eq!(reader.genesis_hash(), g.hash());
+        let blocks: Vec<Block> = reader.collect::<anyhow::Result<_>>().unwrap();
+        assert_eq!(blocks, vec![b1, b2]);
+
+        // 截断在区块中间：报错而非静默结束
+        let raw = fs::read(&path).unwrap();
+        fs::write(&path, &raw[..raw.len() - 3]).unwrap();
+        let mut reader = BlockReader::open(&path).unwrap();
+        assert!(reader.next_block().unwrap().is_some());
+        assert!(reader.next_block().is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,424 @@
+//! 链数据存储：区块头 / 区块体 / 回执 / 哈希索引 / 交易索引 / 日志索引 / 最新状态。
+//!
+//! 高度键统一为 u64 大端，保证按高度有序遍历。
+//! 日志查询：指定合约地址时按日志索引定位区块，否则逐块用区块头的 Bloom 预筛，再读取回执精确匹配。
+use crate::db::{Column, Db, WriteBatch};
+use crate::metrics;
+use ark_types::log::OneOrMany;
+use ark_types::{Address, Block, BlockHeader, Log, LogFilter, Receipt, SignedTransaction, H256};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+const META_HEAD: &[u8] = b"head";
+const META_GENESIS: &[u8] = b"genesis_hash";
+
+/// 交易在链上的位置。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxLocation {
+    pub height: u64,
+    pub index: u32,
+}
+
+/// 带位置信息的日志。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedLog {
+    pub log: Log,
+    pub height: u64,
+    pub tx_hash: H256,
+    pub tx_index: u32,
+    /// 区块内序号
+    pub log_index: u32,
+}
+
+pub struct ChainStore {
+    db: Db,
+}
+
+impl ChainStore {
+    pub fn open(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let store = Self { db: Db::open(dir)? };
+        if let Some(head) = store.head() {
+            store.report_head(head);
+        }
+        Ok(store)
+    }
+
+    pub fn in_memory() -> Self {
+        Self {
+            db: Db::in_memory(),
+        }
+    }
+
+    pub fn db(&self) -> &Db {
+        &self.db
+    }
+
+    pub fn db_mut(&mut self) -> &mut Db {
+        &mut self.db
+    }
+
+    pub fn genesis_hash(&self) -> Option<H256> {
+        let raw = self.db.get(Column::Meta, META_GENESIS)?;
+        Some(H256(raw.try_into().ok()?))
+    }
+
+    /// 最新已存储区块高度。
+    pub fn head(&self) -> Option<u64> {
+        let raw = self.db.get(Column::Meta, META_HEAD)?;
+        Some(u64::from_be_bytes(raw.try_into().ok()?))
+    }
+
+    /// 写入 0 号区块与创世状态，并刷盘。
+    pub fn init_genesis(
+        &mut self,
+        block: &Block,
+        state_entries: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(block.height() == 0, "genesis block must have height 0");
+        anyhow::ensure!(self.head().is_none(), "database already initialized");
+        let mut batch = WriteBatch::new();
+        self.block_ops(&mut batch, block, &[]);
+        self.state_ops(&mut batch, state_entries);
+        batch.put(Column::Meta, META_GENESIS, block.hash().0.to_vec());
+        self.db.write(batch)?;
+        self.db.flush()?;
+        self.report_head(0);
+        Ok(())
+    }
+
+    /// 从快照恢复空库：只写入快照高度的区块头与状态，更早的区块不可查询。
+    pub fn init_from_snapshot(
+        &mut self,
+        genesis_hash: H256,
+        header: &BlockHeader,
+        state_entries: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(self.head().is_none(), "database already initialized");
+        let h = header.height.to_be_bytes();
+        let mut batch = WriteBatch::new();
+        batch.put(Column::Headers, h, json(header));
+        batch.put(Column::BlockIndex, header.hash().0, h);
+        batch.put(Column::Meta, META_HEAD, h);
+        batch.put(Column::Meta, META_GENESIS, genesis_hash.0.to_vec());
+        self.state_ops(&mut batch, state_entries);
+        self.db.write(batch)?;
+        self.db.flush()?;
+        self.report_head(header.height);
+        Ok(())
+    }
+
+    /// 追加区块、回执与执行后的状态（同一批次原子写入）。
+    pub fn commit_block(
+        &mut self,
+        block: &Block,
+        receipts: &[Receipt],
+        state_entries: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::new();
+        self.block_ops(&mut batch, block, receipts);
+        self.state_ops(&mut batch, state_entries);
+        self.db.write(batch)?;
+        self.report_head(block.height());
+        Ok(())
+    }
+
+    fn report_head(&self, height: u64) {
+        if self.db.path().is_some() {
+            metrics::HEAD_HEIGHT.set(height as i64);
+        }
+    }
+
+    fn block_ops(&self, batch: &mut WriteBatch, block: &Block, receipts: &[Receipt]) {
+        let h = block.height().to_be_bytes();
+        batch.put(Column::Headers, h, json(&block.header));
+        batch.put(Column::Bodies, h, json(&block.txs));
+        batch.put(Column::Receipts, h, json(&receipts));
+        batch.put(Column::BlockIndex, block.hash().0, h);
+        for (i, tx) in block.txs.iter().enumerate() {
+            let mut loc = h.to_vec();
+            loc.extend_from_slice(&(i as u32).to_be_bytes());
+            batch.put(Column::TxIndex, tx.hash().0, loc);
+        }
+        let emitters: std::collections::BTreeSet<&Address> = receipts
+            .iter()
+            .flat_map(|r| r.logs.iter().map(|l| &l.address))
+            .collect();
+        for addr in emitters {
+            batch.put(
+                Column::LogIndex,
+                log_index_key(addr, block.height()),
+                Vec::new(),
+            );
+        }
+        batch.put(Column::Meta, META_HEAD, h);
+    }
+
+    fn state_ops(&self, batch: &mut WriteBatch, entries: Vec<(Vec<u8>, Vec<u8>)>) {
+        let keep: std::collections::BTreeSet<&[u8]> =
+            entries.iter().map(|(k, _)| k.as_slice()).collect();
+        for (k, _) in self.db.iter(Column::State) {
+            if !keep.contains(k) {
+                batch.delete(Column::State, k);
+            }
+        }
+        for (k, v) in entries {
+            if self.db.get(Column::State, &k) != Some(v.as_slice()) {
+                batch.put(Column::State, k, v);
+            }
+        }
+    }
+
+    pub fn header(&self, height: u64) -> anyhow::Result<Option<BlockHeader>> {
+        decode(self.db.get(Column::Headers, &height.to_be_bytes()))
+    }
+
+    pub fn block(&self, height: u64) -> anyhow::Result<Option<Block>> {
+        let Some(header) = self.header(height)? else {
+            return Ok(None);
+        };
+        let txs: Vec<SignedTransaction> =
+            decode(self.db.get(Column::Bodies, &height.to_be_bytes()))?.unwrap_or_default();
+        Ok(Some(Block { header, txs }))
+    }
+
+    pub fn height_of(&self, hash: &H256) -> Option<u64> {
+        let raw = self.db.get(Column::BlockIndex, hash.as_bytes())?;
+        Some(u64::from_be_bytes(raw.try_into().ok()?))
+    }
+
+    pub fn block_by_hash(&self, hash: &H256) -> anyhow::Result<Option<Block>> {
+        match self.height_of(hash) {
+            Some(h) => self.block(h),
+            None => Ok(None),
+        }
+    }
+
+    pub fn receipts(&self, height: u64) -> anyhow::Result<Option<Vec<Receipt>>> {
+        decode(self.db.get(Column::Receipts, &height.to_be_bytes()))
+    }
+
+    pub fn tx_location(&self, hash: &H256) -> Option<TxLocation> {
+        let raw = self.db.get(Column::TxIndex, hash.as_bytes())?;
+        if raw.len() != 12 {
+            return None;
+        }
+        Some(TxLocation {
+            height: u64::from_be_bytes(raw[..8].try_into().ok()?),
+            index: u32::from_be_bytes(raw[8..].try_into().ok()?),
+        })
+    }
+
+    pub fn transaction(
+        &self,
+        hash: &H256,
+    ) -> anyhow::Result<Option<(SignedTransaction, TxLocation)>> {
+        let Some(loc) = self.tx_location(hash) else {
+            return Ok(None);
+        };
+        let block = self.block(loc.height)?;
+        Ok(block
+            .and_then(|b| b.txs.into_iter().nth(loc.index as usize))
+            .map(|tx| (tx, loc)))
+    }
+
+    pub fn receipt(&self, hash: &H256) -> anyhow::Result<Option<(Receipt, TxLocation)>> {
+        let Some(loc) = self.tx_location(hash) else {
+            return Ok(None);
+        };
+        let receipts = self.receipts(loc.height)?.unwrap_or_default();
+        Ok(receipts
+            .into_iter()
+            .nth(loc.index as usize)
+            .map(|r| (r, loc)))
+    }
+
+    /// [from, to] 区块内匹配 filter 的日志，按区块与区块内序号排列，最多返回 limit 条。
+    pub fn logs(
+        &self,
+        filter: &LogFilter,
+        from: u64,
+        to: u64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<IndexedLog>> {
+        let heights: Vec<u64> = match &filter.address {
+            Some(addresses) => {
+                let mut heights = std::collections::BTreeSet::new();
+                let mut collect = |addr: &Address| {
+                    let start = log_index_key(addr, from);
+                    let end = log_index_key(addr, to);
+                    heights.extend(
+                        self.db
+                            .iter_prefix(Column::LogIndex, addr.as_bytes())
+                            .map(|(k, _)| k)
+                            .skip_while(|k| *k < start.as_slice())
+                            .take_while(|k| *k <= end.as_slice())
+                            .map(|k| {
+                                u64::from_be_bytes(k[20..].try_into().expect("8-byte height"))
+                            }),
+                    );
+                };
+                match addresses {
+                    OneOrMany::One(addr) => collect(addr),
+                    OneOrMany::Many(addrs) => addrs.iter().for_each(collect),
+                }
+                heights.into_iter().collect()
+            }
+            None => (from..=to).collect(),
+        };
+
+        let mut out = Vec::new();
+        for height in heights {
+            let Some(header) = self.header(height)? else {
+                continue;
+            };
+            if !header.logs_bloom.matches_filter(filter) {
+                continue;
+            }
+            let receipts = self.receipts(height)?.unwrap_or_default();
+            let mut log_index = 0u32;
+            for (tx_index, receipt) in receipts.iter().enumerate() {
+                for log in &receipt.logs {
+                    if filter.matches(log) {
+                        if out.len() == limit {
+                            return Ok(out);
+                        }
+                        out.push(IndexedLog {
+                            log: log.clone(),
+                            height,
+                            tx_hash: receipt.tx_hash,
+                            tx_index: tx_index as u32,
+                            log_index,
+                        });
+                    }
+                    log_index += 1;
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// 最新状态条目（按键升序）。
+    pub fn state_entries(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.db
+            .iter(Column::State)
+            .map(|(k, v)| (k.to_vec(), v.to_vec()))
+            .collect()
+    }
+
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        self.db.flush()
+    }
+}
+
+fn log_index_key(addr: &Address, height: u64) -> Vec<u8> {
+    [&addr.0[..], &height.to_be_bytes()].concat()
+}
+
+fn json<T: serde::Serialize>(v: &T) -> Vec<u8> {
+    serde_json::to_vec(v).expect("chain data serializes")
+}
+
+fn decode<T: DeserializeOwned>(raw: Option<&[u8]>) -> anyhow::Result<Option<T>> {
+    raw.map(serde_json::from_slice)
+        .transpose()
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_types::Address;
+
+    fn block(height: u64, parent: H256) -> Block {
+        Block {
+            header: BlockHeader {
+                height,
+                parent_hash: parent,
+                timestamp_ms: 0,
+                proposer: Address::ZERO,
+                state_root: H256::ZERO,
+                tx_root: H256::ZERO,
+                receipts_root: H256::ZERO,
+                gas_limit: 1,
+                gas_used: 0,
+                base_fee: 1,
+                logs_bloom: Default::default(),
+            },
+            txs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn genesis_then_blocks_are_indexed() {
+        let mut store = ChainStore::in_memory();
+        let g = block(0, H256::ZERO);
+        store
+            .init_genesis(&g, vec![(b"a1".to_vec(), b"x".to_vec())])
+            .unwrap();
+        assert_eq!(store.genesis_hash(), Some(g.hash()));
+        assert!(store.init_genesis(&g, Vec::new()).is_err());
+
+        let b1 = block(1, g.hash());
+        store
+            .commit_block(&b1, &[], vec![(b"a2".to_vec(), b"y".to_vec())])
+            .unwrap();
+        assert_eq!(store.head(), Some(1));
+        assert_eq!(store.block_by_hash(&b1.hash()).unwrap(), Some(b1));
+        assert_eq!(store.state_entries(), vec![(b"a2".to_vec(), b"y".to_vec())]);
+    }
+
+    #[test]
+    fn logs_are_found_by_address_index_and_bloom() {
+        let log = |addr: u8, topic: u128| Log {
+            address: Address([addr; 20]),
+            topics: vec![H256::from_u128(topic)],
+            data: Vec::new(),
+        };
+        let receipt = |logs: Vec<Log>| Receipt {
+            tx_hash: H256::from_u128(logs.len() as u128),
+            status: ark_types::ExecStatus::Success,
+            gas_used: 0,
+            cumulative_gas_used: 0,
+            effective_gas_price: 0,
+            contract_address: None,
+            revert_reason: None,
+            logs,
+        };
+        let mut store = ChainStore::in_memory();
+        let mut parent = block(0, H256::ZERO);
+        store.init_genesis(&parent, Vec::new()).unwrap();
+        let blocks = [
+            vec![receipt(vec![log(1, 10), log(2, 20)])],
+            vec![],
+            vec![receipt(vec![]), receipt(vec![log(1, 11)])],
+        ];
+        for (i, receipts) in blocks.iter().enumerate() {
+            let mut b = block(i as u64 + 1, parent.hash());
+            b.header.logs_bloom = ark_types::block::logs_bloom(receipts);
+            store.commit_block(&b, receipts, Vec::new()).unwrap();
+            parent = b;
+        }
+
+        let by_addr = LogFilter {
+            address: Some(OneOrMany::One(Address([1; 20]))),
+            topics: Vec::new(),
+        };
+        let found = store.logs(&by_addr, 0, 3, 10).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            (found[1].height, found[1].tx_index, found[1].log_index),
+            (3, 1, 0)
+        );
+        assert_eq!(store.logs(&by_addr, 2, 3, 10).unwrap().len(), 1);
+        assert_eq!(store.logs(&by_addr, 0, 3, 1).unwrap().len(), 1);
+
+        let by_topic = LogFilter {
+            address: None,
+            topics: vec![Some(OneOrMany::One(H256::from_u128(20)))],
+        };
+        let found = store.logs(&by_topic, 0, 3, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].height, found[0].log_index), (1, 1));
+        assert_eq!(found[0].log, log(2, 20));
+    }
+}
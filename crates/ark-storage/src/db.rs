@@ -0,0 +1,497 @@
+//! 列式键值存储：内存有序表 + 追加写日志。
+//!
+//! - 数据按列（Column）分区，每列为有序 BTreeMap
+//! - 写入以 WriteBatch 为单位先追加到 `data.log`，再应用到内存
+//! - 打开时按批次重放日志：只应用校验通过的完整批次；仅当最后一帧的帧头或负载不完整（崩溃时半写）
+//!   且其后没有任何校验通过的帧时才截断丢弃，其余损坏（含完整帧的 crc 不符）一律拒绝打开，
+//!   不静默丢弃已提交的数据
+//! - flush() 刷盘（fsync）；compact() 以当前数据重写日志，回收被覆盖/删除的记录，替换后同步目录
+//!
+//! 日志由批次帧组成：len(u32be) | crc32(u32be) | ops，crc 覆盖 ops，len 为 ops 字节数。
+//! 每条 op：op(1) | column(1) | key_len(u32be) | key | value_len(u32be) | value
+use crate::metrics;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const LOG_FILE: &str = "data.log";
+const OP_PUT: u8 = 1;
+const OP_DELETE: u8 = 2;
+/// 批次帧头：len(4) + crc32(4)
+const FRAME_HEADER: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Column {
+    /// 元数据：head、genesis hash 等
+    Meta = 0,
+    /// 高度 -> 区块头
+    Headers = 1,
+    /// 高度 -> 区块体（交易）
+    Bodies = 2,
+    /// 高度 -> 回执列表
+    Receipts = 3,
+    /// 区块哈希 -> 高度
+    BlockIndex = 4,
+    /// 交易哈希 -> (高度, 序号)
+    TxIndex = 5,
+    /// 最新状态条目
+    State = 6,
+    /// (合约地址, 高度) -> 空：该合约在该区块发出过日志
+    LogIndex = 7,
+}
+
+impl Column {
+    pub const ALL: [Column; 8] = [
+        Column::Meta,
+        Column::Headers,
+        Column::Bodies,
+        Column::Receipts,
+        Column::BlockIndex,
+        Column::TxIndex,
+        Column::State,
+        Column::LogIndex,
+    ];
+
+    fn from_u8(v: u8) -> Option<Column> {
+        Column::ALL.get(v as usize).copied()
+    }
+
+    /// 指标标签用的列名。
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Meta => "meta",
+            Column::Headers => "headers",
+            Column::Bodies => "bodies",
+            Column::Receipts => "receipts",
+            Column::BlockIndex => "block_index",
+            Column::TxIndex => "tx_index",
+            Column::State => "state",
+            Column::LogIndex => "log_index",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Op {
+    Put(Column, Vec<u8>, Vec<u8>),
+    Delete(Column, Vec<u8>),
+}
+
+/// 原子写入批次。
+#[derive(Clone, Debug, Default)]
+pub struct WriteBatch {
+    ops: Vec<Op>,
+}
+
+impl WriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put(&mut self, col: Column, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) {
+        self.ops.push(Op::Put(col, key.into(), value.into()));
+    }
+
+    pub fn delete(&mut self, col: Column, key: impl Into<Vec<u8>>) {
+        self.ops.push(Op::Delete(col, key.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+pub struct Db {
+    dir: Option<PathBuf>,
+    cols: BTreeMap<Column, BTreeMap<Vec<u8>, Vec<u8>>>,
+    /// 各列键值总字节数（随写入增量维护）
+    bytes: BTreeMap<Column, usize>,
+    log: Option<BufWriter<File>>,
+}
+
+impl Db {
+    /// 纯内存数据库（测试 / 临时使用）。
+    pub fn in_memory() -> Self {
+        Self {
+            dir: None,
+            cols: BTreeMap::new(),
+            bytes: BTreeMap::new(),
+            log: None,
+        }
+    }
+
+    /// 打开（不存在则创建）目录下的数据库并重放日志。
+    pub fn open(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(LOG_FILE);
+        let mut db = Self {
+            dir: Some(dir),
+            cols: BTreeMap::new(),
+            bytes: BTreeMap::new(),
+            log: None,
+        };
+        let valid_len = if path.exists() {
+            let mut raw = Vec::new();
+            File::open(&path)?.read_to_end(&mut raw)?;
+            let (batches, valid) = decode_log(&raw).map_err(|at| {
+                anyhow::anyhow!("{}: corrupt batch at offset {at}", path.display())
+            })?;
+            for op in batches.into_iter().flatten() {
+                db.apply(op);
+            }
+            if valid < raw.len() {
+                tracing::warn!(
+                    path = %path.display(),
+                    dropped = raw.len() - valid,
+                    "truncating incomplete trailing log batch"
+                );
+            }
+            valid as u64
+        } else {
+            0
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        file.set_len(valid_len)?;
+        file.seek(SeekFrom::End(0))?;
+        db.log = Some(BufWriter::new(file));
+        db.report_metrics();
+        Ok(db)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    pub fn get(&self, col: Column, key: &[u8]) -> Option<&[u8]> {
+        self.cols.get(&col)?.get(key).map(Vec::as_slice)
+    }
+
+    /// 列内按键升序遍历。
+    pub fn iter(&self, col: Column) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.cols
+            .get(&col)
+            .into_iter()
+            .flat_map(|m| m.iter().map(|(k, v)| (k.as_slice(), v.as_slice())))
+    }
+
+    /// 列内以 prefix 开头的键。
+    pub fn iter_prefix<'a>(
+        &'a self,
+        col: Column,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = (&'a [u8], &'a [u8])> + 'a {
+        self.cols.get(&col).into_iter().flat_map(move |m| {
+            m.range(prefix.to_vec()..)
+                .take_while(move |(k, _)| k.starts_with(prefix))
+                .map(|(k, v)| (k.as_slice(), v.as_slice()))
+        })
+    }
+
+    pub fn len(&self, col: Column) -> usize {
+        self.cols.get(&col).map(BTreeMap::len).unwrap_or(0)
+    }
+
+    /// 列内键值总字节数。
+    pub fn size_bytes(&self, col: Column) -> usize {
+        self.bytes.get(&col).copied().unwrap_or(0)
+    }
+
+    pub fn write(&mut self, batch: WriteBatch) -> anyhow::Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        if let Some(log) = self.log.as_mut() {
+            log.write_all(&encode_batch(&batch.ops))?;
+        }
+        for op in batch.ops {
+            self.apply(op);
+        }
+        self.report_metrics();
+        Ok(())
+    }
+
+    pub fn put(&mut self, col: Column, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut b = WriteBatch::new();
+        b.put(col, key, value);
+        self.write(b)
+    }
+
+    /// 将缓冲写入刷到磁盘。
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        if let Some(log) = self.log.as_mut() {
+            log.flush()?;
+            log.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// 以当前数据重写日志（先写临时文件再原子替换）。
+    pub fn compact(&mut self) -> anyhow::Result<()> {
+        let Some(dir) = self.dir.clone() else {
+            return Ok(());
+        };
+        self.flush()?;
+        let tmp = dir.join(format!("{LOG_FILE}.tmp"));
+        {
+            // 每个条目单独成帧：重写后的日志整体经原子替换生效，无需跨条目的批次边界
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            for (col, m) in &self.cols {
+                for (k, v) in m {
+                    w.write_all(&encode_batch(&[Op::Put(*col, k.clone(), v.clone())]))?;
+                }
+            }
+            w.flush()?;
+            w.get_ref().sync_all()?;
+        }
+        let path = dir.join(LOG_FILE);
+        fs::rename(&tmp, &path)?;
+        sync_dir(&dir)?;
+        let file = OpenOptions::new().append(true).open(&path)?;
+        self.log = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    fn apply(&mut self, op: Op) {
+        match op {
+            Op::Put(col, k, v) => {
+                let (key_len, value_len) = (k.len(), v.len());
+                let bytes = self.bytes.entry(col).or_default();
+                match self.cols.entry(col).or_default().insert(k, v) {
+                    // 覆盖：键长不变，只替换值
+                    Some(old) => *bytes = *bytes - old.len() + value_len,
+                    None => *bytes += key_len + value_len,
+                }
+            }
+            Op::Delete(col, k) => {
+                if let Some(old) = self.cols.get_mut(&col).and_then(|m| m.remove(&k)) {
+                    *self.bytes.entry(col).or_default() -= k.len() + old.len();
+                }
+            }
+        }
+    }
+
+    /// 只上报磁盘数据库（内存库用于测试与临时计算）。
+    fn report_metrics(&self) {
+        if self.dir.is_none() {
+            return;
+        }
+        for col in Column::ALL {
+            let name = col.name();
+            metrics::COLUMN_KEYS
+                .with_label_values(&[name])
+                .set(self.len(col) as i64);
+            metrics::COLUMN_BYTES
+                .with_label_values(&[name])
+                .set(self.size_bytes(col) as i64);
+        }
+    }
+}
+
+impl Drop for Db {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::error!(error = %e, "db flush on drop failed");
+        }
+    }
+}
+
+fn encode_op(op: &Op, out: &mut Vec<u8>) {
+    let (code, col, key, value): (u8, Column, &[u8], &[u8]) = match op {
+        Op::Put(c, k, v) => (OP_PUT, *c, k, v),
+        Op::Delete(c, k) => (OP_DELETE, *c, k, &[]),
+    };
+    out.push(code);
+    out.push(col as u8);
+    out.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    out.extend_from_slice(key);
+    out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    out.extend_from_slice(value);
+}
+
+fn encode_batch(ops: &[Op]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for op in ops {
+        encode_op(op, &mut body);
+    }
+    let mut out = Vec::with_capacity(FRAME_HEADER + body.len());
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(&crc32fast::hash(&body).to_be_bytes());
+    out.extend_from_slice(&body);
+    out
+}
+
+/// 按批次解析日志，返回完整批次与有效字节长度。
+///
+/// 帧头或负载不完整的帧只有在其后找不到任何校验通过的帧时才视为半写的末尾并在此截止
+/// （长度字段中间位翻转会让帧看似越过文件末尾，不能因此丢弃其后已提交的批次）；
+/// 其余情况视为日志损坏，返回该帧偏移。
+fn decode_log(raw: &[u8]) -> Result<(Vec<Vec<Op>>, usize), usize> {
+    let mut batches = Vec::new();
+    let mut pos = 0;
+    while pos < raw.len() {
+        if frame_end(raw, pos).is_none() {
+            if (pos + 1..raw.len()).any(|q| decode_batch(raw, q).is_some()) {
+                return Err(pos);
+            }
+            break;
+        }
+        let (ops, next) = decode_batch(raw, pos).ok_or(pos)?;
+        batches.push(ops);
+        pos = next;
+    }
+    Ok((batches, pos))
+}
+
+/// pos 处帧的结束偏移；帧头或负载不完整时为 None。
+fn frame_end(raw: &[u8], pos: usize) -> Option<usize> {
+    let header = raw.get(pos..pos.checked_add(FRAME_HEADER)?)?;
+    let len = u32::from_be_bytes(header[..4].try_into().ok()?) as usize;
+    let end = (pos + FRAME_HEADER).checked_add(len)?;
+    (end <= raw.len()).then_some(end)
+}
+
+fn decode_batch(raw: &[u8], pos: usize) -> Option<(Vec<Op>, usize)> {
+    let end = frame_end(raw, pos)?;
+    let crc = u32::from_be_bytes(raw[pos + 4..pos + FRAME_HEADER].try_into().ok()?);
+    let body = &raw[pos + FRAME_HEADER..end];
+    if crc32fast::hash(body) != crc {
+        return None;
+    }
+    let mut ops = Vec::new();
+    let mut at = 0;
+    while at < body.len() {
+        let (op, next) = decode_one(body, at)?;
+        ops.push(op);
+        at = next;
+    }
+    Some((ops, end))
+}
+
+/// 使目录项（rename 结果）落盘；非 unix 平台无法打开目录，跳过。
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+fn decode_one(raw: &[u8], pos: usize) -> Option<(Op, usize)> {
+    let code = *raw.get(pos)?;
+    let col = Column::from_u8(*raw.get(pos + 1)?)?;
+    let read_len = |at: usize| -> Option<usize> {
+        let b: [u8; 4] = raw.get(at..at + 4)?.try_into().ok()?;
+        Some(u32::from_be_bytes(b) as usize)
+    };
+    let klen = read_len(pos + 2)?;
+    let kstart = pos + 6;
+    let key = raw.get(kstart..kstart + klen)?.to_vec();
+    let vlen = read_len(kstart + klen)?;
+    let vstart = kstart + klen + 4;
+    let value = raw.get(vstart..vstart + vlen)?.to_vec();
+    let op = match code {
+        OP_PUT => Op::Put(col, key, value),
+        OP_DELETE => Op::Delete(col, key),
+        _ => return None,
+    };
+    Some((op, vstart + vlen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ark-db-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn reopen_replays_log_and_drops_torn_tail() {
+        let dir = temp_dir("replay");
+        {
+            let mut db = Db::open(&dir).unwrap();
+            let mut b = WriteBatch::new();
+            b.put(Column::Meta, b"k1".to_vec(), b"v1".to_vec());
+            b.put(Column::Meta, b"k2".to_vec(), b"v2".to_vec());
+            db.write(b).unwrap();
+            let mut b = WriteBatch::new();
+            b.delete(Column::Meta, b"k1".to_vec());
+            db.write(b).unwrap();
+            db.flush().unwrap();
+        }
+        // 模拟崩溃：追加半条记录
+        let mut f = OpenOptions::new()
+            .append(true)
+            .open(dir.join(LOG_FILE))
+            .unwrap();
+        f.write_all(&[OP_PUT, 0, 0, 0]).unwrap();
+        drop(f);
+
+        let mut db = Db::open(&dir).unwrap();
+        assert_eq!(db.get(Column::Meta, b"k1"), None);
+        assert_eq!(db.get(Column::Meta, b"k2"), Some(&b"v2"[..]));
+        assert_eq!(db.size_bytes(Column::Meta), 4);
+        db.put(Column::Meta, b"k2", b"longer").unwrap();
+        assert_eq!(db.size_bytes(Column::Meta), 8);
+        db.put(Column::State, b"x", b"y").unwrap();
+        db.compact().unwrap();
+        drop(db);
+
+        let db = Db::open(&dir).unwrap();
+        assert_eq!(db.get(Column::State, b"x"), Some(&b"y"[..]));
+        assert_eq!(db.iter_prefix(Column::Meta, b"k").count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn torn_batch_is_dropped_whole_and_mid_log_corruption_is_refused() {
+        let dir = temp_dir("torn");
+        let log = dir.join(LOG_FILE);
+        {
+            let mut db = Db::open(&dir).unwrap();
+            db.put(Column::Meta, b"head", b"1").unwrap();
+            let mut b = WriteBatch::new();
+            b.put(Column::State, b"acct".to_vec(), b"balance".to_vec());
+            b.put(Column::Meta, b"head".to_vec(), b"2".to_vec());
+            db.write(b).unwrap();
+        }
+        let full = fs::read(&log).unwrap();
+        // 截掉最后一个批次的末尾：state 写入已落盘而 head 未落盘，整批都不能生效
+        fs::write(&log, &full[..full.len() - 3]).unwrap();
+        let db = Db::open(&dir).unwrap();
+        assert_eq!(db.get(Column::Meta, b"head"), Some(&b"1"[..]));
+        assert_eq!(db.get(Column::State, b"acct"), None);
+        drop(db);
+        let first = fs::metadata(&log).unwrap().len() as usize;
+        assert_eq!(&fs::read(&log).unwrap()[..], &full[..first]);
+
+        // 中间批次损坏而其后仍有数据：拒绝打开
+        let mut corrupt = full.clone();
+        corrupt[FRAME_HEADER] ^= 0xff;
+        fs::write(&log, &corrupt).unwrap();
+        assert!(Db::open(&dir).is_err());
+
+        // 第一帧长度字段位翻转，看似越过文件末尾：其后的批次校验通过，不能当作末尾截断
+        let mut corrupt = full.clone();
+        corrupt[0] ^= 0x80;
+        fs::write(&log, &corrupt).unwrap();
+        assert!(Db::open(&dir).is_err());
+        assert_eq!(fs::read(&log).unwrap(), corrupt);
+
+        // 最后一帧完整但 crc 不符：不是半写，拒绝打开
+        let mut corrupt = full.clone();
+        *corrupt.last_mut().unwrap() ^= 0xff;
+        fs::write(&log, &corrupt).unwrap();
+        assert!(Db::open(&dir).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
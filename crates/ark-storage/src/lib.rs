@@ -1,2 +1,11 @@
-//! 存储占位：后续集成 RocksDB、快照/修剪
-pub struct Db;
+//! 存储：列式日志 KV（Db）、链数据存储（ChainStore）、状态快照（Snapshot）与可移植区块文件
+pub mod blockfile;
+pub mod chain;
+pub mod db;
+pub mod metrics;
+pub mod snapshot;
+
+pub use blockfile::{export_blocks, BlockReader};
+pub use chain::{ChainStore, IndexedLog, TxLocation};
+pub use db::{Column, Db, WriteBatch};
+pub use snapshot::{Snapshot, SnapshotMeta};
@@ -0,0 +1,28 @@
+//! 存储指标：链高度与各列大小（仅磁盘数据库上报）。
+use ark_metrics::{IntGauge, IntGaugeVec};
+use std::sync::LazyLock;
+
+pub(crate) static HEAD_HEIGHT: LazyLock<IntGauge> = LazyLock::new(|| {
+    ark_metrics::int_gauge("chain_head_height", "Height of the latest stored block")
+});
+pub(crate) static COLUMN_KEYS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    ark_metrics::int_gauge_vec(
+        "storage_column_keys",
+        "Number of keys per column",
+        &["column"],
+    )
+});
+pub(crate) static COLUMN_BYTES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    ark_metrics::int_gauge_vec(
+        "storage_column_bytes",
+        "Total key and value bytes per column",
+        &["column"],
+    )
+});
+
+/// 在全局注册表中注册本 crate 的指标。
+pub fn register() {
+    LazyLock::force(&HEAD_HEIGHT);
+    LazyLock::force(&COLUMN_KEYS);
+    LazyLock::force(&COLUMN_BYTES);
+}
@@ -0,0 +1,188 @@
+//! 状态快照：某一高度的区块头与全部状态条目，用于备份与快速启动新节点。
+//!
+//! 文件格式：magic `ARKSNAP1` | meta_len(u32be) | meta(JSON) | 条目 ...
+//! 条目格式：key_len(u32be) | key | value_len(u32be) | value，按键升序。
+//! 读取时重算状态根并与区块头中的 state_root 比对。
+use crate::chain::ChainStore;
+use ark_types::{merkle, BlockHeader, H256};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"ARKSNAP1";
+/// 元数据长度上限，防止损坏文件导致超大分配
+const MAX_META_LEN: u32 = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotMeta {
+    pub genesis_hash: H256,
+    pub header: BlockHeader,
+    pub entries: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub meta: SnapshotMeta,
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Snapshot {
+    /// 截取数据库当前 head 的快照。
+    pub fn capture(store: &ChainStore) -> anyhow::Result<Self> {
+        let genesis_hash = store
+            .genesis_hash()
+            .ok_or_else(|| anyhow::anyhow!("database not initialized"))?;
+        let head = store.head().unwrap_or(0);
+        let header = store
+            .header(head)?
+            .ok_or_else(|| anyhow::anyhow!("missing header for head {head}"))?;
+        let entries = store.state_entries();
+        Ok(Self {
+            meta: SnapshotMeta {
+                genesis_hash,
+                header,
+                entries: entries.len() as u64,
+            },
+            entries,
+        })
+    }
+
+    pub fn height(&self) -> u64 {
+        self.meta.header.height
+    }
+
+    /// 默认文件名：`snapshot-<高度>.arksnap`（高度补零，按名称排序即按高度排序）。
+    pub fn file_name(&self) -> String {
+        format!("snapshot-{:012}.arksnap", self.height())
+    }
+
+    /// 写入 dir 下的默认文件名（先写临时文件再原子替换），返回文件路径。
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        self.write(&path)?;
+        Ok(path)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp = path.with_extension("tmp");
+        {
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            let meta = serde_json::to_vec(&self.meta)?;
+            w.write_all(MAGIC)?;
+            w.write_all(&(meta.len() as u32).to_be_bytes())?;
+            w.write_all(&meta)?;
+            for (k, v) in &self.entries {
+                w.write_all(&(k.len() as u32).to_be_bytes())?;
+                w.write_all(k)?;
+                w.write_all(&(v.len() as u32).to_be_bytes())?;
+                w.write_all(v)?;
+            }
+            w.flush()?;
+            w.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// 读取并校验快照（条目数、键序与状态根）。
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let mut r = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        anyhow::ensure!(&magic == MAGIC, "not a snapshot file: {}", path.display());
+        let meta_len = read_u32(&mut r)?;
+        anyhow::ensure!(meta_len <= MAX_META_LEN, "snapshot metadata too large");
+        let mut meta = vec![0u8; meta_len as usize];
+        r.read_exact(&mut meta)?;
+        let meta: SnapshotMeta = serde_json::from_slice(&meta)?;
+
+        let mut entries = Vec::with_capacity(meta.entries.min(1 << 20) as usize);
+        for _ in 0..meta.entries {
+            let key = read_chunk(&mut r)?;
+            let value = read_chunk(&mut r)?;
+            if let Some((prev, _)) = entries.last() {
+                anyhow::ensure!(*prev < key, "snapshot entries out of order");
+            }
+            entries.push((key, value));
+        }
+        anyhow::ensure!(
+            r.read(&mut [0u8; 1])? == 0,
+            "trailing data after snapshot entries"
+        );
+        let root = merkle::root(entries.iter().map(|(k, v)| (k.as_slice(), v.as_slice())));
+        anyhow::ensure!(
+            root == meta.header.state_root,
+            "snapshot state root mismatch: header {}, entries {root}",
+            meta.header.state_root
+        );
+        Ok(Self { meta, entries })
+    }
+}
+
+fn read_u32(r: &mut impl Read) -> anyhow::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_chunk(r: &mut impl Read) -> anyhow::Result<Vec<u8>> {
+    let len = read_u32(r)? as u64;
+    let mut buf = Vec::new();
+    r.take(len).read_to_end(&mut buf)?;
+    anyhow::ensure!(buf.len() as u64 == len, "truncated snapshot entry");
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_types::{Address, Block};
+
+    #[test]
+    fn snapshot_roundtrip_verifies_state_root() {
+        let entries = vec![
+            (b"a1".to_vec(), b"x".to_vec()),
+            (b"a2".to_vec(), b"y".to_vec()),
+        ];
+        let state_root = merkle::root(entries.iter().map(|(k, v)| (k.as_slice(), v.as_slice())));
+        let genesis = Block {
+            header: BlockHeader {
+                height: 0,
+                parent_hash: H256::ZERO,
+                timestamp_ms: 0,
+                proposer: Address::ZERO,
+                state_root,
+                tx_root: H256::ZERO,
+                receipts_root: H256::ZERO,
+                gas_limit: 1,
+                gas_used: 0,
+                base_fee: 1,
+                logs_bloom: Default::default(),
+            },
+            txs: Vec::new(),
+        };
+        let mut store = ChainStore::in_memory();
+        store.init_genesis(&genesis, entries.clone()).unwrap();
+
+        let snap = Snapshot::capture(&store).unwrap();
+        let dir = std::env::temp_dir().join(format!("ark-snapshot-{}", std::process::id()));
+        let path = snap.write_to_dir(&dir).unwrap();
+        assert!(path.ends_with("snapshot-000000000000.arksnap"));
+        let read = Snapshot::read(&path).unwrap();
+        assert_eq!(read, snap);
+        assert_eq!(read.entries, entries);
+
+        // 篡改最后一个值：状态根不再匹配
+        let mut raw = fs::read(&path).unwrap();
+        *raw.last_mut().unwrap() ^= 1;
+        fs::write(&path, raw).unwrap();
+        assert!(Snapshot::read(&path)
+            .unwrap_err()
+            .to_string()
+            .contains("state root"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
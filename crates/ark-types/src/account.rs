@@ -0,0 +1,75 @@
+//! 账户与状态键的编码（状态根 Merkle 叶子的格式，执行层与证明校验共用）。
+//!
+//! - `a` + 地址 -> nonce(8) | balance(16) | 有无代码(1) | code_hash(32)
+//! - `c` + 代码哈希 -> 代码
+//! - `m` + 模块键 -> 模块值
+//! - `s` + 地址 + 键 -> 值
+//! - `v` + 运营者地址 -> Validator JSON
+use crate::primitives::{amount, Address, Amount, H256};
+use serde::{Deserialize, Serialize};
+
+pub const PREFIX_ACCOUNT: u8 = b'a';
+pub const PREFIX_CODE: u8 = b'c';
+pub const PREFIX_MODULE: u8 = b'm';
+pub const PREFIX_STORAGE: u8 = b's';
+pub const PREFIX_VALIDATOR: u8 = b'v';
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    pub nonce: u64,
+    #[serde(with = "amount")]
+    pub balance: Amount,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_hash: Option<H256>,
+}
+
+pub fn account_key(addr: &Address) -> Vec<u8> {
+    prefixed(PREFIX_ACCOUNT, &[addr.as_bytes()])
+}
+
+pub fn storage_key(addr: &Address, key: &H256) -> Vec<u8> {
+    prefixed(PREFIX_STORAGE, &[addr.as_bytes(), key.as_bytes()])
+}
+
+pub fn prefixed(prefix: u8, parts: &[&[u8]]) -> Vec<u8> {
+    let mut k = vec![prefix];
+    for p in parts {
+        k.extend_from_slice(p);
+    }
+    k
+}
+
+pub fn encode_account(acc: &Account) -> Vec<u8> {
+    let mut out = Vec::with_capacity(57);
+    out.extend_from_slice(&acc.nonce.to_be_bytes());
+    out.extend_from_slice(&acc.balance.to_be_bytes());
+    match &acc.code_hash {
+        Some(h) => {
+            out.push(1);
+            out.extend_from_slice(h.as_bytes());
+        }
+        None => out.push(0),
+    }
+    out
+}
+
+pub fn decode_account(b: &[u8]) -> Result<Account, String> {
+    if b.len() != 25 && b.len() != 57 {
+        return Err(format!("invalid account encoding length {}", b.len()));
+    }
+    let nonce = u64::from_be_bytes(b[..8].try_into().expect("8 bytes"));
+    let balance = u128::from_be_bytes(b[8..24].try_into().expect("16 bytes"));
+    let code_hash = match (b[24], b.len()) {
+        (0, 25) => None,
+        (1, 57) => {
+            let h: [u8; 32] = b[25..].try_into().expect("32 bytes");
+            Some(H256(h))
+        }
+        _ => return Err("invalid account code flag".into()),
+    };
+    Ok(Account {
+        nonce,
+        balance,
+        code_hash,
+    })
+}
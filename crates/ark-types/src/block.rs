@@ -0,0 +1,70 @@
+//! 区块与区块头。
+use crate::log::Bloom;
+use crate::merkle;
+use crate::primitives::{amount, sha256, Address, Amount, Gas, H256};
+use crate::receipt::Receipt;
+use crate::tx::SignedTransaction;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub height: u64,
+    pub parent_hash: H256,
+    pub timestamp_ms: u64,
+    pub proposer: Address,
+    /// 执行完本区块后的状态根
+    pub state_root: H256,
+    /// 交易哈希的 Merkle 根
+    pub tx_root: H256,
+    /// 回执哈希的 Merkle 根
+    pub receipts_root: H256,
+    pub gas_limit: Gas,
+    pub gas_used: Gas,
+    /// 本区块每单位 gas 的 base fee
+    #[serde(with = "amount")]
+    pub base_fee: Amount,
+    /// 全部回执日志的 Bloom；为空时不参与序列化，无日志区块的哈希保持不变
+    #[serde(default, skip_serializing_if = "Bloom::is_empty")]
+    pub logs_bloom: Bloom,
+}
+
+impl BlockHeader {
+    pub fn hash(&self) -> H256 {
+        sha256(&serde_json::to_vec(self).expect("header serializes"))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    pub header: BlockHeader,
+    pub txs: Vec<SignedTransaction>,
+}
+
+impl Block {
+    pub fn hash(&self) -> H256 {
+        self.header.hash()
+    }
+
+    pub fn height(&self) -> u64 {
+        self.header.height
+    }
+}
+
+/// 交易根：以交易哈希为叶子的 Merkle 根（见 `merkle`），空区块为全零。
+pub fn tx_root(txs: &[SignedTransaction]) -> H256 {
+    merkle::root_from_leaves(txs.iter().map(SignedTransaction::hash).collect())
+}
+
+/// 回执叶子：回执 JSON 编码的哈希。
+pub fn receipt_leaf(receipt: &Receipt) -> H256 {
+    sha256(&serde_json::to_vec(receipt).expect("receipt serializes"))
+}
+
+pub fn receipts_root(receipts: &[Receipt]) -> H256 {
+    merkle::root_from_leaves(receipts.iter().map(receipt_leaf).collect())
+}
+
+/// 区块内全部回执日志的 Bloom。
+pub fn logs_bloom(receipts: &[Receipt]) -> Bloom {
+    Bloom::from_logs(receipts.iter().flat_map(|r| &r.logs))
+}
@@ -0,0 +1,40 @@
+//! 交易模拟（预执行）的请求与结果：不需要签名，发送方直接给出地址。
+use crate::log::Log;
+use crate::primitives::{amount, hex_bytes, opt_amount, Address, Amount, Gas};
+use crate::receipt::ExecStatus;
+use crate::tx::Action;
+use serde::{Deserialize, Serialize};
+
+/// 模拟请求。nonce 取发送方当前值；未给出的费用字段按“刚好可打包”填充。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallRequest {
+    pub from: Address,
+    pub action: Action,
+    #[serde(default, with = "amount")]
+    pub value: Amount,
+    /// 缺省为区块 gas 上限（并受发送方余额约束）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<Gas>,
+    /// 缺省为当前 base fee
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "opt_amount")]
+    pub max_fee_per_gas: Option<Amount>,
+    /// 缺省为 0
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "opt_amount")]
+    pub max_priority_fee_per_gas: Option<Amount>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallResult {
+    pub status: ExecStatus,
+    pub gas_used: Gas,
+    /// 实际使用的 gas_limit
+    pub gas_limit: Gas,
+    #[serde(with = "hex_bytes")]
+    pub output: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<Log>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+}
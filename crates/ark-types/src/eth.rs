@@ -0,0 +1,332 @@
+//! 以太坊兼容表示：`eth_*` RPC 使用的十六进制地址、数值链 ID 与 EIP-1559 交易信封。
+//!
+//! - 地址文本形式为 `0x` 十六进制（不校验大小写校验和）
+//! - 以太坊交易的发送方按以太坊规则派生：keccak256(非压缩公钥去掉 0x04 前缀) 后 20 字节；
+//!   原生交易仍为 Sha256(压缩公钥) 前 20 字节，同一私钥在两种交易下是两个不同账户
+//! - 只接受 EIP-1559（类型 0x02）交易，访问列表须为空，金额须在 128 位以内
+use crate::primitives::{sha256, Address, Amount, Gas, H256};
+use crate::tx::{Action, Transaction};
+use alloy_rlp::{Decodable, Encodable, Header};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+/// EIP-2718 交易类型：EIP-1559 动态费用交易。
+pub const EIP1559_TX_TYPE: u8 = 0x02;
+
+pub fn keccak256(data: &[u8]) -> H256 {
+    H256(Keccak256::digest(data).into())
+}
+
+/// 由 65 字节非压缩公钥（0x04 || x || y）派生以太坊地址。
+pub fn address_from_pubkey(uncompressed: &[u8]) -> Address {
+    let key = uncompressed.strip_prefix(&[0x04]).unwrap_or(uncompressed);
+    let mut out = [0u8; 20];
+    out.copy_from_slice(&keccak256(key).0[12..]);
+    Address(out)
+}
+
+/// 由链 ID 字符串派生的 EIP-155 数值链 ID：Sha256 前 8 字节，截断到 53 位以便 JS 精确表示。
+pub fn eth_chain_id(chain_id: &str) -> u64 {
+    let h = sha256(chain_id.as_bytes());
+    let mut b = [0u8; 8];
+    b.copy_from_slice(&h.0[..8]);
+    u64::from_be_bytes(b) & ((1 << 53) - 1)
+}
+
+pub fn address_to_hex(addr: &Address) -> String {
+    format!("0x{}", hex::encode(addr.0))
+}
+
+pub fn address_from_hex(s: &str) -> Result<Address, String> {
+    let raw = s
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("address `{s}` must be 0x-prefixed hex"))?;
+    let mut out = [0u8; 20];
+    hex::decode_to_slice(raw, &mut out).map_err(|e| format!("invalid address `{s}`: {e}"))?;
+    Ok(Address(out))
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EthTxError {
+    #[error("empty transaction")]
+    Empty,
+    #[error(
+        "unsupported transaction type {0:#04x}: only EIP-1559 (0x02) transactions are accepted"
+    )]
+    UnsupportedType(u8),
+    #[error("rlp: {0}")]
+    Rlp(#[from] alloy_rlp::Error),
+    #[error("unexpected fields after the signature")]
+    TrailingFields,
+    #[error("access lists are not supported")]
+    AccessList,
+    #[error("invalid `to` address")]
+    To,
+    #[error("invalid signature encoding")]
+    Signature,
+}
+
+/// 已签名的 EIP-1559 交易。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: Amount,
+    pub max_fee_per_gas: Amount,
+    pub gas_limit: Gas,
+    /// None 为合约创建
+    pub to: Option<Address>,
+    pub value: Amount,
+    pub data: Vec<u8>,
+    /// r || s || y_parity
+    pub signature: [u8; 65],
+}
+
+impl Eip1559Transaction {
+    /// 解码 `0x02 || rlp([chain_id, nonce, tip, max_fee, gas, to, value, data, access_list, y, r, s])`。
+    pub fn decode(raw: &[u8]) -> Result<Self, EthTxError> {
+        let (&ty, body) = raw.split_first().ok_or(EthTxError::Empty)?;
+        if ty != EIP1559_TX_TYPE {
+            return Err(EthTxError::UnsupportedType(ty));
+        }
+        let mut buf = body;
+        let header = Header::decode(&mut buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString.into());
+        }
+        if buf.len() != header.payload_length {
+            return Err(alloy_rlp::Error::UnexpectedLength.into());
+        }
+        let f = &mut buf;
+        let chain_id = u64::decode(f)?;
+        let nonce = u64::decode(f)?;
+        let max_priority_fee_per_gas = u128::decode(f)?;
+        let max_fee_per_gas = u128::decode(f)?;
+        let gas_limit = u64::decode(f)?;
+        let to = match Header::decode_bytes(f, false)? {
+            [] => None,
+            b => Some(Address(b.try_into().map_err(|_| EthTxError::To)?)),
+        };
+        let value = u128::decode(f)?;
+        let data = Header::decode_bytes(f, false)?.to_vec();
+        if !Header::decode_bytes(f, true)?.is_empty() {
+            return Err(EthTxError::AccessList);
+        }
+        let parity = u64::decode(f)?;
+        if parity > 1 {
+            return Err(EthTxError::Signature);
+        }
+        let mut signature = [0u8; 65];
+        signature[..32].copy_from_slice(&word(Header::decode_bytes(f, false)?)?);
+        signature[32..64].copy_from_slice(&word(Header::decode_bytes(f, false)?)?);
+        signature[64] = parity as u8;
+        if !f.is_empty() {
+            return Err(EthTxError::TrailingFields);
+        }
+        Ok(Self {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            to,
+            value,
+            data,
+            signature,
+        })
+    }
+
+    /// 由原生交易字段与签名还原以太坊交易；只有 Call / EvmCreate 有对应形式。
+    pub fn from_native(tx: &Transaction, signature: &[u8]) -> Option<Self> {
+        let (to, data) = match &tx.action {
+            Action::Call { to, input } => (Some(*to), input.clone()),
+            Action::EvmCreate { code } => (None, code.clone()),
+            _ => return None,
+        };
+        Some(Self {
+            chain_id: eth_chain_id(&tx.chain_id),
+            nonce: tx.nonce,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            gas_limit: tx.gas_limit,
+            to,
+            value: tx.value,
+            data,
+            signature: signature.try_into().ok()?,
+        })
+    }
+
+    /// 转为原生交易内容（chain_id 为对应的链 ID 字符串）。
+    pub fn to_native(&self, chain_id: &str) -> Transaction {
+        let action = match self.to {
+            Some(to) => Action::Call {
+                to,
+                input: self.data.clone(),
+            },
+            None => Action::EvmCreate {
+                code: self.data.clone(),
+            },
+        };
+        Transaction {
+            chain_id: chain_id.to_string(),
+            nonce: self.nonce,
+            action,
+            value: self.value,
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        }
+    }
+
+    fn unsigned_fields(&self, out: &mut Vec<u8>) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.gas_limit.encode(out);
+        self.to.as_ref().map_or(&[][..], |a| &a.0[..]).encode(out);
+        self.value.encode(out);
+        self.data[..].encode(out);
+        // 空访问列表
+        Header {
+            list: true,
+            payload_length: 0,
+        }
+        .encode(out);
+    }
+
+    fn envelope(payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![EIP1559_TX_TYPE];
+        Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut out);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// 签名消息摘要：keccak256(0x02 || rlp(不含签名的 9 个字段))。
+    pub fn signing_hash(&self) -> H256 {
+        let mut fields = Vec::new();
+        self.unsigned_fields(&mut fields);
+        keccak256(&Self::envelope(&fields))
+    }
+
+    /// 签名交易的规范编码（与 eth_sendRawTransaction 收到的字节相同）。
+    pub fn encode(&self) -> Vec<u8> {
+        let mut fields = Vec::new();
+        self.unsigned_fields(&mut fields);
+        (self.signature[64] as u64).encode(&mut fields);
+        for half in [&self.signature[..32], &self.signature[32..64]] {
+            let start = half.iter().take_while(|b| **b == 0).count();
+            half[start..].encode(&mut fields);
+        }
+        Self::envelope(&fields)
+    }
+
+    /// 以太坊交易哈希：keccak256(签名交易编码)。
+    pub fn hash(&self) -> H256 {
+        keccak256(&self.encode())
+    }
+}
+
+/// 签名分量 r / s：至多 32 字节的无前导零整数，左侧补零。
+fn word(b: &[u8]) -> Result<[u8; 32], EthTxError> {
+    if b.len() > 32 {
+        return Err(EthTxError::Signature);
+    }
+    if b.first() == Some(&0) {
+        return Err(alloy_rlp::Error::LeadingZero.into());
+    }
+    let mut out = [0u8; 32];
+    out[32 - b.len()..].copy_from_slice(b);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_address_round_trips_and_chain_id_is_stable() {
+        let addr = Address::from_pubkey(&[2u8; 33]);
+        assert_eq!(address_from_hex(&address_to_hex(&addr)), Ok(addr));
+        assert!(address_from_hex("1234").is_err());
+        assert!(address_from_hex("0x1234").is_err());
+        assert_eq!(eth_chain_id("ark-astra-1"), eth_chain_id("ark-astra-1"));
+        assert_ne!(eth_chain_id("ark-astra-1"), eth_chain_id("ark-devnet"));
+        assert!(eth_chain_id("ark-astra-1") < 1 << 53);
+    }
+
+    #[test]
+    fn eth_address_is_keccak_of_uncompressed_key() {
+        // 私钥 1 的公钥即生成元 G
+        let g = hex::decode(
+            "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        )
+        .unwrap();
+        assert_eq!(
+            address_to_hex(&address_from_pubkey(&g)),
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+    }
+
+    #[test]
+    fn eip1559_envelope_round_trips_through_native_form() {
+        let mut signature = [0x11u8; 65];
+        signature[0] = 0;
+        signature[64] = 1;
+        let tx = Eip1559Transaction {
+            chain_id: eth_chain_id("ark-astra-1"),
+            nonce: 7,
+            max_priority_fee_per_gas: 2,
+            max_fee_per_gas: 1_000_000_000,
+            gas_limit: 21_000,
+            to: Some(Address([0xaa; 20])),
+            value: 10u128.pow(18),
+            data: vec![0xde, 0xad],
+            signature,
+        };
+        let raw = tx.encode();
+        assert_eq!(raw[0], EIP1559_TX_TYPE);
+        assert_eq!(Eip1559Transaction::decode(&raw), Ok(tx.clone()));
+        assert_ne!(tx.signing_hash(), tx.hash());
+
+        let native = tx.to_native("ark-astra-1");
+        assert_eq!(
+            Eip1559Transaction::from_native(&native, &signature),
+            Some(tx.clone())
+        );
+        let create = Eip1559Transaction { to: None, ..tx };
+        let native = create.to_native("ark-astra-1");
+        assert!(matches!(native.action, Action::EvmCreate { .. }));
+        assert_eq!(
+            Eip1559Transaction::decode(&create.encode()).unwrap().to,
+            None
+        );
+
+        let mut legacy = raw.clone();
+        legacy[0] = 0xf8;
+        assert_eq!(
+            Eip1559Transaction::decode(&legacy),
+            Err(EthTxError::UnsupportedType(0xf8))
+        );
+        assert!(Eip1559Transaction::decode(&raw[..raw.len() - 1]).is_err());
+
+        // 签名之后多出的字段、非列表负载都被拒绝
+        let mut body = &raw[1..];
+        Header::decode(&mut body).unwrap();
+        let mut payload = body.to_vec();
+        0u8.encode(&mut payload);
+        assert_eq!(
+            Eip1559Transaction::decode(&Eip1559Transaction::envelope(&payload)),
+            Err(EthTxError::TrailingFields)
+        );
+        assert_eq!(
+            Eip1559Transaction::decode(&[EIP1559_TX_TYPE, 0x80]),
+            Err(EthTxError::Rlp(alloy_rlp::Error::UnexpectedString))
+        );
+    }
+}
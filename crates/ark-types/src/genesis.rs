@@ -0,0 +1,232 @@
+//! 创世配置（config/genesis.json）：链参数、初始余额、预部署合约、创世验证者、功能开关与预定升级。
+use crate::primitives::{amount, hex_bytes, Address, Amount, Gas, H256};
+use crate::ChainId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChainParams {
+    pub block_time_ms: u64,
+    pub epoch_blocks: u64,
+    /// 单个区块可消耗的 gas 上限
+    pub gas_limit_block: Gas,
+    /// 创世区块的 base fee
+    #[serde(with = "amount")]
+    pub base_fee: Amount,
+    /// base fee 下限
+    #[serde(with = "amount")]
+    pub gas_price_min: Amount,
+    #[serde(default)]
+    pub fee_market: FeeMarketParams,
+    pub staking: StakingParams,
+    pub slashing: SlashingParams,
+    pub wasm: WasmParams,
+    #[serde(default)]
+    pub governance: GovernanceParams,
+}
+
+/// 动态 base fee 参数（EIP-1559 风格）。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeeMarketParams {
+    /// gas 目标 = gas_limit_block / elasticity_multiplier
+    pub elasticity_multiplier: u64,
+    /// 单个区块 base fee 最大变化 = 1 / base_fee_change_denominator
+    pub base_fee_change_denominator: u64,
+    /// base fee 去向：缺省销毁；配置地址则转入该地址（如国库）
+    #[serde(default)]
+    pub base_fee_recipient: Option<Address>,
+}
+
+impl Default for FeeMarketParams {
+    fn default() -> Self {
+        Self {
+            elasticity_multiplier: 2,
+            base_fee_change_denominator: 8,
+            base_fee_recipient: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StakingParams {
+    #[serde(with = "amount")]
+    pub min_stake: Amount,
+    pub unbonding_epochs: u64,
+    pub max_validators: u32,
+    /// 每个 epoch 增发并分配给验证者的奖励
+    #[serde(default, with = "amount")]
+    pub epoch_reward: Amount,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlashingParams {
+    /// 双签罚没比例（十进制字符串，如 "0.05"）
+    pub double_sign: String,
+    pub downtime_epochs: u64,
+}
+
+/// 链上治理参数。比例均为万分比。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GovernanceParams {
+    /// 提交提案的最低押金
+    #[serde(with = "amount")]
+    pub min_deposit: Amount,
+    /// 投票期（区块数）
+    pub voting_period_blocks: u64,
+    /// 法定投票率：参与投票的抵押占全部抵押的比例
+    pub quorum_bps: u16,
+    /// 通过阈值：赞成占（赞成 + 反对）的比例，须严格超过
+    pub threshold_bps: u16,
+}
+
+impl Default for GovernanceParams {
+    fn default() -> Self {
+        Self {
+            min_deposit: 1_000_000_000,
+            voting_period_blocks: 43_200,
+            quorum_bps: 3_340,
+            threshold_bps: 5_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WasmParams {
+    pub max_code_size: usize,
+    pub aot: bool,
+    pub deterministic: bool,
+}
+
+/// 创世余额分配。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenesisBalance {
+    pub address: Address,
+    #[serde(with = "amount")]
+    pub amount: Amount,
+}
+
+/// 预部署合约：代码、初始存储与余额。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Predeploy {
+    pub address: Address,
+    #[serde(with = "hex_bytes")]
+    pub code: Vec<u8>,
+    #[serde(default)]
+    pub storage: BTreeMap<H256, H256>,
+    #[serde(default, with = "amount")]
+    pub balance: Amount,
+}
+
+/// 创世验证者：共识公钥 + 运营者账户 + 自抵押金额（创世时直接记为已抵押）。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenesisValidator {
+    pub operator: Address,
+    /// 共识公钥（压缩 secp256k1，33 字节）
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    #[serde(with = "amount")]
+    pub stake: Amount,
+    /// 佣金比例（万分比）
+    #[serde(default)]
+    pub commission_bps: u16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureGates {
+    #[serde(default)]
+    pub wasm_vm: bool,
+    #[serde(default)]
+    pub evm: bool,
+    #[serde(default)]
+    pub ibc_bridge: bool,
+}
+
+impl FeatureGates {
+    /// 本版本实现的特性门名称（与字段名一致）。
+    pub const KNOWN: [&'static str; 3] = ["wasm_vm", "evm", "ibc_bridge"];
+
+    /// 按名称查询；未知名称返回 None。
+    pub fn get(&self, name: &str) -> Option<bool> {
+        match name {
+            "wasm_vm" => Some(self.wasm_vm),
+            "evm" => Some(self.evm),
+            "ibc_bridge" => Some(self.ibc_bridge),
+            _ => None,
+        }
+    }
+
+    /// 按名称开启；未知名称返回 false。
+    pub fn enable(&mut self, name: &str) -> bool {
+        let gate = match name {
+            "wasm_vm" => &mut self.wasm_vm,
+            "evm" => &mut self.evm,
+            "ibc_bridge" => &mut self.ibc_bridge,
+            _ => return false,
+        };
+        *gate = true;
+        true
+    }
+}
+
+/// 协议升级计划：在 height 区块执行任何交易前开启 gates 中的特性门。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradePlan {
+    pub name: String,
+    pub height: u64,
+    /// 特性门名称；可以包含本版本尚未实现的名称（到达高度时节点停止，等待升级二进制）
+    #[serde(default)]
+    pub gates: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Genesis {
+    pub chain_id: ChainId,
+    /// RFC 3339 时间，作为 0 号区块时间戳
+    pub genesis_time: String,
+    pub params: ChainParams,
+    #[serde(default)]
+    pub bootnodes: Vec<String>,
+    #[serde(default)]
+    pub validators: Vec<GenesisValidator>,
+    #[serde(default)]
+    pub balances: Vec<GenesisBalance>,
+    #[serde(default)]
+    pub predeploy: Vec<Predeploy>,
+    #[serde(default)]
+    pub feature_gates: FeatureGates,
+    /// 按高度预定的协议升级
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub upgrades: Vec<UpgradePlan>,
+}
+
+impl Genesis {
+    pub fn from_json(raw: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(raw)
+    }
+
+    /// genesis_time 转毫秒时间戳。
+    pub fn timestamp_ms(&self) -> Result<u64, String> {
+        let t = chrono::DateTime::parse_from_rfc3339(&self.genesis_time)
+            .map_err(|e| format!("invalid genesis_time: {e}"))?;
+        u64::try_from(t.timestamp_millis()).map_err(|_| "genesis_time before epoch".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_repo_genesis() {
+        let raw = include_str!("../../../config/genesis.json");
+        let g = Genesis::from_json(raw).unwrap();
+        assert_eq!(g.chain_id, "ark-astra-1");
+        assert_eq!(g.params.gas_limit_block, 20_000_000);
+        assert_eq!(g.params.base_fee, 1000);
+        assert_eq!(g.params.staking.unbonding_epochs, 14);
+        assert_eq!(g.params.fee_market.elasticity_multiplier, 2);
+        assert!(g.feature_gates.wasm_vm && !g.feature_gates.evm);
+        assert_eq!(g.timestamp_ms().unwrap(), 1_757_894_400_000);
+    }
+}
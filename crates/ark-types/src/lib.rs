@@ -1,2 +1,28 @@
-//! 基础类型占位：后续扩展 Address/Tx/Block/Receipt 等
+//! 基础类型：Address/Tx/Block/Receipt/Genesis 等
+pub mod account;
+pub mod block;
+pub mod call;
+pub mod eth;
+pub mod genesis;
+pub mod log;
+pub mod merkle;
+pub mod primitives;
+pub mod proof;
+pub mod receipt;
+pub mod tx;
+pub mod validator;
+
 pub type ChainId = String;
+
+pub use block::{Block, BlockHeader};
+pub use call::{CallRequest, CallResult};
+pub use genesis::{
+    ChainParams, FeatureGates, FeeMarketParams, Genesis, GovernanceParams, UpgradePlan,
+};
+pub use log::{Bloom, Log, LogFilter};
+pub use primitives::{sha256, Address, Amount, Gas, H256};
+pub use receipt::{ExecStatus, Receipt};
+pub use tx::{
+    Action, GovernanceOp, ProposalContent, SignedTransaction, StakingOp, Transaction, VoteOption,
+};
+pub use validator::{ValidatorInfo, ValidatorSet};
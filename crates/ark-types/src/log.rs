@@ -0,0 +1,213 @@
+//! 合约事件日志、过滤条件与日志布隆过滤器。
+//!
+//! Bloom 为 2048 位：对合约地址与每个主题各取 Sha256，用前三对字节的低 11 位置位。
+//! 区块头带全部日志的 Bloom，按地址 / 主题查询时据此跳过不可能命中的区块。
+use crate::primitives::{hex_bytes, sha256, Address, H256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Log {
+    /// 发出事件的合约
+    pub address: Address,
+    pub topics: Vec<H256>,
+    #[serde(with = "hex_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// 单个值或值列表（列表内任意一个匹配即可）。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T: PartialEq> OneOrMany<T> {
+    pub fn contains(&self, v: &T) -> bool {
+        match self {
+            OneOrMany::One(x) => x == v,
+            OneOrMany::Many(xs) => xs.contains(v),
+        }
+    }
+}
+
+impl<T> OneOrMany<T> {
+    pub fn any(&self, mut f: impl FnMut(&T) -> bool) -> bool {
+        match self {
+            OneOrMany::One(x) => f(x),
+            OneOrMany::Many(xs) => xs.iter().any(f),
+        }
+    }
+}
+
+/// 日志过滤：address 为空匹配任意合约；topics 按位置匹配，null 位置为通配。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<OneOrMany<Address>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<Option<OneOrMany<H256>>>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, log: &Log) -> bool {
+        if let Some(addr) = &self.address {
+            if !addr.contains(&log.address) {
+                return false;
+            }
+        }
+        self.topics.iter().enumerate().all(|(i, want)| match want {
+            None => true,
+            Some(want) => log.topics.get(i).is_some_and(|t| want.contains(t)),
+        })
+    }
+}
+
+pub const BLOOM_BYTES: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bloom(pub [u8; BLOOM_BYTES]);
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom([0; BLOOM_BYTES])
+    }
+}
+
+impl std::fmt::Debug for Bloom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Bloom(0x{})", hex::encode(self.0))
+    }
+}
+
+impl Bloom {
+    pub fn from_logs<'a>(logs: impl IntoIterator<Item = &'a Log>) -> Self {
+        let mut bloom = Bloom::default();
+        for log in logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|b| *b == 0)
+    }
+
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.accrue(log.address.as_bytes());
+        for topic in &log.topics {
+            self.accrue(topic.as_bytes());
+        }
+    }
+
+    pub fn accrue(&mut self, input: &[u8]) {
+        for (byte, bit) in bit_positions(input) {
+            self.0[byte] |= bit;
+        }
+    }
+
+    pub fn accrue_bloom(&mut self, other: &Bloom) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a |= b;
+        }
+    }
+
+    /// 可能包含 input（假阳性可能，假阴性不会出现）。
+    pub fn contains_input(&self, input: &[u8]) -> bool {
+        bit_positions(input)
+            .into_iter()
+            .all(|(byte, bit)| self.0[byte] & bit != 0)
+    }
+
+    /// 区块是否可能含有匹配 filter 的日志。
+    pub fn matches_filter(&self, filter: &LogFilter) -> bool {
+        let address_ok = filter
+            .address
+            .as_ref()
+            .is_none_or(|a| a.any(|a| self.contains_input(a.as_bytes())));
+        address_ok
+            && filter
+                .topics
+                .iter()
+                .flatten()
+                .all(|t| t.any(|t| self.contains_input(t.as_bytes())))
+    }
+}
+
+fn bit_positions(input: &[u8]) -> [(usize, u8); 3] {
+    let h = sha256(input);
+    std::array::from_fn(|i| {
+        let bit = (u16::from_be_bytes([h.0[2 * i], h.0[2 * i + 1]]) & 0x7ff) as usize;
+        (BLOOM_BYTES - 1 - bit / 8, 1 << (bit % 8))
+    })
+}
+
+impl Serialize for Bloom {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        hex_bytes::serialize(&self.0, s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bloom {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let raw = hex_bytes::deserialize(d)?;
+        let bytes = raw.try_into().map_err(|v: Vec<u8>| {
+            serde::de::Error::custom(format!(
+                "bloom must be {BLOOM_BYTES} bytes, got {}",
+                v.len()
+            ))
+        })?;
+        Ok(Bloom(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_matches_address_and_positional_topics() {
+        let log = Log {
+            address: Address([1; 20]),
+            topics: vec![H256::from_u128(1), H256::from_u128(2)],
+            data: Vec::new(),
+        };
+        assert!(LogFilter::default().matches(&log));
+        let f: LogFilter = serde_json::from_value(serde_json::json!({
+            "address": Address([1; 20]).to_string(),
+            "topics": [null, [H256::from_u128(3).to_string(), H256::from_u128(2).to_string()]],
+        }))
+        .unwrap();
+        assert!(f.matches(&log));
+        let f = LogFilter {
+            topics: vec![None, None, Some(OneOrMany::One(H256::from_u128(2)))],
+            ..LogFilter::default()
+        };
+        assert!(!f.matches(&log));
+    }
+
+    #[test]
+    fn bloom_covers_address_and_topics() {
+        let log = Log {
+            address: Address([1; 20]),
+            topics: vec![H256::from_u128(7)],
+            data: vec![1, 2, 3],
+        };
+        let bloom = Bloom::from_logs([&log]);
+        assert!(Bloom::default().is_empty());
+        assert!(bloom.contains_input(Address([1; 20]).as_bytes()));
+        assert!(bloom.contains_input(H256::from_u128(7).as_bytes()));
+        assert!(!bloom.contains_input(Address([2; 20]).as_bytes()));
+
+        let mut f = LogFilter {
+            address: Some(OneOrMany::Many(vec![Address([2; 20]), Address([1; 20])])),
+            topics: vec![Some(OneOrMany::One(H256::from_u128(7)))],
+        };
+        assert!(bloom.matches_filter(&f));
+        f.topics.push(Some(OneOrMany::One(H256::from_u128(8))));
+        assert!(!bloom.matches_filter(&f));
+
+        let json = serde_json::to_string(&bloom).unwrap();
+        assert_eq!(serde_json::from_str::<Bloom>(&json).unwrap(), bloom);
+    }
+}
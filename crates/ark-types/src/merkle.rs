@@ -0,0 +1,175 @@
+//! 二叉 Merkle 树（键有序的叶子集合）。
+//!
+//! - 叶子：Sha256(0x00 || len(key) u32be || key || value)
+//! - 内部节点：Sha256(0x01 || left || right)；奇数个节点时末尾节点直接上提（不复制）
+//! - 空集合的根为全零
+//! - 包含证明：自底向上的兄弟节点；被上提的层没有兄弟，由 leaf_count 推出。
+//!   哈希链唯一确定叶子在树中的左右路径，因此下标相邻的两个证明说明叶子相邻
+use crate::primitives::{sha256, H256};
+use serde::{Deserialize, Serialize};
+
+pub fn leaf_hash(key: &[u8], value: &[u8]) -> H256 {
+    let mut buf = Vec::with_capacity(1 + 4 + key.len() + value.len());
+    buf.push(0x00);
+    buf.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    buf.extend_from_slice(key);
+    buf.extend_from_slice(value);
+    sha256(&buf)
+}
+
+pub fn node_hash(left: &H256, right: &H256) -> H256 {
+    let mut buf = [0u8; 65];
+    buf[0] = 0x01;
+    buf[1..33].copy_from_slice(left.as_bytes());
+    buf[33..].copy_from_slice(right.as_bytes());
+    sha256(&buf)
+}
+
+fn parent_level(level: &[H256]) -> Vec<H256> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [l, r] => node_hash(l, r),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// 由叶子哈希（调用方保证顺序）计算根。
+pub fn root_from_leaves(mut level: Vec<H256>) -> H256 {
+    if level.is_empty() {
+        return H256::ZERO;
+    }
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level[0]
+}
+
+/// 由 (key, value) 条目计算根；条目须已按 key 升序排列。
+pub fn root<'a, I>(entries: I) -> H256
+where
+    I: IntoIterator<Item = (&'a [u8], &'a [u8])>,
+{
+    root_from_leaves(entries.into_iter().map(|(k, v)| leaf_hash(k, v)).collect())
+}
+
+/// 单个叶子的包含证明。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<H256>,
+}
+
+impl MerkleProof {
+    /// 由叶子哈希重算根；证明结构不合法（下标越界、兄弟数量不符）时返回 None。
+    pub fn root(&self, leaf: H256) -> Option<H256> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let (mut index, mut width) = (self.index, self.leaf_count);
+        let mut siblings = self.siblings.iter();
+        let mut acc = leaf;
+        while width > 1 {
+            if index % 2 == 1 {
+                acc = node_hash(siblings.next()?, &acc);
+            } else if index + 1 < width {
+                acc = node_hash(&acc, siblings.next()?);
+            }
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none().then_some(acc)
+    }
+}
+
+/// 为 leaves[index] 生成包含证明。
+pub fn prove(leaves: &[H256], index: usize) -> Option<MerkleProof> {
+    MerkleTree::new(leaves.to_vec()).prove(index)
+}
+
+/// 保留全部中间层的树：对同一组叶子反复出证明时不必每次重算（单个证明 O(log n)）。
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// levels[0] 为叶子，最后一层只剩根（叶子为空时只有空的第 0 层）
+    levels: Vec<Vec<H256>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<H256>) -> Self {
+        let mut levels = vec![leaves];
+        while let Some(top) = levels.last().filter(|l| l.len() > 1) {
+            let next = parent_level(top);
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn root(&self) -> H256 {
+        self.levels
+            .last()
+            .and_then(|top| top.first())
+            .copied()
+            .unwrap_or(H256::ZERO)
+    }
+
+    pub fn prove(&self, index: usize) -> Option<MerkleProof> {
+        let leaf_count = self.levels[0].len();
+        if index >= leaf_count {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut i = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(i ^ 1) {
+                siblings.push(*sibling);
+            }
+            i /= 2;
+        }
+        Some(MerkleProof {
+            index: index as u64,
+            leaf_count: leaf_count as u64,
+            siblings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_of_small_sets() {
+        assert_eq!(root(Vec::<(&[u8], &[u8])>::new()), H256::ZERO);
+        let a = leaf_hash(b"a", b"1");
+        let b = leaf_hash(b"b", b"2");
+        let c = leaf_hash(b"c", b"3");
+        assert_eq!(root([(&b"a"[..], &b"1"[..])]), a);
+        let entries: [(&[u8], &[u8]); 3] = [(b"a", b"1"), (b"b", b"2"), (b"c", b"3")];
+        assert_eq!(root(entries), node_hash(&node_hash(&a, &b), &c));
+    }
+
+    #[test]
+    fn proofs_verify_for_every_leaf() {
+        for n in 1..=9u8 {
+            let leaves: Vec<H256> = (0..n).map(|i| leaf_hash(&[i], b"v")).collect();
+            let root = root_from_leaves(leaves.clone());
+            let tree = MerkleTree::new(leaves.clone());
+            assert_eq!(tree.root(), root);
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.prove(i).unwrap();
+                assert_eq!(proof.root(*leaf), Some(root), "n={n} i={i}");
+                let mut wrong = proof.clone();
+                wrong.index = (wrong.index + 1) % wrong.leaf_count;
+                if n > 1 {
+                    assert_ne!(wrong.root(*leaf), Some(root), "n={n} i={i}");
+                }
+            }
+            assert!(prove(&leaves, n as usize).is_none());
+        }
+        assert_eq!(MerkleTree::new(Vec::new()).root(), H256::ZERO);
+        assert!(MerkleTree::new(Vec::new()).prove(0).is_none());
+    }
+}
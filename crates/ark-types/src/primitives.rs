@@ -0,0 +1,236 @@
+//! 基础原语：地址、32 字节哈希/存储字、金额序列化辅助。
+//!
+//! - Address：Sha256(压缩公钥) 前 20 字节，文本形式与钱包一致（Base58Check，版本 0x23）；
+//!   以太坊交易的发送方按以太坊规则派生，见 `eth`
+//! - H256：32 字节哈希或存储字，文本形式为 `0x` 前缀小写十六进制
+//! - Amount：u128，JSON 中以十进制字符串表示（与 genesis.json 保持一致）
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// 金额（最小单位）。
+pub type Amount = u128;
+/// Gas 数量。
+pub type Gas = u64;
+
+/// 地址 Base58Check 版本字节（与 ark-wallet-cli 保持一致）。
+pub const ADDRESS_VERSION: u8 = 0x23;
+
+/// 计算 Sha256 摘要。
+pub fn sha256(data: &[u8]) -> H256 {
+    H256(Sha256::digest(data).into())
+}
+
+/// 20 字节账户地址。
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(pub [u8; 20]);
+
+impl Address {
+    pub const ZERO: Address = Address([0u8; 20]);
+
+    /// 由压缩公钥（33 字节）派生地址。
+    pub fn from_pubkey(pk_compressed: &[u8]) -> Self {
+        let h = Sha256::digest(pk_compressed);
+        let mut out = [0u8; 20];
+        out.copy_from_slice(&h[..20]);
+        Address(out)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::with_capacity(25);
+        buf.push(ADDRESS_VERSION);
+        buf.extend_from_slice(&self.0);
+        let check = Sha256::digest(Sha256::digest(&buf));
+        buf.extend_from_slice(&check[..4]);
+        f.write_str(&bs58::encode(buf).into_string())
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Address({self})")
+    }
+}
+
+impl FromStr for Address {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = bs58::decode(s)
+            .into_vec()
+            .map_err(|e| format!("invalid base58 address: {e}"))?;
+        if raw.len() != 25 {
+            return Err(format!("invalid address length: {}", raw.len()));
+        }
+        let (body, check) = raw.split_at(21);
+        let expect = Sha256::digest(Sha256::digest(body));
+        if check != &expect[..4] {
+            return Err("address checksum mismatch".into());
+        }
+        if body[0] != ADDRESS_VERSION {
+            return Err(format!("unexpected address version: {:#04x}", body[0]));
+        }
+        let mut out = [0u8; 20];
+        out.copy_from_slice(&body[1..]);
+        Ok(Address(out))
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// 32 字节哈希 / 存储字。
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct H256(pub [u8; 32]);
+
+impl H256 {
+    pub const ZERO: H256 = H256([0u8; 32]);
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0u8; 32]
+    }
+
+    /// 以大端方式存放 u128（高 16 字节为 0）。
+    pub fn from_u128(v: u128) -> Self {
+        let mut out = [0u8; 32];
+        out[16..].copy_from_slice(&v.to_be_bytes());
+        H256(out)
+    }
+
+    /// 取低 16 字节作为 u128（高位被截断）。
+    pub fn low_u128(&self) -> u128 {
+        let mut b = [0u8; 16];
+        b.copy_from_slice(&self.0[16..]);
+        u128::from_be_bytes(b)
+    }
+}
+
+impl fmt::Display for H256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for H256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "H256({self})")
+    }
+}
+
+impl FromStr for H256 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = hex::decode(s.strip_prefix("0x").unwrap_or(s))
+            .map_err(|e| format!("invalid hex: {e}"))?;
+        if raw.len() != 32 {
+            return Err(format!("expected 32 bytes, got {}", raw.len()));
+        }
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&raw);
+        Ok(H256(out))
+    }
+}
+
+impl Serialize for H256 {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for H256 {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// `#[serde(with = "amount")]`：u128 与十进制字符串互转。
+pub mod amount {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(v: &u128, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(v)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<u128, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// `#[serde(with = "opt_amount")]`：`Option<u128>` 与十进制字符串 / null 互转。
+pub mod opt_amount {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(v: &Option<u128>, s: S) -> Result<S::Ok, S::Error> {
+        match v {
+            Some(v) => s.collect_str(v),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u128>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|s| s.parse().map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// `#[serde(with = "hex_bytes")]`：字节数组与 `0x` 十六进制字符串互转。
+pub mod hex_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&format!("0x{}", hex::encode(v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(d)?;
+        hex::decode(s.strip_prefix("0x").unwrap_or(&s)).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_text_roundtrip() {
+        let a = Address::from_pubkey(&[2u8; 33]);
+        let s = a.to_string();
+        assert_eq!(s.parse::<Address>().unwrap(), a);
+
+        let mut bad = s.into_bytes();
+        *bad.last_mut().unwrap() ^= 0x01;
+        assert!(String::from_utf8(bad).unwrap().parse::<Address>().is_err());
+    }
+
+    #[test]
+    fn h256_hex_and_u128() {
+        let h = H256::from_u128(0xdead_beef);
+        assert_eq!(h.low_u128(), 0xdead_beef);
+        assert_eq!(h.to_string().parse::<H256>().unwrap(), h);
+    }
+}
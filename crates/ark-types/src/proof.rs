@@ -0,0 +1,313 @@
+//! 轻客户端证明与离线校验：状态条目（账户 / 合约存储）相对状态根，
+//! 交易 / 回执相对区块头的 tx_root / receipts_root。
+//!
+//! 状态叶子按键升序排列，因此“不存在”用相邻两个叶子（或最左 / 最右叶子）证明。
+use crate::account::{account_key, decode_account, storage_key, Account};
+use crate::block::{receipt_leaf, BlockHeader};
+use crate::merkle::{leaf_hash, MerkleProof, MerkleTree};
+use crate::primitives::{hex_bytes, Address, H256};
+use crate::receipt::Receipt;
+use crate::tx::SignedTransaction;
+use serde::{Deserialize, Serialize};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("proof does not match root")]
+    RootMismatch,
+    #[error("proof is for a different key")]
+    KeyMismatch,
+    #[error("neighbouring entries do not bracket the key")]
+    NotBracketing,
+    #[error("malformed proof: {0}")]
+    Malformed(&'static str),
+    #[error("invalid value: {0}")]
+    InvalidValue(String),
+}
+
+/// 带包含证明的状态条目。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenEntry {
+    #[serde(with = "hex_bytes")]
+    pub key: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub value: Vec<u8>,
+    pub proof: MerkleProof,
+}
+
+impl ProvenEntry {
+    fn check(&self, root: &H256) -> Result<(), ProofError> {
+        match self.proof.root(leaf_hash(&self.key, &self.value)) {
+            Some(r) if r == *root => Ok(()),
+            Some(_) => Err(ProofError::RootMismatch),
+            None => Err(ProofError::Malformed("inconsistent merkle path")),
+        }
+    }
+}
+
+/// 状态键的存在或不存在证明。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StateProof {
+    Present(ProvenEntry),
+    Absent {
+        #[serde(with = "hex_bytes")]
+        key: Vec<u8>,
+        /// 小于 key 的最大条目；key 小于全部条目时为空
+        left: Option<ProvenEntry>,
+        /// 大于 key 的最小条目；key 大于全部条目时为空
+        right: Option<ProvenEntry>,
+    },
+}
+
+impl StateProof {
+    pub fn key(&self) -> &[u8] {
+        match self {
+            StateProof::Present(e) => &e.key,
+            StateProof::Absent { key, .. } => key,
+        }
+    }
+
+    /// 相对状态根校验，返回被证明的值（None 表示键不存在）。
+    pub fn verify(&self, state_root: &H256) -> Result<Option<&[u8]>, ProofError> {
+        match self {
+            StateProof::Present(e) => {
+                e.check(state_root)?;
+                Ok(Some(&e.value))
+            }
+            StateProof::Absent { key, left, right } => {
+                match (left, right) {
+                    (None, None) if state_root.is_zero() => {}
+                    (None, None) => return Err(ProofError::RootMismatch),
+                    (Some(l), Some(r)) => {
+                        if l.proof.leaf_count != r.proof.leaf_count
+                            || l.proof.index + 1 != r.proof.index
+                        {
+                            return Err(ProofError::NotBracketing);
+                        }
+                    }
+                    (Some(l), None) => {
+                        if l.proof.index + 1 != l.proof.leaf_count {
+                            return Err(ProofError::NotBracketing);
+                        }
+                    }
+                    (None, Some(r)) => {
+                        if r.proof.index != 0 {
+                            return Err(ProofError::NotBracketing);
+                        }
+                    }
+                }
+                for e in left.iter().chain(right) {
+                    e.check(state_root)?;
+                }
+                let above_left = left.as_ref().is_none_or(|l| l.key < *key);
+                let below_right = right.as_ref().is_none_or(|r| *key < r.key);
+                if !(above_left && below_right) {
+                    return Err(ProofError::NotBracketing);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// 校验账户证明；不存在的账户视为默认值（nonce 0、余额 0）。
+    pub fn verify_account(
+        &self,
+        state_root: &H256,
+        address: &Address,
+    ) -> Result<Account, ProofError> {
+        if self.key() != account_key(address) {
+            return Err(ProofError::KeyMismatch);
+        }
+        match self.verify(state_root)? {
+            Some(raw) => decode_account(raw).map_err(ProofError::InvalidValue),
+            None => Ok(Account::default()),
+        }
+    }
+
+    /// 校验合约存储证明；不存在的槽位值为零。
+    pub fn verify_storage(
+        &self,
+        state_root: &H256,
+        address: &Address,
+        slot: &H256,
+    ) -> Result<H256, ProofError> {
+        if self.key() != storage_key(address, slot) {
+            return Err(ProofError::KeyMismatch);
+        }
+        match self.verify(state_root)? {
+            Some(raw) => raw
+                .try_into()
+                .map(H256)
+                .map_err(|_| ProofError::InvalidValue("storage value must be 32 bytes".into())),
+            None => Ok(H256::ZERO),
+        }
+    }
+}
+
+/// 交易包含证明（相对区块头的 tx_root）。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxProof {
+    pub header: BlockHeader,
+    pub transaction: SignedTransaction,
+    pub proof: MerkleProof,
+}
+
+impl TxProof {
+    /// 校验交易确实位于该区块头所在区块；调用方需另行信任区块头（如通过区块哈希）。
+    pub fn verify(&self) -> Result<(), ProofError> {
+        check_root(&self.proof, self.transaction.hash(), &self.header.tx_root)
+    }
+}
+
+/// 回执包含证明（相对区块头的 receipts_root）。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptProof {
+    pub header: BlockHeader,
+    pub receipt: Receipt,
+    pub proof: MerkleProof,
+}
+
+impl ReceiptProof {
+    pub fn verify(&self) -> Result<(), ProofError> {
+        check_root(
+            &self.proof,
+            receipt_leaf(&self.receipt),
+            &self.header.receipts_root,
+        )
+    }
+}
+
+fn check_root(proof: &MerkleProof, leaf: H256, root: &H256) -> Result<(), ProofError> {
+    match proof.root(leaf) {
+        Some(r) if r == *root => Ok(()),
+        Some(_) => Err(ProofError::RootMismatch),
+        None => Err(ProofError::Malformed("inconsistent merkle path")),
+    }
+}
+
+/// 由按键升序排列的全部状态条目生成 key 的证明（节点侧使用）。
+pub fn prove_state(entries: &[(Vec<u8>, Vec<u8>)], key: &[u8]) -> StateProof {
+    StateTree::new(entries.to_vec()).prove(key)
+}
+
+/// 某一状态的全部条目及其 Merkle 树；节点在两个区块之间缓存它，避免每次证明都重建。
+#[derive(Clone, Debug)]
+pub struct StateTree {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    tree: MerkleTree,
+}
+
+impl StateTree {
+    /// entries 须按键升序排列（即 `State::entries()` 的输出）。
+    pub fn new(entries: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        let tree = MerkleTree::new(entries.iter().map(|(k, v)| leaf_hash(k, v)).collect());
+        Self { entries, tree }
+    }
+
+    pub fn root(&self) -> H256 {
+        self.tree.root()
+    }
+
+    pub fn prove(&self, key: &[u8]) -> StateProof {
+        let entry = |i: usize| ProvenEntry {
+            key: self.entries[i].0.clone(),
+            value: self.entries[i].1.clone(),
+            proof: self.tree.prove(i).expect("index in range"),
+        };
+        match self
+            .entries
+            .binary_search_by(|(k, _)| k.as_slice().cmp(key))
+        {
+            Ok(i) => StateProof::Present(entry(i)),
+            Err(i) => StateProof::Absent {
+                key: key.to_vec(),
+                left: i.checked_sub(1).map(entry),
+                right: (i < self.entries.len()).then(|| entry(i)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::encode_account;
+    use crate::merkle;
+
+    fn entries() -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut out: Vec<(Vec<u8>, Vec<u8>)> = [1u8, 3, 5, 7, 9]
+            .iter()
+            .map(|i| {
+                let acc = Account {
+                    nonce: *i as u64,
+                    balance: *i as u128 * 100,
+                    code_hash: None,
+                };
+                (account_key(&Address([*i; 20])), encode_account(&acc))
+            })
+            .collect();
+        out.push((
+            storage_key(&Address([1; 20]), &H256::from_u128(1)),
+            H256::from_u128(42).0.to_vec(),
+        ));
+        out
+    }
+
+    #[test]
+    fn state_proofs_for_present_and_absent_keys() {
+        let entries = entries();
+        let root = merkle::root(entries.iter().map(|(k, v)| (k.as_slice(), v.as_slice())));
+
+        let addr = Address([5; 20]);
+        let acc = prove_state(&entries, &account_key(&addr))
+            .verify_account(&root, &addr)
+            .unwrap();
+        assert_eq!((acc.nonce, acc.balance), (5, 500));
+
+        // 中间、最左、最右三种不存在情形
+        for missing in [4u8, 0, 200] {
+            let addr = Address([missing; 20]);
+            let proof = prove_state(&entries, &account_key(&addr));
+            assert!(matches!(proof, StateProof::Absent { .. }));
+            assert_eq!(
+                proof.verify_account(&root, &addr).unwrap(),
+                Account::default()
+            );
+        }
+
+        let slot = prove_state(
+            &entries,
+            &storage_key(&Address([1; 20]), &H256::from_u128(1)),
+        );
+        assert_eq!(
+            slot.verify_storage(&root, &Address([1; 20]), &H256::from_u128(1))
+                .unwrap(),
+            H256::from_u128(42)
+        );
+
+        // 把存在的键伪装成不存在：跳过中间的叶子不构成相邻
+        let present = prove_state(&entries, &account_key(&addr));
+        let StateProof::Present(hidden) = present else {
+            panic!()
+        };
+        let forged = StateProof::Absent {
+            key: hidden.key.clone(),
+            left: match prove_state(&entries, &account_key(&Address([3; 20]))) {
+                StateProof::Present(e) => Some(e),
+                _ => None,
+            },
+            right: match prove_state(&entries, &account_key(&Address([7; 20]))) {
+                StateProof::Present(e) => Some(e),
+                _ => None,
+            },
+        };
+        assert_eq!(forged.verify(&root), Err(ProofError::NotBracketing));
+        // 错误的值
+        let mut tampered = prove_state(&entries, &account_key(&addr));
+        if let StateProof::Present(e) = &mut tampered {
+            e.value[10] ^= 1;
+        }
+        assert_eq!(tampered.verify(&root), Err(ProofError::RootMismatch));
+        assert_eq!(prove_state(&[], b"a").verify(&H256::ZERO), Ok(None));
+    }
+}
@@ -0,0 +1,40 @@
+//! 交易回执。
+use crate::log::Log;
+use crate::primitives::{amount, Address, Amount, Gas, H256};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecStatus {
+    Success,
+    /// 合约主动回滚，状态变更撤销，未用 gas 退还
+    Reverted,
+    /// gas 耗尽，状态变更撤销，gas_limit 全部扣除
+    OutOfGas,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub tx_hash: H256,
+    pub status: ExecStatus,
+    /// 扣除退款后实际计费的 gas
+    pub gas_used: Gas,
+    /// 区块内截至本交易的累计 gas
+    pub cumulative_gas_used: Gas,
+    /// 实际成交单价（base fee + 实际小费）
+    #[serde(with = "amount")]
+    pub effective_gas_price: Amount,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// 执行成功时合约发出的事件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<Log>,
+}
+
+impl Receipt {
+    pub fn is_success(&self) -> bool {
+        self.status == ExecStatus::Success
+    }
+}
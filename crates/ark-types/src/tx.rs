@@ -0,0 +1,193 @@
+//! 交易类型。
+//!
+//! - Transaction：待签名内容；签名消息为其 JSON 编码的 Sha256
+//! - Action：转账 / 部署合约（原生 VM 或 EVM）/ 调用合约 / 原生质押 / 链上治理
+//! - SignedTransaction：交易 + 压缩公钥 + 签名；发送方地址由公钥派生
+//! - 以太坊交易（eth_sendRawTransaction 提交的 EIP-1559 交易）同样以 SignedTransaction 表示：
+//!   公钥为 65 字节非压缩格式，签名为 r||s||y_parity，签名与哈希按其 RLP 编码计算（见 [`crate::eth`]）
+use crate::eth::{self, Eip1559Transaction};
+use crate::primitives::{amount, hex_bytes, sha256, Address, Amount, Gas, H256};
+use crate::{ChainId, UpgradePlan};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// 原生转账
+    Transfer { to: Address },
+    /// 部署合约字节码
+    Deploy {
+        #[serde(with = "hex_bytes")]
+        code: Vec<u8>,
+    },
+    /// 调用已部署合约
+    Call {
+        to: Address,
+        #[serde(with = "hex_bytes")]
+        input: Vec<u8>,
+    },
+    /// 部署 EVM 合约：执行初始化代码，返回值作为运行时代码（需开启 evm 特性门）
+    EvmCreate {
+        #[serde(with = "hex_bytes")]
+        code: Vec<u8>,
+    },
+    /// 原生质押操作
+    Staking { op: StakingOp },
+    /// 链上治理：提交提案 / 投票
+    Governance { op: GovernanceOp },
+}
+
+/// 原生质押操作。CreateValidator / Delegate 的金额取交易 value。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StakingOp {
+    /// 以发送方为运营者创建验证者，value 为自抵押金额
+    CreateValidator {
+        #[serde(with = "hex_bytes")]
+        pubkey: Vec<u8>,
+        /// 佣金比例（万分比）
+        commission_bps: u16,
+    },
+    /// 向验证者委托 value
+    Delegate { validator: Address },
+    /// 解除委托，进入解绑期
+    Undelegate {
+        validator: Address,
+        #[serde(with = "amount")]
+        amount: Amount,
+    },
+    /// 提取已到期的解绑金额
+    Withdraw,
+}
+
+/// 链上治理操作。Submit 的押金取交易 value。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GovernanceOp {
+    Submit {
+        title: String,
+        #[serde(default)]
+        description: String,
+        content: ProposalContent,
+    },
+    /// 投票期内可改票，以最后一次为准
+    Vote { proposal: u64, option: VoteOption },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposalContent {
+    /// 在 height 区块末尾修改链参数，从下一个区块起生效。
+    /// 键为 params 中的路径（如 "staking.min_stake"），值按 genesis.json 中的写法
+    ParamChange {
+        height: u64,
+        changes: BTreeMap<String, serde_json::Value>,
+    },
+    /// 通过后调度协议升级，到达计划高度时开启特性门
+    FeatureGate { upgrade: UpgradePlan },
+    /// 纯文本提案，只记录表决结果
+    Text,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+impl Action {
+    /// 附带的数据负载（用于计算 calldata gas）。
+    pub fn payload(&self) -> &[u8] {
+        match self {
+            Action::Transfer { .. } => &[],
+            Action::Deploy { code } | Action::EvmCreate { code } => code,
+            Action::Call { input, .. } => input,
+            Action::Staking { .. } | Action::Governance { .. } => &[],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub chain_id: ChainId,
+    pub nonce: u64,
+    pub action: Action,
+    #[serde(with = "amount")]
+    pub value: Amount,
+    /// 本交易愿意消耗的最大 gas
+    pub gas_limit: Gas,
+    /// 每单位 gas 愿意支付的最高价格（base fee + 小费）
+    #[serde(with = "amount")]
+    pub max_fee_per_gas: Amount,
+    /// 每单位 gas 给出块者的最高小费
+    #[serde(with = "amount")]
+    pub max_priority_fee_per_gas: Amount,
+}
+
+impl Transaction {
+    /// 在给定 base fee 下的实际单价：min(max_fee, base_fee + max_priority_fee)。
+    /// max_fee 低于 base fee 时返回 None（不可打包）。
+    pub fn effective_gas_price(&self, base_fee: Amount) -> Option<Amount> {
+        if self.max_fee_per_gas < base_fee {
+            return None;
+        }
+        Some(
+            self.max_fee_per_gas
+                .min(base_fee.saturating_add(self.max_priority_fee_per_gas)),
+        )
+    }
+
+    /// 在给定 base fee 下每单位 gas 支付给出块者的小费。
+    pub fn effective_tip(&self, base_fee: Amount) -> Option<Amount> {
+        self.effective_gas_price(base_fee).map(|p| p - base_fee)
+    }
+
+    /// 签名消息摘要。
+    pub fn signing_hash(&self) -> H256 {
+        sha256(&serde_json::to_vec(self).expect("transaction serializes"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub tx: Transaction,
+    /// 压缩公钥（33 字节）；以太坊交易为非压缩公钥（65 字节）
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    /// 签名（r||s，64 字节）；以太坊交易为 r||s||y_parity（65 字节）
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl SignedTransaction {
+    /// 是否为以太坊交易（以 65 字节非压缩公钥标识）。
+    pub fn is_eth(&self) -> bool {
+        self.pubkey.len() == 65
+    }
+
+    /// 以太坊交易的 EIP-1559 形式；原生交易或动作无以太坊对应形式时为 None。
+    pub fn eth_transaction(&self) -> Option<Eip1559Transaction> {
+        if !self.is_eth() {
+            return None;
+        }
+        Eip1559Transaction::from_native(&self.tx, &self.signature)
+    }
+
+    pub fn sender(&self) -> Address {
+        if self.is_eth() {
+            return eth::address_from_pubkey(&self.pubkey);
+        }
+        Address::from_pubkey(&self.pubkey)
+    }
+
+    /// 交易哈希（含签名）；以太坊交易为其 RLP 编码的 keccak256，与以太坊工具计算的一致。
+    pub fn hash(&self) -> H256 {
+        if let Some(eth) = self.eth_transaction() {
+            return eth.hash();
+        }
+        sha256(&serde_json::to_vec(self).expect("transaction serializes"))
+    }
+}
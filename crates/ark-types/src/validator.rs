@@ -0,0 +1,47 @@
+//! 验证者集合：共识按 epoch 使用的有序验证者及投票权。
+use crate::primitives::{amount, hex_bytes, Address, Amount};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorInfo {
+    pub operator: Address,
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    /// 投票权（= 抵押总额）
+    #[serde(with = "amount")]
+    pub power: Amount,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSet {
+    pub epoch: u64,
+    /// 按投票权降序、运营者地址升序排列
+    pub validators: Vec<ValidatorInfo>,
+}
+
+impl ValidatorSet {
+    pub fn total_power(&self) -> Amount {
+        self.validators.iter().map(|v| v.power).sum()
+    }
+
+    /// 超过 2/3 投票权所需的最小值。
+    pub fn quorum_power(&self) -> Amount {
+        self.total_power() * 2 / 3 + 1
+    }
+
+    pub fn get(&self, operator: &Address) -> Option<&ValidatorInfo> {
+        self.validators.iter().find(|v| &v.operator == operator)
+    }
+
+    pub fn by_pubkey(&self, pubkey: &[u8]) -> Option<&ValidatorInfo> {
+        self.validators.iter().find(|v| v.pubkey == pubkey)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.validators.len()
+    }
+}